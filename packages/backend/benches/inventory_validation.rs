@@ -0,0 +1,98 @@
+// File Path: benches/inventory_validation.rs
+// Version: 1.0.0
+// Description: Compares full and incremental (changed devices only) schema validation of a
+// 1000-device inventory when one device is edited.
+//
+// Usage Guide:
+// ```
+// cargo bench --bench inventory_validation
+// ```
+// Prints the mean time per write for each mode. The incremental figure includes computing
+// the device diff, as YamlService does before validating.
+//
+// Change Log:
+// - 1.0.0: Initial implementation
+
+#[path = "../src/services/inventory_diff.rs"]
+mod inventory_diff;
+
+use jsonschema::{Draft, JSONSchema};
+use serde_json::{json, Value};
+use std::{hint::black_box, time::Instant};
+
+use inventory_diff::changed_devices;
+
+const LOCATIONS: usize = 10;
+const CATEGORIES: usize = 4;
+const DEVICES_PER_CATEGORY: usize = 25;
+const ITERATIONS: u32 = 200;
+
+fn main() {
+    let schema_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../shared/schemas/inventory.schema.json");
+    let schema: Value = serde_json::from_str(&std::fs::read_to_string(schema_path).unwrap()).unwrap();
+    let schema = JSONSchema::options().with_draft(Draft::Draft7).compile(&schema).unwrap();
+
+    let previous = inventory();
+    let mut updated = previous.clone();
+    updated["locations"]["site-0"]["routers"][0]["ip_address"] = json!("10.255.0.1");
+
+    let (partial, devices) = changed_devices(&previous, &updated).expect("only a device changed");
+    assert_eq!(devices, 1);
+    assert!(schema.is_valid(&updated) && schema.is_valid(&partial));
+
+    let full = time(|| schema.is_valid(black_box(&updated)));
+    let incremental = time(|| {
+        let (partial, _) = changed_devices(black_box(&previous), black_box(&updated)).unwrap();
+        schema.is_valid(&partial)
+    });
+
+    println!(
+        "{} devices, one changed, {} iterations",
+        LOCATIONS * CATEGORIES * DEVICES_PER_CATEGORY,
+        ITERATIONS
+    );
+    println!("full validation:        {:>10.1?} per write", full);
+    println!("incremental validation: {:>10.1?} per write", incremental);
+}
+
+/// Mean time of one call, after a warm-up round
+fn time(mut validate: impl FnMut() -> bool) -> std::time::Duration {
+    for _ in 0..ITERATIONS / 10 {
+        black_box(validate());
+    }
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert!(black_box(validate()));
+    }
+    started.elapsed() / ITERATIONS
+}
+
+/// An inventory of `LOCATIONS` x `CATEGORIES` x `DEVICES_PER_CATEGORY` devices
+fn inventory() -> Value {
+    let categories = ["routers", "switches", "firewalls", "servers"];
+    let locations: serde_json::Map<String, Value> = (0..LOCATIONS)
+        .map(|location| {
+            let categories: serde_json::Map<String, Value> = categories[..CATEGORIES]
+                .iter()
+                .enumerate()
+                .map(|(category_index, category)| {
+                    let devices: Vec<Value> = (0..DEVICES_PER_CATEGORY)
+                        .map(|device| {
+                            json!({
+                                "host_name": format!("{}-{}-{}", category, location, device),
+                                "vendor": "juniper",
+                                "ip_address": format!("10.{}.{}.{}", location, category_index, device + 1),
+                                "platform": "mx",
+                                "username": "admin",
+                                "password_env": "DEVICE_PASSWORD_LAB",
+                            })
+                        })
+                        .collect();
+                    (category.to_string(), Value::Array(devices))
+                })
+                .collect();
+            (format!("site-{}", location), Value::Object(categories))
+        })
+        .collect();
+    json!({ "locations": locations })
+}
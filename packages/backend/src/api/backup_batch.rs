@@ -0,0 +1,262 @@
+// =========================================================================================
+// File Path: src/api/backup_batch.rs
+// Version: 1.2.1
+//
+// Description:
+// Batch backups across several devices. Every device is validated before any backup is
+// launched, so a batch is either started as a whole or refused as a whole.
+//
+// Key Features:
+// - POST /api/backups/batch validates all devices first: name, credentials, duplicates,
+//   reachability and the per-device cooldown
+// - Any invalid device refuses the batch with 400 listing each bad device and why;
+//   nothing is started and no cooldown is consumed
+// - Reachability is a TCP connect to the device's inventory address (hostname when not in
+//   the inventory) on BACKUP_REACHABILITY_PORT (default 22, `0` disables the check)
+//   within BACKUP_REACHABILITY_TIMEOUT_SECS (default 3)
+// - Each device then runs as its own tracked, cancellable `backup` job through the backup
+//   pool; job events carry the shared `batch_id`
+//
+// Usage Guide:
+// POST /api/backups/batch → {
+//   devices: [{ hostname, username?, password? }],
+//   username?, password?,   // overrides for devices without their own; otherwise the
+//                           // inventory and DEVICE_USERNAME / DEVICE_PASSWORD are used
+//   inventory_file?, force?
+// }
+// Returns { batch_id, jobs: [{ device, job_id, queue_position }] }.
+//
+// Change Log:
+// - 1.2.1: Job launch lives in device_jobs
+// - 1.2.0: Job launch moved to backup_jobs, shared with synchronous backups
+// - 1.1.0: Credentials come from the credentials service; request values are overrides
+// - 1.0.0: Initial implementation
+// =========================================================================================
+
+use axum::{extract::State, response::Json};
+use chrono::Utc;
+use futures_util::future::join_all;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::{collections::HashSet, time::Duration};
+use tokio::net::TcpStream;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    config::env_or,
+    api::{device_jobs::launch_backup, backups::validate_device_name, inventory::flatten_inventory},
+    models::{ApiError, ApiResult, DeviceRejection},
+    services::credentials_service::Credentials,
+    AppState,
+};
+
+/// Port probed when BACKUP_REACHABILITY_PORT is unset or invalid
+const DEFAULT_REACHABILITY_PORT: u16 = 22;
+
+/// Probe timeout when BACKUP_REACHABILITY_TIMEOUT_SECS is unset or invalid
+const DEFAULT_REACHABILITY_TIMEOUT_SECS: u64 = 3;
+
+/// Reads the probed port from BACKUP_REACHABILITY_PORT; `0` disables reachability checks
+fn reachability_port() -> u16 {
+    env_or("BACKUP_REACHABILITY_PORT", DEFAULT_REACHABILITY_PORT)
+}
+
+/// Reads the probe timeout from BACKUP_REACHABILITY_TIMEOUT_SECS
+fn reachability_timeout() -> Duration {
+    Duration::from_secs(env_or("BACKUP_REACHABILITY_TIMEOUT_SECS", DEFAULT_REACHABILITY_TIMEOUT_SECS).max(1))
+}
+
+// =========================================================================================
+// SECTION 1: REQUEST STRUCTS
+// =========================================================================================
+
+#[derive(Deserialize)]
+pub struct BatchBackupRequest {
+    pub devices: Vec<BatchDevice>,
+    /// Overrides the login of devices that do not carry their own username
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Overrides the login of devices that do not carry their own password
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub inventory_file: Option<String>,
+    /// Skip the per-device backup cooldown for every device
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Deserialize)]
+pub struct BatchDevice {
+    pub hostname: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// A validated device, ready to be launched
+struct BatchTarget {
+    hostname: String,
+    credentials: Credentials,
+}
+
+// =========================================================================================
+// SECTION 2: HANDLER IMPLEMENTATION
+// =========================================================================================
+
+pub async fn run_backup_batch(
+    State(state): State<AppState>,
+    Json(payload): Json<BatchBackupRequest>,
+) -> ApiResult<Json<Value>> {
+    if payload.devices.is_empty() {
+        return Err(ApiError::BadRequest("devices cannot be empty".to_string()));
+    }
+
+    // Nothing is launched until every device has passed
+    let targets = validate_batch(&state, &payload).await?;
+    let hostnames: Vec<&str> = targets.iter().map(|target| target.hostname.as_str()).collect();
+    state.backup_pool.start_device_backups(&hostnames, payload.force)?;
+
+    let batch_id = Uuid::new_v4().to_string();
+    info!("Backup batch {} validated, starting {} devices", batch_id, targets.len());
+
+    let mut jobs = Vec::with_capacity(targets.len());
+    for target in targets {
+        let job_id = Uuid::new_v4().to_string();
+        let launch = launch_backup(
+            &state,
+            &job_id,
+            &target.hostname,
+            &target.credentials,
+            payload.inventory_file.clone(),
+            Map::from_iter([("batch_id".to_string(), Value::String(batch_id.clone()))]),
+        )
+        .await;
+        jobs.push(serde_json::json!({
+            "device": target.hostname,
+            "job_id": job_id,
+            "queue_position": launch.queue_position,
+        }));
+    }
+
+    Ok(Json(serde_json::json!({
+        "status": "started",
+        "message": format!("Backup batch started for {} devices", jobs.len()),
+        "batch_id": batch_id,
+        "jobs": jobs,
+        "timestamp": Utc::now().to_rfc3339()
+    })))
+}
+
+// =========================================================================================
+// SECTION 3: UPFRONT VALIDATION
+// =========================================================================================
+
+/// Checks every device in the batch, returning all of them or every rejection
+async fn validate_batch(state: &AppState, payload: &BatchBackupRequest) -> ApiResult<Vec<BatchTarget>> {
+    let mut rejected = Vec::new();
+    let mut targets = Vec::new();
+    let mut seen = HashSet::new();
+
+    for device in &payload.devices {
+        let hostname = device.hostname.trim().to_string();
+        let reject = |reason: &str| DeviceRejection {
+            device: hostname.clone(),
+            reason: reason.to_string(),
+        };
+
+        if validate_device_name(&hostname).is_err() {
+            rejected.push(reject("Invalid device name"));
+            continue;
+        }
+        if !seen.insert(hostname.clone()) {
+            rejected.push(reject("Listed more than once"));
+            continue;
+        }
+
+        let resolved = state
+            .credentials_service
+            .resolve(
+                &hostname,
+                device.username.as_deref().or(payload.username.as_deref()),
+                device.password.as_deref().or(payload.password.as_deref()),
+            )
+            .await;
+        match resolved {
+            Ok(credentials) => targets.push(BatchTarget { hostname, credentials }),
+            Err(ApiError::BadRequest(reason)) => rejected.push(reject(&reason)),
+            Err(e) => return Err(e),
+        }
+    }
+
+    let port = reachability_port();
+    if port != 0 && !targets.is_empty() {
+        let addresses = inventory_addresses(state).await;
+        let timeout = reachability_timeout();
+        let probes = targets.iter().map(|target| {
+            let address = addresses
+                .iter()
+                .find(|(host, _)| host == &target.hostname)
+                .map_or_else(|| target.hostname.clone(), |(_, ip)| ip.clone());
+            probe(address, port, timeout)
+        });
+        for (target, result) in targets.iter().zip(join_all(probes).await) {
+            if let Err(reason) = result {
+                rejected.push(DeviceRejection {
+                    device: target.hostname.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    if !rejected.is_empty() {
+        warn!("Backup batch refused: {} of {} devices invalid", rejected.len(), payload.devices.len());
+        return Err(ApiError::InvalidDevices(rejected));
+    }
+    Ok(targets)
+}
+
+/// Hostname and management address of every inventory device
+///
+/// An unreadable inventory is logged and treated as empty, so devices are probed by name.
+async fn inventory_addresses(state: &AppState) -> Vec<(String, String)> {
+    match state.yaml_service.get_yaml_data("inventory", Some("inventories/inventory.yaml")).await {
+        Ok(data) => flatten_inventory(&data)
+            .into_iter()
+            .filter(|device| !device.ip_address.is_empty())
+            .map(|device| (device.host_name, device.ip_address))
+            .collect(),
+        Err(e) => {
+            warn!("Inventory unavailable for batch reachability checks: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Opens and drops a TCP connection to `address:port`
+async fn probe(address: String, port: u16, timeout: Duration) -> Result<(), String> {
+    match tokio::time::timeout(timeout, TcpStream::connect((address.as_str(), port))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(format!("Unreachable at {}:{}: {}", address, port, e)),
+        Err(_) => Err(format!("Unreachable at {}:{}: no answer within {}s", address, port, timeout.as_secs())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn probe_reports_closed_ports() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(probe("127.0.0.1".to_string(), port, Duration::from_secs(1)).await.is_ok());
+
+        drop(listener);
+        let error = probe("127.0.0.1".to_string(), port, Duration::from_secs(1)).await.unwrap_err();
+        assert!(error.starts_with(&format!("Unreachable at 127.0.0.1:{}", port)));
+    }
+}
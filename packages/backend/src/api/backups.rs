@@ -1,6 +1,6 @@
 // =========================================================================================
 // FILE: src/api/backups.rs
-// VERSION: 2.0.0
+// VERSION: 2.10.2
 //
 // DESCRIPTION:
 // API handlers for backup operations. Communicates with Python FastAPI service
@@ -16,14 +16,61 @@
 // - Comprehensive error handling and logging
 // - Consistent API structure with frontend expectations
 // - Proper service discovery using Docker container names
+// - Downloads all backups of a device as one zip archive
+// - Exports a device's inventory entry, backup list and latest backup as one bundle
+// - Cancels a device's in-flight backup by device name
+// - Runs a backup as a tracked job and waits for its result, up to BACKUP_SYNC_MAX_WAIT_SECS
+//
+// CHANGE LOG:
+// - 2.10.2: Backups run through POST /api/backups/devices go to PYTHON_API_URL instead of a fixed host
+// - 2.10.1: A synchronous backup that outlives its wait returns 504 with `job_id` in the body
+// - 2.10.0: Added POST /api/backups/run-sync, which waits for the backup job and returns 504 past the max wait
+// - 2.9.0: Added DELETE /api/backups/device/:device_name/job to cancel a backup by device name
+// - 2.8.0: Backup credentials are resolved by the credentials service (request, inventory, environment)
+// - 2.7.0: Device backup listing accepts ?from=&to=&sort=asc|desc, using the timestamp in each file name
+// - 2.6.1: Backup file retrieval returns 501 Not Implemented instead of a placeholder success
+// - 2.6.0: Backups are rejected with 429 within the per-device cooldown unless `force` is set
+// - 2.5.0: Device listing goes through the device list circuit breaker and may be served stale
+// - 2.4.0: Added GET /api/backups/device/:device_name/bundle
+// - 2.3.0: Device listing is parsed into typed devices; unexpected upstream shapes return 502
+// - 2.2.0: Responses carry typed BackupFiles payloads instead of raw JSON
+// - 2.1.0: Added GET /api/backups/device/:device_name/archive
 // =========================================================================================
 
-use axum::{extract::{State, Path}, Json};
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use reqwest::Client;
-use serde_json::json;
+use serde::Deserialize;
+use serde_json::{json, Map};
+use std::{
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::PathBuf,
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tracing::{error, info, warn};
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
 
-use crate::{models::{ApiError, ApiResult, BackupRequest, BackupResponse}, AppState};
+use crate::{
+    config::env_or,
+    api::{
+        device_jobs::{launch_backup, python_api_endpoint, BACKUP_PATH},
+        inventory::flatten_inventory,
+    },
+    models::{
+        websocket::JobEventPayload, ApiError, ApiResult, BackupFileContent, BackupFileInfo, BackupFiles, BackupRequest,
+        BackupResponse, DeviceBundle,
+    },
+    AppState,
+};
 
 // =============================================================================
 // SECTION 1: MAIN BACKUP HANDLER
@@ -40,7 +87,7 @@ pub async fn backups_handler(
     // If no request body, handle as GET request (list devices)
     if request.is_none() {
         info!("Handling GET request for device listing");
-        return list_devices().await;
+        return list_devices(&state).await;
     }
     
     // If request body exists, handle as POST request (execute backup)
@@ -55,38 +102,32 @@ pub async fn backups_handler(
 // Calls Python API to retrieve list of devices with backups
 
 /// Retrieves list of devices from Python API service
-async fn list_devices() -> ApiResult<Json<BackupResponse>> {
-    info!("Calling Python API to list devices");
-    
-    let client = Client::new();
-    
-    let response = client.get("http://python_runner:8000/api/backups/devices")
-        .send()
-        .await
-        .map_err(|e| {
-            error!("Failed to connect to Python API: {}", e);
-            ApiError::InternalError(format!("Python API unavailable: {}", e))
-        })?;
+///
+/// Goes through the device list circuit breaker: while the Python API is
+/// down, the last known list is returned with `stale: true`.
+async fn list_devices(state: &AppState) -> ApiResult<Json<BackupResponse>> {
+    let listing = state.device_list_service.list().await?;
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        error!("Python API returned error: {} - {}", status, body);
-        return Err(ApiError::InternalError(format!("Python API error: {}", status)));
+    if listing.stale {
+        warn!(
+            "Serving cached device list from {} ({} devices)",
+            listing.fetched_at.to_rfc3339(),
+            listing.devices.count
+        );
+    } else {
+        info!("Successfully retrieved device list ({} devices)", listing.devices.count);
     }
 
-    let devices_data: serde_json::Value = response.json().await.map_err(|e| {
-        error!("Failed to parse Python API response: {}", e);
-        ApiError::InternalError("Invalid response from Python API".to_string())
-    })?;
-
-    info!("Successfully retrieved device list");
-    
     Ok(Json(BackupResponse {
         status: "success".to_string(),
-        message: "Devices listed successfully".to_string(),
+        message: if listing.stale {
+            format!("Python API unavailable; showing devices as of {}", listing.fetched_at.to_rfc3339())
+        } else {
+            "Devices listed successfully".to_string()
+        },
         logs: None,
-        files: Some(devices_data),
+        files: Some(BackupFiles::DeviceList(listing.devices)),
+        stale: listing.stale,
     }))
 }
 
@@ -97,15 +138,21 @@ async fn list_devices() -> ApiResult<Json<BackupResponse>> {
 
 /// Executes backup operation via Python API service
 async fn execute_backup(
-    State(_state): State<AppState>,
-    Json(backup_request): Json<BackupRequest>,
+    State(state): State<AppState>,
+    Json(mut backup_request): Json<BackupRequest>,
 ) -> ApiResult<Json<BackupResponse>> {
-    info!("Starting backup operation for host: {}", backup_request.hostname);
+    let credentials = state
+        .credentials_service
+        .resolve(&backup_request.hostname, backup_request.username.as_deref(), backup_request.password.as_deref())
+        .await?;
+    state.backup_pool.start_device_backup(&backup_request.hostname, backup_request.force)?;
+    info!("Starting backup operation for host: {} ({})", backup_request.hostname, credentials);
+    backup_request.username = Some(credentials.username.clone());
+    backup_request.password = Some(credentials.password().to_string());
     
     let client = Client::new();
     
-    // Use port 8000 (internal container port)
-    let response = client.post("http://python_runner:8000/api/backups/devices")
+    let response = client.post(python_api_endpoint(&state, BACKUP_PATH))
         .json(&backup_request)
         .send()
         .await
@@ -132,7 +179,8 @@ async fn execute_backup(
         status: "success".to_string(),
         message: "Backup completed successfully".to_string(),
         logs: None,
-        files: Some(result),
+        files: Some(BackupFiles::parse(result, BackupFiles::BackupResult)),
+        stale: false,
     }))
 }
 // =============================================================================
@@ -140,16 +188,42 @@ async fn execute_backup(
 // =============================================================================
 // Calls Python API to list backup files for a specific device
 
+/// Date range and ordering for a device's backup listing
+#[derive(Debug, Deserialize)]
+pub struct BackupListQuery {
+    /// Earliest backup to include: RFC 3339 timestamp or YYYY-MM-DD
+    pub from: Option<String>,
+    /// Latest backup to include: RFC 3339 timestamp or YYYY-MM-DD (whole day)
+    pub to: Option<String>,
+    /// "asc" or "desc" by backup timestamp; upstream order when omitted
+    pub sort: Option<String>,
+}
+
 /// Retrieves list of backup files for a specific device from Python API
+///
+/// Backup dates come from the `YYYYMMDD_HHMMSS` timestamp in each file name. With
+/// `from`/`to`, files without a timestamp are left out; when sorting they go last.
 pub async fn list_device_backups(
     Path(device_name): Path<String>,
+    Query(query): Query<BackupListQuery>,
 ) -> ApiResult<Json<BackupResponse>> {
     info!("Listing backups for device: {}", device_name);
+
+    let from = query.from.as_deref().map(|value| parse_range_bound(value, false)).transpose()?;
+    let to = query.to.as_deref().map(|value| parse_range_bound(value, true)).transpose()?;
+    let descending = match query.sort.as_deref() {
+        None => None,
+        Some("asc") => Some(false),
+        Some("desc") => Some(true),
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!("Invalid sort '{}': expected asc or desc", other)));
+        }
+    };
     
     let client = Client::new();
     
     // Make HTTP request to Python service
-    let response = client.get(&format!("http://python_runner:8001/api/backups/device/{}", device_name))
+    let response = client.get(format!("http://python_runner:8001/api/backups/device/{}", device_name))
         .send()
         .await
         .map_err(|e| {
@@ -166,45 +240,572 @@ pub async fn list_device_backups(
     }
 
     // Parse successful response
-    let backups_data: serde_json::Value = response.json().await.map_err(|e| {
+    let mut backups_data: serde_json::Value = response.json().await.map_err(|e| {
         error!("Failed to parse backups response: {}", e);
         ApiError::InternalError("Invalid response from Python API".to_string())
     })?;
 
     info!("Successfully listed backups for device: {}", device_name);
+
+    // Upstream may omit the device name; it is known from the path
+    if let Some(obj) = backups_data.as_object_mut() {
+        obj.entry("device").or_insert_with(|| json!(device_name));
+    }
+
+    let mut files = BackupFiles::parse(backups_data, BackupFiles::BackupFileList);
+    match &mut files {
+        BackupFiles::BackupFileList(list) => {
+            if from.is_some() || to.is_some() {
+                list.files.retain(|name| {
+                    backup_timestamp(name).is_some_and(|at| {
+                        from.is_none_or(|from| at >= from) && to.is_none_or(|to| at <= to)
+                    })
+                });
+            }
+            if let Some(descending) = descending {
+                // Files without a timestamp sort after all dated ones
+                list.files.sort_by_cached_key(|name| {
+                    let secs = backup_timestamp(name).map(|at| at.timestamp());
+                    (secs.is_none(), secs.map(|secs| if descending { -secs } else { secs }))
+                });
+            }
+            list.count = list.files.len();
+        }
+        _ if from.is_some() || to.is_some() || descending.is_some() => {
+            warn!("Unexpected backup list shape for {}; returning it unfiltered", device_name);
+        }
+        _ => {}
+    }
     
     // Return formatted response
     Ok(Json(BackupResponse {
         status: "success".to_string(),
         message: "Backups listed successfully".to_string(),
         logs: None,
-        files: Some(backups_data),
+        files: Some(files),
+        stale: false,
     }))
 }
 
+/// Parses a `from`/`to` bound; a bare date covers the whole day
+fn parse_range_bound(value: &str, end_of_day: bool) -> ApiResult<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| if end_of_day { date.and_hms_opt(23, 59, 59) } else { date.and_hms_opt(0, 0, 0) })
+        .map(|at| at.and_utc())
+        .ok_or_else(|| ApiError::BadRequest(format!(
+            "Invalid date '{}': expected RFC 3339 or YYYY-MM-DD",
+            value
+        )))
+}
+
+/// Timestamp embedded in a backup file name as `YYYYMMDD_HHMMSS`
+/// (e.g. `20250914_101500_router1_config.conf`), taken as UTC
+fn backup_timestamp(name: &str) -> Option<DateTime<Utc>> {
+    name.as_bytes()
+        .windows(15)
+        .position(|window| {
+            window[8] == b'_'
+                && window[..8].iter().chain(&window[9..]).all(u8::is_ascii_digit)
+        })
+        .and_then(|start| NaiveDateTime::parse_from_str(&name[start..start + 15], "%Y%m%d_%H%M%S").ok())
+        .map(|at| at.and_utc())
+}
+
 // =============================================================================
 // SECTION 5: BACKUP FILE CONTENT RETRIEVAL
 // =============================================================================
 // Calls Python API to get content of a specific backup file
 
 /// Retrieves content of a specific backup file
+///
+/// Not available until the Python API exposes backup file contents; answers 501.
 pub async fn get_backup_file(
     Path((device_name, filename)): Path<(String, String)>,
 ) -> ApiResult<Json<BackupResponse>> {
     info!("Retrieving backup file: {}/{}", device_name, filename);
-    
-    // Note: This endpoint would need to be implemented in Python API
-    // For now, return a placeholder response
-    warn!("Backup file content endpoint not fully implemented");
-    
-    Ok(Json(BackupResponse {
-        status: "success".to_string(),
-        message: "Backup file retrieval not implemented".to_string(),
-        logs: None,
-        files: Some(json!({
-            "device": device_name,
-            "filename": filename,
-            "content": "Backup file content retrieval requires Python API implementation"
-        })),
+    warn!("Backup file content endpoint is not implemented");
+
+    Err(ApiError::NotImplemented(
+        "Backup file retrieval requires a Python API endpoint that does not exist yet".to_string(),
+    ))
+}
+
+// =============================================================================
+// SECTION 6: DEVICE BACKUP ARCHIVE
+// =============================================================================
+// Streams every backup file of a device as one zip archive
+
+/// Directory holding one sub-directory of backup files per device
+const BACKUPS_DIR: &str = "/shared/data/backups";
+
+/// Number of zip chunks buffered between the writer task and the response body
+const ARCHIVE_CHANNEL_CAPACITY: usize = 16;
+
+/// Streams all backup files for a device as `<device>_backups.zip`
+///
+/// The zip writer needs a seekable sink, so a blocking task builds the archive in a
+/// temporary file and then copies it into a bounded channel feeding the response body.
+/// Memory use stays flat regardless of archive size, but the temporary directory needs
+/// room for the whole archive and no bytes are sent until it is complete.
+/// Returns 404 when the device has no backup files.
+pub async fn download_device_archive(
+    Path(device_name): Path<String>,
+) -> ApiResult<Response> {
+    info!("Building backup archive for device: {}", device_name);
+    validate_device_name(&device_name)?;
+
+    let device_dir = PathBuf::from(BACKUPS_DIR).join(&device_name);
+    let files = list_backup_files(&device_dir).await;
+    if files.is_empty() {
+        return Err(ApiError::NotFound(format!("No backups found for device '{}'", device_name)));
+    }
+
+    let (tx, rx) = mpsc::channel::<io::Result<Bytes>>(ARCHIVE_CHANNEL_CAPACITY);
+    let archive_device = device_name.clone();
+    tokio::task::spawn_blocking(move || {
+        let error_tx = tx.clone();
+        if let Err(e) = stream_archive(&files, ChannelWriter { tx }) {
+            error!("Failed to build backup archive for {}: {}", archive_device, e);
+            let _ = error_tx.blocking_send(Err(e));
+        }
+    });
+
+    info!("Streaming backup archive for device: {}", device_name);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}_backups.zip\"", device_name),
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(rx)),
+    ).into_response())
+}
+
+/// Rejects device names that could leave the backups directory
+pub(crate) fn validate_device_name(device_name: &str) -> ApiResult<()> {
+    if device_name.is_empty() || device_name.contains(['/', '\\']) || device_name.contains("..") {
+        return Err(ApiError::BadRequest(format!("Invalid device name: {}", device_name)));
+    }
+    Ok(())
+}
+
+/// Lists regular files in a device backup directory, sorted by name
+async fn list_backup_files(device_dir: &std::path::Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(device_dir).await else {
+        return files;
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if entry.file_type().await.map(|t| t.is_file()).unwrap_or(false) {
+            files.push(entry.path());
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Builds the archive in a temporary file and copies it to `out`; the file is always removed
+fn stream_archive(files: &[PathBuf], out: ChannelWriter) -> io::Result<()> {
+    let path = std::env::temp_dir().join(format!("backup-archive-{}.zip", uuid::Uuid::new_v4()));
+    let result = (|| {
+        let file = std::fs::File::options().read(true).write(true).create_new(true).open(&path)?;
+        let mut file = write_archive(files, BufWriter::new(file))?
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        let mut out = BufWriter::new(out);
+        io::copy(&mut file, &mut out)?;
+        out.flush()
+    })();
+
+    if let Err(e) = std::fs::remove_file(&path) {
+        warn!("Failed to remove temporary archive {}: {}", path.display(), e);
+    }
+    result
+}
+
+/// Writes files into a zip on a seekable sink and returns the sink
+fn write_archive<W: Write + Seek>(files: &[PathBuf], writer: W) -> io::Result<W> {
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for path in files {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        zip.start_file(name, options).map_err(io::Error::other)?;
+        let mut file = std::fs::File::open(path)?;
+        io::copy(&mut file, &mut zip)?;
+    }
+
+    zip.finish().map_err(io::Error::other)
+}
+
+/// Blocking writer forwarding zip chunks to the response body channel
+struct ChannelWriter {
+    tx: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.tx
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "archive download aborted"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// =============================================================================
+// SECTION 7: DEVICE BUNDLE EXPORT
+// =============================================================================
+// Collects a device's inventory entry and backups into one response
+
+/// Returns the device's inventory entry, backup files with timestamps and the
+/// content of its latest backup
+///
+/// Returns 404 when the device is neither in the inventory nor has backups.
+pub async fn get_device_bundle(
+    State(state): State<AppState>,
+    Path(device_name): Path<String>,
+) -> ApiResult<Json<DeviceBundle>> {
+    info!("Building backup bundle for device: {}", device_name);
+    validate_device_name(&device_name)?;
+
+    let inventory_data = state
+        .yaml_service
+        .get_yaml_data("inventory", Some("inventories/inventory.yaml"))
+        .await?;
+    let inventory = flatten_inventory(&inventory_data)
+        .into_iter()
+        .find(|device| device.host_name == device_name);
+
+    let mut backups = Vec::new();
+    for path in list_backup_files(&PathBuf::from(BACKUPS_DIR).join(&device_name)).await {
+        let metadata = tokio::fs::metadata(&path).await?;
+        backups.push(BackupFileInfo {
+            name: path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string(),
+            size: metadata.len(),
+            modified: metadata.modified().ok().map(chrono::DateTime::<chrono::Utc>::from),
+        });
+    }
+    backups.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.name.cmp(&b.name)));
+
+    if inventory.is_none() && backups.is_empty() {
+        return Err(ApiError::NotFound(format!("Device '{}' not found", device_name)));
+    }
+
+    let latest_backup = match backups.last() {
+        Some(latest) => {
+            let bytes = tokio::fs::read(PathBuf::from(BACKUPS_DIR).join(&device_name).join(&latest.name)).await?;
+            Some(BackupFileContent {
+                name: latest.name.clone(),
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+            })
+        }
+        None => None,
+    };
+
+    info!("Bundle for {}: {} backup files", device_name, backups.len());
+
+    Ok(Json(DeviceBundle {
+        device: device_name,
+        inventory,
+        backups,
+        latest_backup,
+        generated_at: chrono::Utc::now(),
     }))
 }
+
+// =============================================================================
+// SECTION 8: BACKUP CANCELLATION
+// =============================================================================
+// Cancels an in-flight backup job looked up by device instead of job id
+
+/// Cancels the in-flight backup job of a device
+///
+/// Returns the cancelled job id, or 404 when no backup is running for the device.
+pub async fn cancel_device_backup(
+    State(state): State<AppState>,
+    Path(device_name): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    validate_device_name(&device_name)?;
+    let job = state.job_service.cancel_device_job(&device_name, "backup").await?;
+    info!("Backup job {} for {} cancelled by device name", job.job_id, job.device);
+
+    let event = JobEventPayload {
+        job_id: job.job_id.clone(),
+        device: job.device.clone(),
+        job_type: job.job_type,
+        event_type: "OPERATION_COMPLETE".to_string(),
+        status: "cancelled".to_string(),
+        timestamp: Utc::now(),
+        data: json!({ "message": "Backup cancelled by operator" }),
+        error: None,
+        trace_id: None,
+    };
+    if let Err(e) = state.websocket_service.broadcast_job_event(event).await {
+        warn!("Failed to broadcast cancellation for job {}: {}", job.job_id, e);
+    }
+
+    Ok(Json(json!({
+        "status": "cancelled",
+        "job_id": job.job_id,
+        "device": job.device,
+        "started_at": job.started_at.to_rfc3339(),
+    })))
+}
+
+// =============================================================================
+// SECTION 9: SYNCHRONOUS BACKUP
+// =============================================================================
+// Runs a backup as a normal tracked job, but answers only once the job has ended
+
+/// Longest a synchronous backup waits when BACKUP_SYNC_MAX_WAIT_SECS is unset or invalid
+const DEFAULT_SYNC_MAX_WAIT_SECS: u64 = 300;
+
+/// Reads the synchronous wait ceiling from BACKUP_SYNC_MAX_WAIT_SECS
+fn sync_max_wait() -> Duration {
+    Duration::from_secs(env_or("BACKUP_SYNC_MAX_WAIT_SECS", DEFAULT_SYNC_MAX_WAIT_SECS).max(1))
+}
+
+#[derive(Deserialize)]
+pub struct SyncBackupRequest {
+    pub hostname: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub inventory_file: Option<String>,
+    /// Skip the per-device backup cooldown
+    #[serde(default)]
+    pub force: bool,
+    /// Shorter wait than the server maximum; larger values are capped
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Runs a device backup and blocks until it finishes or the wait runs out
+///
+/// Job events are broadcast as for any backup job. Past the wait the job keeps running and
+/// 504 is returned with its id, so the caller can follow it like an asynchronous backup.
+pub async fn run_backup_sync(
+    State(state): State<AppState>,
+    Json(payload): Json<SyncBackupRequest>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let hostname = payload.hostname.trim().to_string();
+    validate_device_name(&hostname)?;
+    let credentials = state
+        .credentials_service
+        .resolve(&hostname, payload.username.as_deref(), payload.password.as_deref())
+        .await?;
+    state.backup_pool.start_device_backup(&hostname, payload.force)?;
+
+    let max_wait = sync_max_wait();
+    let wait = payload
+        .timeout_secs
+        .filter(|secs| *secs > 0)
+        .map_or(max_wait, |secs| Duration::from_secs(secs).min(max_wait));
+
+    let job_id = Uuid::new_v4().to_string();
+    info!("Starting synchronous backup job {} for {} ({}), waiting up to {}s", job_id, hostname, credentials, wait.as_secs());
+    let launch = launch_backup(&state, &job_id, &hostname, &credentials, payload.inventory_file, Map::new()).await;
+
+    match tokio::time::timeout(wait, launch.outcome).await {
+        Ok(Ok(Ok(result))) => Ok(Json(json!({
+            "status": "completed",
+            "job_id": job_id,
+            "device": hostname,
+            "result": result,
+            "timestamp": Utc::now().to_rfc3339(),
+        }))),
+        Ok(Ok(Err(message))) => Err(ApiError::UpstreamError(format!("Backup job {} failed: {}", job_id, message))),
+        Ok(Err(_)) => Err(ApiError::Conflict(format!(
+            "Backup job {} ended without a result (cancelled or crashed)",
+            job_id
+        ))),
+        Err(_) => {
+            warn!("Synchronous backup job {} for {} still running after {}s", job_id, hostname, wait.as_secs());
+            Err(ApiError::GatewayTimeout {
+                message: format!("Backup job {} did not finish within {}s and is still running", job_id, wait.as_secs()),
+                job_id,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use std::sync::Arc;
+
+    #[test]
+    fn backup_timestamps_come_from_file_names() {
+        let at = backup_timestamp("20250914_101500_router1_config.conf").unwrap();
+        assert_eq!(at.to_rfc3339(), "2025-09-14T10:15:00+00:00");
+        assert_eq!(
+            backup_timestamp("router1-20250914_101500.conf"),
+            Some(at)
+        );
+        assert_eq!(backup_timestamp("router1_config.conf"), None);
+        assert_eq!(backup_timestamp("20251399_101500_router1.conf"), None);
+    }
+
+    #[test]
+    fn archives_read_back_with_every_file() {
+        let dir = std::env::temp_dir().join(format!("backup-archive-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let files = vec![dir.join("r1_a.conf"), dir.join("r1_b.conf")];
+        std::fs::write(&files[0], "hostname r1\n").unwrap();
+        std::fs::write(&files[1], "interface ge-0/0/0\n").unwrap();
+
+        let archive = write_archive(&files, io::Cursor::new(Vec::new())).unwrap();
+        let mut archive = zip::ZipArchive::new(archive).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut content = String::new();
+        io::Read::read_to_string(&mut archive.by_name("r1_b.conf").unwrap(), &mut content).unwrap();
+        assert_eq!(content, "interface ge-0/0/0\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn date_bounds_cover_whole_days() {
+        assert_eq!(parse_range_bound("2025-09-14", false).unwrap().to_rfc3339(), "2025-09-14T00:00:00+00:00");
+        assert_eq!(parse_range_bound("2025-09-14", true).unwrap().to_rfc3339(), "2025-09-14T23:59:59+00:00");
+        assert!(matches!(parse_range_bound("yesterday", false), Err(ApiError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn backup_file_contents_answer_501() {
+        let result = get_backup_file(Path(("r1".to_string(), "r1_config.conf".to_string()))).await;
+        let error = result.unwrap_err();
+        assert!(matches!(error, ApiError::NotImplemented(_)));
+        assert_eq!(error.into_response().status(), StatusCode::NOT_IMPLEMENTED);
+    }
+
+    /// Stand-in Python API backup endpoint: "ok" answers, "fail" returns 500, anything else hangs
+    fn stub_python_api(mode: Arc<std::sync::Mutex<&'static str>>) -> axum::Router {
+        axum::Router::new().route(
+            "/api/backups/devices",
+            axum::routing::post(move || {
+                let mode = *mode.lock().unwrap();
+                async move {
+                    match mode {
+                        "ok" => Json(json!({ "status": "success", "file": "r1.conf" })).into_response(),
+                        "fail" => (StatusCode::INTERNAL_SERVER_ERROR, "device unreachable").into_response(),
+                        _ => std::future::pending().await,
+                    }
+                }
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn backups_run_against_the_configured_python_api() {
+        let mut app = crate::test_support::TestApp::new().await;
+        let mode = Arc::new(std::sync::Mutex::new("ok"));
+        app.stub_python_api(stub_python_api(Arc::clone(&mode))).await;
+        let request = || BackupRequest {
+            hostname: "r1".to_string(),
+            username: Some("netops".to_string()),
+            password: Some("secret".to_string()),
+            inventory_file: None,
+            force: false,
+        };
+
+        let Json(response) = backups_handler(State(app.state.clone()), Some(Json(request()))).await.unwrap();
+        assert_eq!(response.status, "success");
+
+        *mode.lock().unwrap() = "fail";
+        let failed = backups_handler(State(app.state.clone()), Some(Json(request()))).await;
+        assert!(matches!(failed, Err(ApiError::InternalError(_))));
+    }
+
+    #[tokio::test]
+    async fn sync_backups_answer_with_the_job_outcome() {
+        let mut app = crate::test_support::TestApp::new().await;
+        let mode = Arc::new(std::sync::Mutex::new("ok"));
+        app.stub_python_api(stub_python_api(Arc::clone(&mode))).await;
+        let request = || SyncBackupRequest {
+            hostname: "r1".to_string(),
+            username: Some("netops".to_string()),
+            password: Some("secret".to_string()),
+            inventory_file: None,
+            force: false,
+            timeout_secs: Some(1),
+        };
+
+        let Json(completed) = run_backup_sync(State(app.state.clone()), Json(request())).await.unwrap();
+        assert_eq!(completed["status"], "completed");
+        assert_eq!(completed["result"]["file"], "r1.conf");
+
+        *mode.lock().unwrap() = "fail";
+        let failed = run_backup_sync(State(app.state.clone()), Json(request())).await;
+        assert!(matches!(failed, Err(ApiError::UpstreamError(ref message)) if message.contains("device unreachable")));
+
+        // Past the wait the job keeps running, and the 504 names it
+        *mode.lock().unwrap() = "hang";
+        let timed_out = run_backup_sync(State(app.state.clone()), Json(request())).await.unwrap_err();
+        let response = timed_out.into_response();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        let job_id = body["job_id"].as_str().unwrap();
+        let running = app.state.job_service.list_jobs().await;
+        assert!(running.iter().any(|job| job.job_id == job_id));
+        app.state.job_service.cancel_device_job("r1", "backup").await.unwrap();
+
+        // A job cancelled while the caller waits has no result to return
+        let waiting = tokio::spawn(run_backup_sync(State(app.state.clone()), Json(request())));
+        let cancelled = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                if app.state.job_service.cancel_device_job("r1", "backup").await.is_ok() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        assert!(cancelled.is_ok());
+        let response = waiting.await.unwrap().unwrap_err().into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn bundles_need_an_inventory_entry_or_backups() {
+        let app = crate::test_support::TestApp::new().await;
+        app.write_data(
+            "inventories/inventory.yaml",
+            "locations:\n  LAB:\n    routers:\n      - { host_name: lab-r1, vendor: juniper, ip_address: 10.0.0.1 }\n",
+        )
+        .await;
+        let bundle = |device: &str| get_device_bundle(State(app.state.clone()), Path(device.to_string()));
+
+        let Json(listed) = bundle("lab-r1").await.unwrap();
+        let inventory = listed.inventory.expect("inventory entry");
+        assert_eq!((inventory.site.as_str(), inventory.ip_address.as_str()), ("LAB", "10.0.0.1"));
+        assert!(listed.backups.is_empty() && listed.latest_backup.is_none());
+
+        assert!(matches!(bundle("lab-r9").await, Err(ApiError::NotFound(_))));
+        assert!(matches!(bundle("..").await, Err(ApiError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,323 @@
+// =========================================================================================
+// File Path: src/api/device_jobs.rs
+// Version: 2.1.0
+//
+// Description:
+// Launches a device operation forwarded to the Python API as a tracked background job.
+// Shared by every backup entry point and by upgrades, so they all queue, report and
+// cancel the same way.
+//
+// Key Features:
+// - Backups take a backup pool slot or queue behind the running backups; other jobs start at once
+// - Registers the job with the job service so it can be listed and cancelled
+// - Broadcasts OPERATION_START / OPERATION_QUEUED and OPERATION_COMPLETE job events for the job type
+// - Holds a caller's guard (e.g. a device lock) until the job ends
+// - A panicking job task is reported on the `errors` topic and fails the job
+// - Hands the final result to the caller through a oneshot channel; the channel closes
+//   without a value when the job is cancelled or its task panics
+//
+// Usage Guide:
+// ```
+// let launch = launch_backup(&state, &job_id, &hostname, &credentials, inventory_file, Map::new()).await;
+// match launch.outcome.await {
+//     Ok(Ok(result)) => { /* backup finished */ }
+//     Ok(Err(message)) => { /* backup failed */ }
+//     Err(_) => { /* cancelled or crashed */ }
+// }
+// ```
+// Other operations describe themselves with a DeviceJob and call launch_device_job().
+//
+// Change Log:
+// - 2.1.0: Python API endpoints are resolved against PYTHON_API_URL
+// - 2.0.0: Generalized from backup_jobs to any job type and Python API endpoint; used by upgrades
+//          and the WebSocket backup endpoint
+// - 1.0.0: Initial implementation, moved out of backup_batch
+// =========================================================================================
+
+use chrono::Utc;
+use reqwest::Client;
+use serde_json::{Map, Value};
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::oneshot, task};
+use tracing::{error, info, warn};
+
+use crate::{
+    models::websocket::JobEventPayload,
+    services::{credentials_service::Credentials, WebSocketService},
+    AppState,
+};
+
+/// Python API path that performs a device backup
+pub const BACKUP_PATH: &str = "/api/backups/devices";
+
+/// Per-device backup timeout
+const BACKUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+// =========================================================================================
+// SECTION 1: TYPE DEFINITIONS
+// =========================================================================================
+
+/// A device operation to run through the Python API
+pub struct DeviceJob {
+    pub job_id: String,
+    /// Job type in job events and the job registry, e.g. "backup" or "upgrade"
+    pub job_type: &'static str,
+    /// Device named in job events
+    pub device: String,
+    /// Python API endpoint the request is posted to
+    pub url: String,
+    pub request: Value,
+    pub timeout: Duration,
+    /// Waits for a backup pool slot before calling the Python API
+    pub pooled: bool,
+    /// Fields added to the data of every job event, e.g. `batch_id`
+    pub context: Map<String, Value>,
+}
+
+/// A launched job
+pub struct JobLaunch {
+    /// Position in the backup queue when no slot was free
+    pub queue_position: Option<usize>,
+    /// The Python API result or failure message, sent once the job ends
+    pub outcome: oneshot::Receiver<Result<Value, String>>,
+}
+
+// =========================================================================================
+// SECTION 2: JOB LAUNCH
+// =========================================================================================
+
+/// Starts one device's backup job; the device must already hold its backup cooldown slot
+pub async fn launch_backup(
+    state: &AppState,
+    job_id: &str,
+    hostname: &str,
+    credentials: &Credentials,
+    inventory_file: Option<String>,
+    context: Map<String, Value>,
+) -> JobLaunch {
+    let job = DeviceJob {
+        job_id: job_id.to_string(),
+        job_type: "backup",
+        device: hostname.to_string(),
+        url: python_api_endpoint(state, BACKUP_PATH),
+        request: serde_json::json!({
+            "hostname": hostname,
+            "inventory_file": inventory_file.unwrap_or_default(),
+            "username": credentials.username,
+            "password": credentials.password(),
+        }),
+        timeout: BACKUP_TIMEOUT,
+        pooled: true,
+        context,
+    };
+    launch_device_job(state, job, ()).await
+}
+
+/// Full URL of a Python API path, under the configured PYTHON_API_URL
+pub fn python_api_endpoint(state: &AppState, path: &str) -> String {
+    format!("{}{}", state.python_api_url.trim_end_matches('/'), path)
+}
+
+/// Starts a job, holding `guard` until it ends
+pub async fn launch_device_job<G: Send + 'static>(state: &AppState, job: DeviceJob, guard: G) -> JobLaunch {
+    let job = Arc::new(job);
+    let service = Arc::clone(&state.websocket_service);
+
+    // Take a backup slot now, or queue behind the running backups
+    let admission = match job.pooled.then(|| state.backup_pool.try_acquire()) {
+        Some(None) => Some(Err(state.backup_pool.enqueue(&job.job_id))),
+        Some(Some(permit)) => Some(Ok(permit)),
+        None => None,
+    };
+    let queue_position = admission.as_ref().and_then(|admission| admission.as_ref().err()).map(|ticket| ticket.position());
+
+    let label = job_label(job.job_type);
+    let (event_type, status, message) = match queue_position {
+        None => ("OPERATION_START", "in_progress", format!("{} process initiated successfully", label)),
+        Some(_) => ("OPERATION_QUEUED", "queued", format!("{} queued until a backup slot is free", label)),
+    };
+    let event = job_event(&job, event_type, status, serde_json::json!({
+        "message": message,
+        "position": queue_position,
+    }), None);
+    if let Err(e) = service.broadcast_job_event(event).await {
+        warn!("Failed to broadcast start of {} job {}: {}", job.job_type, job.job_id, e);
+    }
+
+    let job_service = Arc::clone(&state.job_service);
+    job_service.register(&job.job_id, &job.device, job.job_type).await;
+
+    let (outcome_tx, outcome) = oneshot::channel();
+    let task_service = Arc::clone(&service);
+    let task_job_service = Arc::clone(&job_service);
+    let task_job = Arc::clone(&job);
+    let handle = task::spawn(async move {
+        // Held until the job finishes; frees the slot for the next queued backup
+        let permit = match admission {
+            Some(Ok(permit)) => Some(permit),
+            Some(Err(ticket)) => Some(ticket.wait().await),
+            None => None,
+        };
+        let job = task_job;
+
+        let result = forward_job(&job).await;
+        match &result {
+            Ok(result) => {
+                task_service
+                    .broadcast_job_event(job_event(&job, "OPERATION_COMPLETE", "completed", serde_json::json!({
+                        "message": format!("{} completed successfully via Python API", job_label(job.job_type)),
+                        "result": result,
+                    }), None))
+                    .await
+                    .ok();
+                info!("{} job {} completed for {}", job_label(job.job_type), job.job_id, job.device);
+            }
+            Err(message) => {
+                error!("{} job {} failed for {}: {}", job_label(job.job_type), job.job_id, job.device, message);
+                send_job_failure(&task_service, &job, message, false).await;
+            }
+        }
+
+        // Released before anyone hears the job finished, so a follow-up job is never refused
+        drop((permit, guard));
+        task_job_service.complete(&job.job_id, result.is_ok()).await;
+        // The caller may have stopped waiting; the job still counts as finished
+        let _ = outcome_tx.send(result);
+    });
+    job_service.attach_handle(&job.job_id, handle.abort_handle()).await;
+
+    // A panic would otherwise leave the job in flight forever; cancellation is not a failure
+    task::spawn(async move {
+        if let Err(join_error) = handle.await {
+            if join_error.is_panic() {
+                let panic = join_error.into_panic();
+                let reason = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                let message = format!("{} task panicked: {}", job_label(job.job_type), reason);
+                send_job_failure(&service, &job, &message, true).await;
+                job_service.complete(&job.job_id, false).await;
+            }
+        }
+    });
+
+    JobLaunch { queue_position, outcome }
+}
+
+// =========================================================================================
+// SECTION 3: PYTHON API CALL AND EVENTS
+// =========================================================================================
+
+/// Sends the job's request to the Python API and waits for its result
+async fn forward_job(job: &DeviceJob) -> Result<Value, String> {
+    let response = Client::new()
+        .post(&job.url)
+        .json(&job.request)
+        .timeout(job.timeout)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to Python API: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Python API error: HTTP {} - {}", status, body));
+    }
+
+    response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Failed to parse Python API response: {}", e))
+}
+
+/// "Backup" for "backup", as used in event messages
+fn job_label(job_type: &str) -> String {
+    let mut chars = job_type.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// A job event whose data carries the job's context fields
+fn job_event(job: &DeviceJob, event_type: &str, status: &str, data: Value, error: Option<String>) -> JobEventPayload {
+    let mut data = match data {
+        Value::Object(data) => data,
+        _ => Map::new(),
+    };
+    for (key, value) in &job.context {
+        data.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+
+    JobEventPayload {
+        job_id: job.job_id.clone(),
+        device: job.device.clone(),
+        job_type: job.job_type.to_string(),
+        event_type: event_type.to_string(),
+        status: status.to_string(),
+        data: Value::Object(data),
+        error,
+        timestamp: Utc::now(),
+        trace_id: None,
+    }
+}
+
+/// Broadcasts a failed OPERATION_COMPLETE and reports it on the `errors` topic
+async fn send_job_failure(service: &WebSocketService, job: &DeviceJob, message: &str, panicked: bool) {
+    let event = job_event(job, "OPERATION_COMPLETE", "failed", serde_json::json!({
+        "message": format!("{} process failed", job_label(job.job_type)),
+    }), Some(message.to_string()));
+
+    if let Err(e) = service.broadcast_job_event(event).await {
+        error!("Failed to send {} failure event: {}", job.job_type, e);
+    }
+    service
+        .report_background_error(job.job_type, Some(&job.job_id), Some(&job.device), message, panicked)
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+    use axum::{routing::post, Json, Router};
+
+    fn job(job_id: &str, url: String) -> DeviceJob {
+        DeviceJob {
+            job_id: job_id.to_string(),
+            job_type: "upgrade",
+            device: "r1".to_string(),
+            url,
+            request: serde_json::json!({ "hostname": "r1" }),
+            timeout: Duration::from_secs(5),
+            pooled: false,
+            context: Map::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn jobs_report_their_outcome_and_release_the_guard() {
+        let app = TestApp::new().await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/run", listener.local_addr().unwrap());
+        let python = Router::new().route("/run", post(|Json(request): Json<Value>| async move { Json(request) }));
+        tokio::spawn(async move { axum::serve(listener, python).await });
+
+        let guard = Arc::new(());
+        let launch = launch_device_job(&app.state, job("ok", url), Arc::clone(&guard)).await;
+        assert_eq!(launch.queue_position, None);
+        assert_eq!(launch.outcome.await.unwrap(), Ok(serde_json::json!({ "hostname": "r1" })));
+        assert_eq!(Arc::strong_count(&guard), 1);
+
+        // Nothing listens on port 1, so the call fails at once
+        let launch = launch_device_job(&app.state, job("down", "http://127.0.0.1:1/run".to_string()), ()).await;
+        let failure = launch.outcome.await.unwrap().unwrap_err();
+        assert!(failure.starts_with("Failed to connect to Python API"), "{}", failure);
+
+        let summary = app.state.job_service.summary().await;
+        assert_eq!((summary["upgrade"].completed, summary["upgrade"].failed), (1, 1));
+        assert!(app.state.job_service.list_jobs().await.is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use axum::{
 use serde_json::Value;
 use std::collections::HashMap;
 
-use crate::{models::ApiResult, AppState};
+use crate::{models::ApiResult, services::yaml_service::SchemaLoadReport, AppState};
 
 // Generic YAML handler that can be used for any schema
 pub async fn get_yaml_by_schema(
@@ -19,10 +19,10 @@ pub async fn get_yaml_by_schema(
 }
 
 // Hot reload endpoint (useful for development)
+// Recompiles schemas and reports any schema files that were skipped
 pub async fn reload_schemas(
-    State(_state): State<AppState>,
-) -> ApiResult<&'static str> {
-    // Note: This would need Arc<Mutex<YamlService>> to actually reload
-    // For now, just return success
-    Ok("Schemas reloaded successfully")
+    State(state): State<AppState>,
+) -> ApiResult<Json<SchemaLoadReport>> {
+    let report = state.yaml_service.reload_schemas().await?;
+    Ok(Json(report))
 }
@@ -1,5 +1,5 @@
 // File Path: src/api/inventory.rs
-// Version: 1.3.1
+// Version: 1.11.0
 //
 // Description:
 // API handlers for accessing the network inventory (routers, switches, firewalls).
@@ -9,25 +9,68 @@
 // - Lists all YAML files in the shared/data/inventories directory
 // - Returns structured JSON response
 // - Provides error handling for missing/invalid YAML
+// - Records every inventory edit (principal, request id, diff) in the inventory audit log
 //
 // Usage Guide:
 // GET /api/inventory → returns full inventory
-// GET /api/inventory/list → lists all inventory YAML files
+// GET /api/inventory/list → lists all inventory YAML files with per-file validation results
+// PUT /api/inventory/file/:filename → validates and writes an inventory file (admin only)
+// GET /api/inventory/grouped?by=site|role|vendor|platform → devices grouped by attribute
+// GET /api/inventory/autocomplete?q=cor&limit=10 → ranked hostname suggestions
+// GET /api/inventory/audit?limit=50 → recorded inventory edits, newest first (admin only)
+//   Edits made through PUT /api/inventory/file/:filename and PUT /api/yaml/inventory are
+//   recorded; writes that change nothing are not. The request id is the caller's
+//   X-Request-Id header when sent, otherwise generated; both write routes return it in the
+//   X-Request-Id response header (and PUT /api/inventory/file/:filename as `request_id`).
 //
 // Change Log:
+// - 1.11.0: Inventory writes return the audit request id in the X-Request-Id response header
+// - 1.10.2: Reading the inventory audit log requires admin access
+// - 1.10.1: Writing an inventory file requires admin access
+// - 1.10.0: Inventory writes are recorded in the audit log; added GET /api/inventory/audit
+// - 1.9.0: File metadata in the inventory listing is read with the YamlService scan concurrency
+// - 1.8.0: Inventory file listing validates each file against the inventory schema
+// - 1.7.0: Added hostname autocomplete endpoint
+// - 1.6.0: Added grouped inventory endpoint
+// - 1.5.0: Added Accept-based YAML/JSON content negotiation for inventory reads
+// - 1.4.0: Validate inventories against inventory.schema.json and added PUT for inventory files
 // - 1.3.1: Fixed absolute path for Docker container
 // - 1.3.0: Fixed path consistency issues
 // - 1.2.0: Fixed path handling for shared/data structure
 // - 1.1.0: Added list_inventory_files endpoint
 // - 1.0.0: Initial implementation
 
-use axum::{extract::State, response::Json};
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, HeaderName},
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use chrono::Utc;
+use serde::Deserialize;
 use serde_json::{json, Value};
-use std::path::Path;
+use futures_util::{stream, StreamExt};
+use std::{collections::BTreeMap, path::Path};
 use tokio::fs;
+use tracing::{error, info};
+use uuid::Uuid;
 
 use crate::{AppState, models::ApiResult};
-use crate::models::ApiError;
+use crate::middleware::{admin::AdminAccess, auth::Principal};
+use crate::models::{
+    ApiError, AutocompleteResponse, DeviceSuggestion, GroupedInventoryResponse, InventoryDevice,
+    InventoryGroup, Negotiated, ResponseFormat,
+};
+use crate::services::{inventory_audit_service::InventoryAuditEntry, yaml_service::WriteOutcome};
+
+/// Schema used to validate every file in the inventories directory
+pub(crate) const INVENTORY_SCHEMA: &str = "inventory";
+
+/// Longest X-Request-Id accepted from a caller; longer ids are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Header carrying the request id of an inventory write, in both directions
+pub(crate) const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
 
 // =============================================================================
 // Inventory Data Retrieval
@@ -35,14 +78,17 @@ use crate::models::ApiError;
 // Handlers for fetching and reading inventory data
 
 /// Handler to return the full inventory
-pub async fn get_inventory(State(state): State<AppState>) -> ApiResult<Json<Value>> {
+pub async fn get_inventory(
+    State(state): State<AppState>,
+    format: ResponseFormat,
+) -> ApiResult<Negotiated<Value>> {
     // Load inventory.yaml from shared/data/inventories - FIXED PATH
     let data = state.yaml_service
-        .get_yaml_data("inventories/inventory", None)
+        .get_yaml_data(INVENTORY_SCHEMA, Some("inventories/inventory.yaml"))
         .await
         .map_err(|e| ApiError::YamlParseError(format!("Failed to load inventory: {}", e)))?;
 
-    Ok(Json(data))
+    Ok(Negotiated::new(format, data))
 }
 
 // =============================================================================
@@ -51,7 +97,10 @@ pub async fn get_inventory(State(state): State<AppState>) -> ApiResult<Json<Valu
 // Handlers for discovering and listing available inventory files
 
 /// Handler to list all YAML files in the shared/data/inventories directory
-pub async fn list_inventory_files() -> ApiResult<Json<Value>> {
+///
+/// Each file is validated against the inventory schema (concurrently) and
+/// carries `valid` and `errors`; `valid_count`/`invalid_count` summarize the set.
+pub async fn list_inventory_files(State(state): State<AppState>) -> ApiResult<Json<Value>> {
     // Define the inventories directory path - FIXED: Use absolute Docker path
     let inventories_path = Path::new("/shared/data/inventories");
 
@@ -72,43 +121,53 @@ pub async fn list_inventory_files() -> ApiResult<Json<Value>> {
     // Read directory contents
     let mut entries = fs::read_dir(inventories_path).await?;
 
-    let mut yaml_files = Vec::new();
-
-    // Iterate through directory entries
+    // Collect entries with a .yaml or .yml extension
+    let mut candidates = Vec::new();
     while let Some(entry) = entries.next_entry().await? {
         let path = entry.path();
+        if matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml")) {
+            candidates.push(path);
+        }
+    }
 
-        // Check if it's a file with .yaml or .yml extension
-        if path.is_file() {
-            if let Some(extension) = path.extension() {
-                if extension == "yaml" || extension == "yml" {
-                    // Extract file information
-                    let file_name = path.file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or_default()
-                        .to_string();
-
-                    let file_stem = path.file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or_default()
-                        .to_string();
-
-                    // Get file metadata
-                    let metadata = entry.metadata().await?;
-
-                    yaml_files.push(json!({
-                        "name": file_name,
-                        "stem": file_stem,
-                        "size": metadata.len(),
-                        "modified": metadata.modified()
-                            .ok()
-                            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                            .map(|d| d.as_secs())
-                            .unwrap_or(0)
-                    }));
-                }
-            }
+    // Read metadata a bounded number of files at a time; directories are skipped
+    let metadata: Vec<_> = stream::iter(candidates)
+        .map(|path| async move {
+            let metadata = fs::metadata(&path).await;
+            (path, metadata)
+        })
+        .buffer_unordered(state.yaml_service.scan_concurrency())
+        .collect()
+        .await;
+
+    let mut yaml_files = Vec::new();
+    for (path, metadata) in metadata {
+        let metadata = metadata?;
+        if !metadata.is_file() {
+            continue;
         }
+
+        // Extract file information
+        let file_name = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let file_stem = path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        yaml_files.push(json!({
+            "name": file_name,
+            "stem": file_stem,
+            "size": metadata.len(),
+            "modified": metadata.modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        }));
     }
 
     // Sort files by name
@@ -117,9 +176,25 @@ pub async fn list_inventory_files() -> ApiResult<Json<Value>> {
             .cmp(b.get("name").and_then(|v| v.as_str()).unwrap_or(""))
     });
 
+    // Validate every listed file; results come back in listing order
+    let relative_paths: Vec<String> = yaml_files
+        .iter()
+        .map(|file| format!("inventories/{}", file["name"].as_str().unwrap_or_default()))
+        .collect();
+    let validations = state.yaml_service
+        .validate_files(INVENTORY_SCHEMA, &relative_paths)
+        .await?;
+    for (file, validation) in yaml_files.iter_mut().zip(validations) {
+        file["valid"] = json!(validation.valid);
+        file["errors"] = json!(validation.errors);
+    }
+    let valid_count = yaml_files.iter().filter(|file| file["valid"] == json!(true)).count();
+
     Ok(Json(json!({
         "files": yaml_files,
         "count": yaml_files.len(),
+        "valid_count": valid_count,
+        "invalid_count": yaml_files.len() - valid_count,
         "path": "/shared/data/inventories"
     })))
 }
@@ -132,22 +207,392 @@ pub async fn list_inventory_files() -> ApiResult<Json<Value>> {
 /// Handler to get a specific inventory file by name
 pub async fn get_inventory_file(
     State(state): State<AppState>,
-    axum::extract::Path(filename): axum::extract::Path<String>
-) -> ApiResult<Json<Value>> {
+    axum::extract::Path(filename): axum::extract::Path<String>,
+    format: ResponseFormat,
+) -> ApiResult<Negotiated<Value>> {
     // Remove .yaml/.yml extension if provided
     let file_stem = filename.trim_end_matches(".yaml").trim_end_matches(".yml");
 
     // Construct the path relative to the inventories directory
-    let inventory_path = format!("inventories/{}", file_stem);
+    let inventory_path = format!("inventories/{}.yaml", file_stem);
 
     // Load the specific inventory file
     let data = state.yaml_service
-        .get_yaml_data(&inventory_path, None)
+        .get_yaml_data(INVENTORY_SCHEMA, Some(&inventory_path))
         .await
         .map_err(|e| ApiError::YamlParseError(format!("Failed to load inventory file '{}': {}", filename, e)))?;
 
-    Ok(Json(json!({
+    Ok(Negotiated::new(format, json!({
         "filename": format!("{}.yaml", file_stem),
         "data": data
     })))
 }
+
+/// Handler to validate and write a specific inventory file
+///
+/// Devices are validated incrementally when only device entries changed
+/// since the file was last read or written.
+pub async fn update_inventory_file(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+    axum::extract::Path(filename): axum::extract::Path<String>,
+    headers: HeaderMap,
+    principal: Option<Extension<Principal>>,
+    Json(data): Json<Value>,
+) -> ApiResult<Response> {
+    let file_stem = filename.trim_end_matches(".yaml").trim_end_matches(".yml");
+    let inventory_path = format!("inventories/{}.yaml", file_stem);
+    let request_id = request_id(&headers);
+
+    let outcome = state.yaml_service
+        .write_yaml_data(INVENTORY_SCHEMA, Some(&inventory_path), data)
+        .await?;
+    audit_inventory_write(
+        &state,
+        &request_id,
+        principal.as_ref().map(|Extension(principal)| principal),
+        &inventory_path,
+        &outcome,
+    )
+    .await;
+
+    Ok((
+        [(REQUEST_ID_HEADER, request_id.clone())],
+        Json(json!({
+            "filename": format!("{}.yaml", file_stem),
+            "validation": outcome.validation,
+            "request_id": request_id
+        })),
+    ).into_response())
+}
+
+// =============================================================================
+// Inventory Audit Log
+// =============================================================================
+// Who changed the inventory, when, and what changed
+
+/// Query parameters for the audit log endpoint
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    /// Maximum entries (default and cap from the shared pagination bounds)
+    pub limit: Option<usize>,
+}
+
+/// The caller's X-Request-Id, or a generated one when it is missing or unusable
+pub(crate) fn request_id(headers: &HeaderMap) -> String {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .map_or_else(|| Uuid::new_v4().to_string(), str::to_string)
+}
+
+/// Records a written inventory file in the audit log under `request_id`
+///
+/// The write has already happened, so a failure to record it is logged rather than
+/// turned into an error response.
+pub(crate) async fn audit_inventory_write(
+    state: &AppState,
+    request_id: &str,
+    principal: Option<&Principal>,
+    file: &str,
+    outcome: &WriteOutcome,
+) {
+    if !outcome.written || outcome.diff.is_empty() {
+        return;
+    }
+
+    let entry = InventoryAuditEntry {
+        timestamp: Utc::now(),
+        request_id: request_id.to_string(),
+        principal: principal.map(|principal| principal.id.clone()),
+        file: file.to_string(),
+        diff: outcome.diff.clone(),
+    };
+    match state.inventory_audit.record(&entry).await {
+        Ok(()) => info!(
+            "Inventory {} changed by {} ({} changes, request {})",
+            file,
+            entry.principal.as_deref().unwrap_or("anonymous"),
+            entry.diff.len(),
+            request_id
+        ),
+        Err(e) => error!("Failed to record inventory change to {} (request {}): {}", file, request_id, e),
+    }
+}
+
+/// Handler returning recorded inventory edits, newest first
+pub async fn get_inventory_audit(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+    Query(params): Query<AuditQuery>,
+) -> ApiResult<Json<Value>> {
+    let limit = state.pagination.page_size(params.limit);
+    let entries = state.inventory_audit.recent(limit).await?;
+
+    Ok(Json(json!({
+        "entries": entries,
+        "count": entries.len(),
+        "limit": limit
+    })))
+}
+
+// =============================================================================
+// Grouped Inventory
+// =============================================================================
+// Flattens the inventory and groups devices by a device attribute
+
+/// Attributes devices can be grouped by
+const GROUP_KEYS: [&str; 4] = ["site", "role", "vendor", "platform"];
+
+/// Query parameters for the grouped inventory endpoint
+#[derive(Debug, Deserialize)]
+pub struct GroupedInventoryQuery {
+    /// Grouping attribute: site (default), role, vendor or platform
+    pub by: Option<String>,
+}
+
+/// Handler to return inventory devices grouped by site, role, vendor or platform
+pub async fn get_grouped_inventory(
+    State(state): State<AppState>,
+    Query(params): Query<GroupedInventoryQuery>,
+    format: ResponseFormat,
+) -> ApiResult<Negotiated<GroupedInventoryResponse>> {
+    let by = params.by.unwrap_or_else(|| "site".to_string()).to_lowercase();
+    if !GROUP_KEYS.contains(&by.as_str()) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid group key '{}', expected one of: {}",
+            by,
+            GROUP_KEYS.join(", ")
+        )));
+    }
+
+    let data = state.yaml_service
+        .get_yaml_data(INVENTORY_SCHEMA, Some("inventories/inventory.yaml"))
+        .await?;
+
+    let devices = flatten_inventory(&data);
+    let total = devices.len();
+
+    let mut groups: BTreeMap<String, InventoryGroup> = BTreeMap::new();
+    for device in devices {
+        let key = match by.as_str() {
+            "site" => device.site.clone(),
+            "role" => device.role.clone(),
+            "vendor" => device.vendor.clone(),
+            _ => device.platform.clone(),
+        };
+        let group = groups.entry(key).or_insert_with(|| InventoryGroup {
+            count: 0,
+            devices: Vec::new(),
+        });
+        group.count += 1;
+        group.devices.push(device);
+    }
+
+    Ok(Negotiated::new(format, GroupedInventoryResponse { by, total, groups }))
+}
+
+// =============================================================================
+// Device Autocomplete
+// =============================================================================
+// Ranked hostname type-ahead over the flattened inventory
+
+/// Suggestions returned when `limit` is not given
+const DEFAULT_AUTOCOMPLETE_LIMIT: usize = 10;
+
+/// Upper bound on `limit`
+const MAX_AUTOCOMPLETE_LIMIT: usize = 50;
+
+/// Query parameters for the autocomplete endpoint
+#[derive(Debug, Deserialize)]
+pub struct AutocompleteQuery {
+    /// Hostname fragment, matched case-insensitively
+    #[serde(default)]
+    pub q: String,
+    /// Maximum suggestions (default 10, capped at 50)
+    pub limit: Option<usize>,
+}
+
+/// Handler returning hostnames matching a query, best matches first
+///
+/// Exact matches rank first, then prefix matches, then substring matches;
+/// ties are broken by shorter hostname, then alphabetically. The parsed
+/// inventory comes from the YAML service's document cache, so repeated
+/// keystrokes don't re-read the file.
+pub async fn autocomplete_devices(
+    State(state): State<AppState>,
+    Query(params): Query<AutocompleteQuery>,
+) -> ApiResult<Json<AutocompleteResponse>> {
+    let query = params.q.trim().to_string();
+    let limit = params.limit.unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT).min(MAX_AUTOCOMPLETE_LIMIT);
+    if query.is_empty() || limit == 0 {
+        return Ok(Json(AutocompleteResponse { query, count: 0, suggestions: Vec::new() }));
+    }
+
+    let data = state.yaml_service
+        .get_yaml_data(INVENTORY_SCHEMA, Some("inventories/inventory.yaml"))
+        .await?;
+
+    let needle = query.to_lowercase();
+    let mut ranked: Vec<(u8, InventoryDevice)> = flatten_inventory(&data)
+        .into_iter()
+        .filter_map(|device| {
+            let host = device.host_name.to_lowercase();
+            let rank = if host == needle {
+                0
+            } else if host.starts_with(&needle) {
+                1
+            } else if host.contains(&needle) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, device))
+        })
+        .collect();
+
+    ranked.sort_by(|(rank_a, a), (rank_b, b)| {
+        rank_a
+            .cmp(rank_b)
+            .then(a.host_name.len().cmp(&b.host_name.len()))
+            .then_with(|| a.host_name.cmp(&b.host_name))
+    });
+    ranked.dedup_by(|(_, a), (_, b)| a.host_name == b.host_name);
+
+    let suggestions: Vec<DeviceSuggestion> = ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, device)| DeviceSuggestion {
+            host_name: device.host_name,
+            role: device.role,
+            site: device.site,
+            ip_address: device.ip_address,
+        })
+        .collect();
+
+    Ok(Json(AutocompleteResponse { query, count: suggestions.len(), suggestions }))
+}
+
+/// Flattens `locations.<site>.<role>[]` into a list of devices
+///
+/// Entries that don't match the device shape are skipped.
+pub fn flatten_inventory(data: &Value) -> Vec<InventoryDevice> {
+    let mut devices = Vec::new();
+    let Some(locations) = data.get("locations").and_then(|l| l.as_object()) else {
+        return devices;
+    };
+
+    for (site, roles) in locations {
+        let Some(roles) = roles.as_object() else { continue };
+        for (role, entries) in roles {
+            let Some(entries) = entries.as_array() else { continue };
+            for entry in entries {
+                let field = |name: &str| {
+                    entry.get(name).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+                };
+                let host_name = field("host_name");
+                if host_name.is_empty() {
+                    continue;
+                }
+                devices.push(InventoryDevice {
+                    host_name,
+                    vendor: field("vendor"),
+                    ip_address: field("ip_address"),
+                    platform: field("platform"),
+                    site: site.clone(),
+                    role: role.clone(),
+                });
+            }
+        }
+    }
+
+    devices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    #[tokio::test]
+    async fn inventory_file_writes_return_and_record_the_request_id() {
+        let app = TestApp::new().await;
+        let write = |data: Value| {
+            update_inventory_file(
+                AdminAccess,
+                State(app.state.clone()),
+                axum::extract::Path("lab.yml".to_string()),
+                HeaderMap::new(),
+                None,
+                Json(data),
+            )
+        };
+
+        let response = write(json!({ "locations": { "LAB": { "routers": [] } } })).await.unwrap();
+        let request_id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert!(!request_id.is_empty());
+
+        let entries = audit_entries(&app).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].request_id.as_str(), entries[0].file.as_str()), (request_id.as_str(), "inventories/lab.yaml"));
+
+        // An unchanged file is written again but not recorded
+        write(json!({ "locations": { "LAB": { "routers": [] } } })).await.unwrap();
+        assert_eq!(audit_entries(&app).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn devices_are_grouped_by_the_requested_attribute() {
+        let app = TestApp::new().await;
+        app.write_data(
+            "inventories/inventory.yaml",
+            "locations:\n  LAB:\n    routers:\n      - { host_name: r1, vendor: juniper, platform: mx }\n      - { vendor: juniper }\n    switches:\n      - { host_name: s1, vendor: arista, platform: eos }\n  DC:\n    routers:\n      - { host_name: r2, vendor: juniper, platform: mx }\n",
+        )
+        .await;
+        let grouped = |by: &str| {
+            get_grouped_inventory(
+                State(app.state.clone()),
+                Query(GroupedInventoryQuery { by: Some(by.to_string()) }),
+                ResponseFormat::Json,
+            )
+        };
+
+        // Entries without a host_name are not devices
+        let response = grouped("Vendor").await.unwrap().body;
+        assert_eq!((response.by.as_str(), response.total), ("vendor", 3));
+        assert_eq!((response.groups["juniper"].count, response.groups["arista"].count), (2, 1));
+        let sites = grouped("site").await.unwrap().body;
+        assert_eq!(sites.groups.keys().collect::<Vec<_>>(), ["DC", "LAB"]);
+
+        assert!(matches!(grouped("owner").await, Err(ApiError::BadRequest(message)) if message.contains("'owner'")));
+    }
+
+    #[tokio::test]
+    async fn autocomplete_ranks_exact_then_prefix_then_substring() {
+        let app = TestApp::new().await;
+        app.write_data(
+            "inventories/inventory.yaml",
+            "locations:\n  LAB:\n    routers:\n      - { host_name: core-r1 }\n      - { host_name: edge-core }\n      - { host_name: core }\n    switches:\n      - { host_name: CORE-s10 }\n      - { host_name: access-1 }\n",
+        )
+        .await;
+        let complete = |q: &str, limit: Option<usize>| {
+            autocomplete_devices(State(app.state.clone()), Query(AutocompleteQuery { q: q.to_string(), limit }))
+        };
+
+        let Json(response) = complete(" Core ", None).await.unwrap();
+        let hosts: Vec<_> = response.suggestions.iter().map(|s| s.host_name.as_str()).collect();
+        assert_eq!(hosts, ["core", "core-r1", "CORE-s10", "edge-core"]);
+        assert_eq!((response.query.as_str(), response.count), ("Core", 4));
+
+        let Json(limited) = complete("core", Some(2)).await.unwrap();
+        assert_eq!(limited.count, 2);
+        let Json(empty) = complete("  ", None).await.unwrap();
+        assert!(empty.suggestions.is_empty());
+    }
+
+    async fn audit_entries(app: &TestApp) -> Vec<InventoryAuditEntry> {
+        app.state.inventory_audit.recent(10).await.unwrap()
+    }
+}
@@ -7,4 +7,7 @@ pub mod websocket;
 pub mod inventory;
 pub mod sidebar;
 pub mod backups;
+pub mod backup_batch;
+pub mod device_jobs;
 pub mod restore;
+pub mod upgrade;
@@ -1,5 +1,5 @@
 // File Path: backend/src/api/navigation.rs
-// Version: 3.1.1
+// Version: 3.4.0
 // Description: API handlers for serving navigation menu data from YAML files with schema validation.
 // Key Features:
 // - Provides endpoints to serve navigation data as JSON.
@@ -11,7 +11,14 @@
 // 2. Place schema under shared/schemas/navigation.schema.json.
 // 3. Frontend calls `/api/navigation` or `/api/navigation/yaml` to get validated JSON.
 // 4. Optional: validate manually using `/api/yaml/navigation/validate`.
+// 5. Send `Accept: text/yaml` to receive the validated data as YAML instead of JSON.
+// 6. Frontend calls `/api/navigation/form-schema` to render a schema-driven navigation editor.
+// 7. `/api/navigation/settings` is validated against settingsSidebarNavigation.schema.json and
+//    returned as a typed NavigationConfig (`items`, `settings`).
 // Change Log:
+// - 3.4.0 (2026-10-16): Settings navigation requires its schema and returns a typed NavigationConfig.
+// - 3.3.0 (2026-10-16): Added form descriptor endpoint derived from navigation.schema.json.
+// - 3.2.0 (2026-10-16): Added Accept-based YAML/JSON content negotiation.
 // - 3.1.1 (2025-09-14): Updated to use absolute data directory path.
 // - 3.1.0 (2025-09-13): Updated get_navigation to load navigation.yaml using yaml_service.
 // - 3.0.0 (2025-09-13): Integrated schema validation through yaml_service.
@@ -25,11 +32,12 @@
 
 use axum::{
     extract::{Query, State},
-    response::Json,
+    Json,
 };
 use std::collections::HashMap;
 use crate::{
-    models::ApiResult,
+    models::{ApiResult, NavigationConfig, Negotiated, ResponseFormat},
+    services::yaml_service::FormSchema,
     AppState,
 };
 
@@ -49,7 +57,8 @@ use crate::{
 pub async fn get_navigation(
     Query(params): Query<HashMap<String, String>>,
     State(state): State<AppState>,
-) -> ApiResult<Json<serde_json::Value>> {
+    format: ResponseFormat,
+) -> ApiResult<Negotiated<serde_json::Value>> {
     let file_path = params.get("file").cloned();
 
     // Load and validate YAML using yaml_service
@@ -62,7 +71,7 @@ pub async fn get_navigation(
         .validate_yaml_data("navigation", file_path.as_deref())
         .await?;
 
-    Ok(Json(data))
+    Ok(Negotiated::new(format, data))
 }
 
 // ====================================================
@@ -82,7 +91,8 @@ pub async fn get_navigation(
 pub async fn get_navigation_from_yaml(
     Query(params): Query<HashMap<String, String>>,
     State(state): State<AppState>,
-) -> ApiResult<Json<serde_json::Value>> {
+    format: ResponseFormat,
+) -> ApiResult<Negotiated<serde_json::Value>> {
     let file_path = params.get("file").cloned();
 
     // This will automatically:
@@ -94,27 +104,53 @@ pub async fn get_navigation_from_yaml(
         .validate_yaml_data("navigation", file_path.as_deref())
         .await?;
 
-    Ok(Json(data))
+    Ok(Negotiated::new(format, data))
 }
 
 // ====================================================
 // SECTION: Settings Navigation Handler
 // ====================================================
 // This section handles loading settings sidebar navigation from YAML,
-// potentially using a different schema.
+// validated against its own schema.
 
-/// Loads settings sidebar navigation from YAML.
-/// Still separate from main nav to allow different schema.
+/// Loads settings sidebar navigation from YAML, validates it against
+/// `settingsSidebarNavigation.schema.json` and returns it as a `NavigationConfig`.
+///
+/// Malformed navigation is a 400 with one entry per validation issue; a missing
+/// schema is a 404 rather than serving unvalidated data.
+///
+/// Example:
+/// GET /api/navigation/settings?file=custom_settings.yaml
 pub async fn get_settings_navigation(
     Query(params): Query<HashMap<String, String>>,
     State(state): State<AppState>,
-) -> ApiResult<Json<serde_json::Value>> {
+    format: ResponseFormat,
+) -> ApiResult<Negotiated<NavigationConfig>> {
     let file_path = params.get("file").cloned();
 
-    let data = state
+    let data: NavigationConfig = state
         .yaml_service
-        .get_yaml_data("settingsSidebarNavigation", file_path.as_deref())
+        .get_typed_data("settingsSidebarNavigation", file_path.as_deref())
         .await?;
 
-    Ok(Json(data))
+    Ok(Negotiated::new(format, data))
+}
+
+// ====================================================
+// SECTION: Navigation Form Schema Handler
+// ====================================================
+// This section exposes the navigation schema as a simplified form descriptor
+// so an editor UI can be driven by the schema.
+
+/// Returns the editable fields of navigation entries (name, type, required,
+/// enum options), derived from `navigation.schema.json` and cached until the
+/// schemas are reloaded.
+///
+/// Example:
+/// GET /api/navigation/form-schema
+pub async fn get_navigation_form_schema(
+    State(state): State<AppState>,
+) -> ApiResult<Json<FormSchema>> {
+    let descriptor = state.yaml_service.form_schema("navigation").await?;
+    Ok(Json(descriptor))
 }
@@ -1,6 +1,6 @@
 // =========================================================================================
 // File Path: src/api/restore.rs
-// Version: 1.1.0
+// Version: 1.8.3
 //
 // Description:
 // API handlers for restoring configuration backups. Calls the Python RestoreConfig worker
@@ -9,21 +9,87 @@
 // Key Features:
 // - POST /api/restore/run executes restore process for a device
 // - Captures stdout/stderr logs
-// - Returns structured JSON with status, message, and logs
+// - Parses the structured JSON result line emitted by RestoreConfig.py
+// - Only one restore or upgrade per device at a time (409 Conflict while one is running)
+// - Returns structured JSON with status (SUCCESS, PARTIAL, FAILED), message, result, and logs
+// - Optional rollback: snapshots the device via the Python API first and restores the
+//   snapshot when the main restore fails, emitting rollback_started/rollback_completed job events
+// - RestoreConfig.py runs as a host subprocess or through the Python runner's container
+//   execution path, selected with RESTORE_RUNTIME
+// - Rejects backup files that belong to another device (by directory or the host name embedded
+//   in `<timestamp>_<host>_config.<ext>`) unless `force` is set
 //
 // Usage Guide:
-// POST /api/restore/run → { hostname, username, password, backup_file }
+// POST /api/restore/run → { hostname, username?, password?, backup_file, rollback_on_failure?, force? }
+// Omitted credentials come from the inventory or DEVICE_USERNAME / DEVICE_PASSWORD.
+// RESTORE_RUNTIME=container routes restores through the Python runner (default: subprocess,
+// for environments without Docker). A container run that prints no structured result line is
+// FAILED: the runner's exit code alone does not show that RestoreConfig.py touched the device.
 //
 // Change Log:
+// - 1.8.3: The pre-restore snapshot goes to PYTHON_API_URL instead of a fixed host
+// - 1.8.2: Container restores without a structured result line are FAILED instead of SUCCESS
+// - 1.8.1: Failed restores and rollbacks are reported on the background `errors` topic
+// - 1.8.0: The device lock is taken as a `restore` holder, shown by GET /api/devices/locks
+// - 1.7.0: Credentials are resolved by the credentials service; request username/password are optional overrides
+// - 1.6.1: The per-device lock is shared with upgrades; the 409 message says so
+// - 1.6.0: Verify the backup file belongs to the target device; `force` overrides with a warning
+// - 1.5.0: Added RESTORE_RUNTIME to run restores through the Python runner's container path
+// - 1.4.1: Count restore outcomes in the job activity summary
+// - 1.4.0: Added opt-in pre-restore snapshot and automatic rollback on failure
+// - 1.3.0: Serialize restores per device; concurrent restores to the same device return 409
+// - 1.2.0: Added structured result parsing and PARTIAL status mapping
 // - 1.1.0: Fixed error handling and route registration
 // - 1.0.0: Initial implementation
 // =========================================================================================
 
 use axum::{extract::State, response::Json};
+use chrono::Utc;
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, path::Path, time::Duration};
 use tokio::process::Command;
+use tracing::{error, info, warn};
+use uuid::Uuid;
 
-use crate::{AppState, models::{ApiResult, ApiError}};
+use crate::{AppState, api::{device_jobs::python_api_endpoint, inventory::flatten_inventory}, models::{ApiResult, ApiError}, services::{credentials_service::Credentials, ExecutionPriority, ExecutionStatus}};
+use crate::models::websocket::JobEventPayload;
+
+/// Python API path used to capture the pre-restore snapshot
+const SNAPSHOT_PATH: &str = "/api/backups/devices";
+
+/// Maximum time to wait for the pre-restore snapshot
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Maximum time to wait for a restore run through the Python runner
+const CONTAINER_RESTORE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Interval between status checks of a restore run through the Python runner
+const CONTAINER_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Where RestoreConfig.py runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RestoreRuntime {
+    /// `python3` on the host, for environments without Docker
+    Subprocess,
+    /// The Python runner's container execution path, like other device-touching scripts
+    Container,
+}
+
+impl RestoreRuntime {
+    /// Reads RESTORE_RUNTIME (`subprocess` or `container`), defaulting to subprocess
+    fn from_env() -> Self {
+        match std::env::var("RESTORE_RUNTIME") {
+            Ok(value) if value.eq_ignore_ascii_case("container") => Self::Container,
+            Ok(value) if !value.is_empty() && !value.eq_ignore_ascii_case("subprocess") => {
+                warn!("Unknown RESTORE_RUNTIME '{}', using subprocess", value);
+                Self::Subprocess
+            }
+            _ => Self::Subprocess,
+        }
+    }
+}
 
 // =========================================================================================
 // SECTION 1: REQUEST/RESPONSE STRUCTS
@@ -33,16 +99,65 @@ use crate::{AppState, models::{ApiResult, ApiError}};
 #[derive(Deserialize)]
 pub struct RestoreRequest {
     pub hostname: String,
-    pub username: String,
-    pub password: String,
+    /// Overrides the inventory and environment login; see CredentialsService
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
     pub backup_file: String,
+    /// Snapshot the device first and restore the snapshot if the restore fails
+    #[serde(default)]
+    pub rollback_on_failure: bool,
+    /// Restore even when the backup file does not belong to `hostname`
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[derive(Serialize)]
 pub struct RestoreResponse {
     pub status: String,
     pub message: String,
+    /// Structured outcome reported by RestoreConfig.py, when present
+    pub result: Option<RestoreResult>,
     pub logs: Option<String>,
+    /// Rollback outcome, present only when a rollback was attempted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollback: Option<RollbackOutcome>,
+}
+
+/// Outcome of restoring the pre-restore snapshot after a failed restore
+#[derive(Debug, Clone, Serialize)]
+pub struct RollbackOutcome {
+    /// Snapshot file that was restored
+    pub snapshot_file: String,
+    /// SUCCESS, PARTIAL or FAILED, as for the main restore
+    pub status: String,
+    pub result: Option<RestoreResult>,
+    pub logs: Option<String>,
+}
+
+/// Exit status and output of one RestoreConfig.py run
+struct ScriptRun {
+    status: &'static str,
+    result: Option<RestoreResult>,
+    logs: String,
+}
+
+/// Structured result line printed by RestoreConfig.py as a single JSON object
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RestoreResult {
+    /// Whether the configuration was committed on the device
+    #[serde(default)]
+    pub committed: bool,
+    /// Whether the restore changed the running configuration
+    #[serde(default)]
+    pub changed: bool,
+    /// Errors reported during the restore
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Any additional script-specific details
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 // =========================================================================================
@@ -51,15 +166,184 @@ pub struct RestoreResponse {
 // =========================================================================================
 
 pub async fn run_restore(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(payload): Json<RestoreRequest>,
 ) -> ApiResult<Json<RestoreResponse>> {
+    verify_backup_owner(&state, &payload).await?;
+    let credentials = state
+        .credentials_service
+        .resolve(&payload.hostname, payload.username.as_deref(), payload.password.as_deref())
+        .await?;
+    info!("Restoring {} to {} ({})", payload.backup_file, payload.hostname, credentials);
+
+    // Hold the device lock for the whole restore so writes to one device never overlap
+    let _device_lock = state.device_lock_service
+        .try_lock(&payload.hostname, "restore", None)
+        .await
+        .ok_or_else(|| ApiError::Conflict(format!(
+            "Another restore or upgrade is already running for {}",
+            payload.hostname
+        )))?;
+
+    // Without a snapshot there is nothing to roll back to, so refuse to start
+    let snapshot_file = if payload.rollback_on_failure {
+        Some(capture_snapshot(&state, &payload, &credentials).await?)
+    } else {
+        None
+    };
+
+    let run = match run_restore_script(&state, &payload, &credentials, &payload.backup_file).await {
+        Ok(run) => run,
+        Err(e) => {
+            report_restore_failure(&state, &payload.hostname, &e.to_string()).await;
+            return Err(e);
+        }
+    };
+    state.job_service.record_outcome("restore", run.status == "SUCCESS").await;
+    if run.status != "SUCCESS" {
+        report_restore_failure(&state, &payload.hostname, &format!("Restore ended with status {}", run.status)).await;
+    }
+    let message = match run.status {
+        "SUCCESS" => format!("Restore for {} completed successfully", payload.hostname),
+        "PARTIAL" => format!("Restore for {} partially completed", payload.hostname),
+        _ => format!("Restore for {} failed", payload.hostname),
+    };
+
+    // A PARTIAL restore left the device half-applied, so it is rolled back too
+    let rollback = match snapshot_file {
+        Some(snapshot_file) if run.status != "SUCCESS" => {
+            Some(rollback(&state, &payload, &credentials, snapshot_file).await)
+        }
+        _ => None,
+    };
+
+    Ok(Json(RestoreResponse {
+        status: run.status.into(),
+        message,
+        result: run.result,
+        logs: Some(run.logs),
+        rollback,
+    }))
+}
+
+// =========================================================================================
+// SECTION 2a: BACKUP OWNERSHIP CHECK
+// Guards against pushing one device's configuration onto another
+// =========================================================================================
+
+/// Rejects a backup file that does not belong to the target device, unless forced
+///
+/// The owner comes from the file's directory (`<host>/...`) and from the host name
+/// BackupConfig.py embeds in the file name; every owner found must name the target,
+/// by host name or inventory IP. A file naming no device cannot be verified and is
+/// rejected as well.
+async fn verify_backup_owner(state: &AppState, payload: &RestoreRequest) -> ApiResult<()> {
+    let owners = backup_file_owners(&payload.backup_file);
+    let aliases = device_aliases(state, &payload.hostname).await;
+
+    let problem = if owners.is_empty() {
+        Some(format!(
+            "Cannot tell which device backup file '{}' belongs to",
+            payload.backup_file
+        ))
+    } else {
+        owners
+            .iter()
+            .find(|owner| !aliases.contains(&owner.to_lowercase()))
+            .map(|owner| format!(
+                "Backup file '{}' belongs to device '{}', not '{}'",
+                payload.backup_file, owner, payload.hostname
+            ))
+    };
+
+    match problem {
+        None => Ok(()),
+        Some(problem) if payload.force => {
+            warn!("⚠️ FORCED RESTORE: {}; restoring anyway because force=true", problem);
+            Ok(())
+        }
+        Some(problem) => Err(ApiError::BadRequest(format!(
+            "{} (set \"force\": true to restore anyway)",
+            problem
+        ))),
+    }
+}
+
+/// Devices a backup path names: its parent directory and the host in its file name
+fn backup_file_owners(backup_file: &str) -> Vec<String> {
+    let path = Path::new(backup_file);
+    let directory = path
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+        .map(str::to_string);
+    let tagged = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(host_from_backup_name);
+
+    directory.into_iter().chain(tagged).collect()
+}
+
+/// Host name from a `<YYYYMMDD>_<HHMMSS>_<host>_config.<ext>` backup file name
+fn host_from_backup_name(file_name: &str) -> Option<String> {
+    let (base, _) = file_name.rsplit_once("_config.")?;
+    let mut parts = base.splitn(3, '_');
+    let date = parts.next()?;
+    let time = parts.next()?;
+    let host = parts.next()?;
+
+    let digits = |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    (digits(date, 8) && digits(time, 6) && !host.is_empty()).then(|| host.to_string())
+}
+
+/// Lowercased names the target device may appear under in backup paths
+///
+/// The requested hostname, plus the inventory host name and IP of the matching
+/// inventory entry, since restores may address a device by IP.
+async fn device_aliases(state: &AppState, hostname: &str) -> Vec<String> {
+    let mut aliases = vec![hostname.to_lowercase()];
+
+    match state.yaml_service.get_yaml_data("inventory", Some("inventories/inventory.yaml")).await {
+        Ok(inventory) => {
+            for device in flatten_inventory(&inventory) {
+                if device.host_name.eq_ignore_ascii_case(hostname) || device.ip_address == hostname {
+                    aliases.push(device.host_name.to_lowercase());
+                    aliases.push(device.ip_address.to_lowercase());
+                }
+            }
+        }
+        Err(e) => warn!("Inventory unavailable for backup ownership check: {}", e),
+    }
+
+    aliases
+}
+
+/// Runs RestoreConfig.py for the request's device with the given backup file
+async fn run_restore_script(
+    state: &AppState,
+    payload: &RestoreRequest,
+    credentials: &Credentials,
+    backup_file: &str,
+) -> ApiResult<ScriptRun> {
+    let args = vec![
+        payload.hostname.clone(),
+        credentials.username.clone(),
+        credentials.password().to_string(),
+        backup_file.to_string(),
+    ];
+
+    match RestoreRuntime::from_env() {
+        RestoreRuntime::Subprocess => run_restore_subprocess(args).await,
+        RestoreRuntime::Container => run_restore_container(state, args).await,
+    }
+}
+
+/// Runs RestoreConfig.py with `python3` on the host
+async fn run_restore_subprocess(args: Vec<String>) -> ApiResult<ScriptRun> {
     let output = Command::new("python3")
         .arg("RestoreConfig.py")
-        .arg(&payload.hostname)
-        .arg(&payload.username)
-        .arg(&payload.password)
-        .arg(&payload.backup_file)
+        .args(&args)
         .output()
         .await
         .map_err(|e| ApiError::ExecutionError(format!("Failed to run RestoreConfig.py: {}", e)))?;
@@ -67,16 +351,322 @@ pub async fn run_restore(
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-    let status = if output.status.success() { "SUCCESS" } else { "FAILED" };
-    let message = if output.status.success() {
-        format!("Restore for {} completed successfully", payload.hostname)
-    } else {
-        format!("Restore for {} failed", payload.hostname)
+    let result = parse_restore_result(&stdout);
+    let status = restore_status(output.status.success(), result.as_ref());
+
+    Ok(ScriptRun {
+        status,
+        result,
+        logs: format!("stdout:\n{}\nstderr:\n{}", stdout, stderr),
+    })
+}
+
+/// Runs RestoreConfig.py through the Python runner and waits for it to finish
+async fn run_restore_container(state: &AppState, args: Vec<String>) -> ApiResult<ScriptRun> {
+    let execution_id = state
+        .python_runner_service
+        .execute_script("RestoreConfig.py", args, HashMap::new(), None, None, ExecutionPriority::High)
+        .await
+        .map_err(|e| ApiError::ExecutionError(format!("Failed to start RestoreConfig.py: {}", e)))?;
+    info!("Restore running in Python runner execution {}", execution_id);
+
+    let deadline = tokio::time::Instant::now() + CONTAINER_RESTORE_TIMEOUT;
+    let execution = loop {
+        let execution = state
+            .python_runner_service
+            .get_execution(&execution_id)
+            .await
+            .map_err(|e| ApiError::ExecutionError(format!("Lost restore execution {}: {}", execution_id, e)))?;
+
+        if !matches!(execution.status, ExecutionStatus::Pending | ExecutionStatus::Running) {
+            break execution;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            let _ = state.python_runner_service.cancel_execution(&execution_id).await;
+            return Err(ApiError::ExecutionError(format!(
+                "Restore execution {} did not finish within {:?}",
+                execution_id, CONTAINER_RESTORE_TIMEOUT
+            )));
+        }
+        tokio::time::sleep(CONTAINER_POLL_INTERVAL).await;
     };
 
-    Ok(Json(RestoreResponse {
-        status: status.into(),
-        message,
-        logs: Some(format!("stdout:\n{}\nstderr:\n{}", stdout, stderr)),
-    }))
+    let stdout = execution.output.unwrap_or_default();
+    let stderr = execution.error.unwrap_or_default();
+    let exit_success = execution.status == ExecutionStatus::Completed && execution.exit_code == Some(0);
+
+    let result = parse_restore_result(&stdout);
+    if result.is_none() {
+        warn!("Restore execution {} printed no result line; treating it as failed", execution_id);
+    }
+    let status = container_restore_status(exit_success, result.as_ref());
+
+    Ok(ScriptRun {
+        status,
+        result,
+        logs: format!("stdout:\n{}\nstderr:\n{}", stdout, stderr),
+    })
+}
+
+// =========================================================================================
+// SECTION 3: SNAPSHOT AND ROLLBACK
+// Pre-restore snapshot via the Python API and automatic rollback on failure
+// =========================================================================================
+
+/// Backs up the device's current configuration via the Python API
+///
+/// Returns the snapshot backup file reported by the Python API.
+async fn capture_snapshot(state: &AppState, payload: &RestoreRequest, credentials: &Credentials) -> ApiResult<String> {
+    info!("Capturing pre-restore snapshot for {}", payload.hostname);
+
+    let response = Client::new()
+        .post(python_api_endpoint(state, SNAPSHOT_PATH))
+        .json(&serde_json::json!({
+            "hostname": payload.hostname,
+            "username": credentials.username,
+            "password": credentials.password(),
+        }))
+        .timeout(SNAPSHOT_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| {
+            error!("Failed to connect to Python API: {}", e);
+            ApiError::InternalError(format!("Pre-restore snapshot failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        error!("Pre-restore snapshot for {} failed with status {}", payload.hostname, status);
+        return Err(ApiError::InternalError(format!("Pre-restore snapshot failed: {}", status)));
+    }
+
+    let result: Value = response.json().await.map_err(|e| {
+        error!("Failed to parse snapshot response: {}", e);
+        ApiError::InternalError("Invalid response from Python API".to_string())
+    })?;
+
+    snapshot_file(&result).ok_or_else(|| {
+        ApiError::InternalError("Pre-restore snapshot did not report a backup file".to_string())
+    })
+}
+
+/// Extracts the backup file from a Python API backup response
+///
+/// Accepts either a `backup_file` string or a `files` array, whose last entry is used.
+fn snapshot_file(result: &Value) -> Option<String> {
+    result
+        .get("backup_file")
+        .and_then(Value::as_str)
+        .or_else(|| result.get("files")?.as_array()?.last()?.as_str())
+        .map(str::to_string)
+}
+
+/// Restores the snapshot after a failed restore, broadcasting rollback job events
+async fn rollback(
+    state: &AppState,
+    payload: &RestoreRequest,
+    credentials: &Credentials,
+    snapshot_file: String,
+) -> RollbackOutcome {
+    let job_id = Uuid::new_v4().to_string();
+    warn!("Restore for {} failed, rolling back to {}", payload.hostname, snapshot_file);
+
+    broadcast_rollback_event(state, &job_id, &payload.hostname, "rollback_started", "in_progress", serde_json::json!({
+        "snapshot_file": snapshot_file,
+        "failed_backup_file": payload.backup_file,
+    }), None).await;
+
+    let outcome = match run_restore_script(state, payload, credentials, &snapshot_file).await {
+        Ok(run) => RollbackOutcome {
+            snapshot_file,
+            status: run.status.into(),
+            result: run.result,
+            logs: Some(run.logs),
+        },
+        Err(e) => RollbackOutcome {
+            snapshot_file,
+            status: "FAILED".into(),
+            result: None,
+            logs: Some(e.to_string()),
+        },
+    };
+
+    let (status, error) = match outcome.status.as_str() {
+        "SUCCESS" => ("completed", None),
+        other => ("failed", Some(format!("Rollback for {} ended with status {}", payload.hostname, other))),
+    };
+    if let Some(error) = &error {
+        report_restore_failure(state, &payload.hostname, error).await;
+    }
+    broadcast_rollback_event(state, &job_id, &payload.hostname, "rollback_completed", status, serde_json::json!({
+        "snapshot_file": outcome.snapshot_file,
+        "rollback_status": outcome.status,
+    }), error).await;
+
+    info!("Rollback for {} finished with status {}", payload.hostname, outcome.status);
+    outcome
+}
+
+/// Reports a failed restore or rollback on the `errors` topic; restores are not tracked jobs
+async fn report_restore_failure(state: &AppState, device: &str, message: &str) {
+    state
+        .websocket_service
+        .report_background_error("restore", None, Some(device), message, false)
+        .await;
+}
+
+async fn broadcast_rollback_event(
+    state: &AppState,
+    job_id: &str,
+    device: &str,
+    event_type: &str,
+    status: &str,
+    data: Value,
+    error: Option<String>,
+) {
+    let event = JobEventPayload {
+        job_id: job_id.to_string(),
+        device: device.to_string(),
+        job_type: "restore".to_string(),
+        event_type: event_type.to_string(),
+        status: status.to_string(),
+        timestamp: Utc::now(),
+        data,
+        error,
+        trace_id: None,
+    };
+
+    if let Err(e) = state.websocket_service.broadcast_job_event(event).await {
+        warn!("Failed to broadcast {} event for {}: {}", event_type, device, e);
+    }
+}
+
+// =========================================================================================
+// SECTION 4: RESULT PARSING
+// Structured result extraction and status mapping
+// =========================================================================================
+
+/// Finds the last stdout line that parses as a RestoreResult JSON object
+fn parse_restore_result(stdout: &str) -> Option<RestoreResult> {
+    stdout
+        .lines()
+        .rev()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{'))
+        .find_map(|line| serde_json::from_str(line).ok())
+}
+
+/// Maps the exit code and structured result to SUCCESS, PARTIAL or FAILED
+///
+/// A restore that committed or changed the device but also reported errors
+/// (or exited non-zero) is PARTIAL. Without a structured result, the exit
+/// code alone decides between SUCCESS and FAILED.
+fn restore_status(exit_success: bool, result: Option<&RestoreResult>) -> &'static str {
+    match result {
+        Some(result) if exit_success && result.errors.is_empty() => "SUCCESS",
+        Some(result) if result.committed || result.changed => "PARTIAL",
+        Some(_) => "FAILED",
+        None if exit_success => "SUCCESS",
+        None => "FAILED",
+    }
+}
+
+/// Like `restore_status`, but a run without a structured result is FAILED
+///
+/// The Python runner reports a clean exit for any script it ran, so only the
+/// result line shows that RestoreConfig.py actually restored the device.
+fn container_restore_status(exit_success: bool, result: Option<&RestoreResult>) -> &'static str {
+    match result {
+        Some(result) => restore_status(exit_success, Some(result)),
+        None => "FAILED",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::websocket::{JobSubscriptionPayload, WsMessage},
+        test_support::TestApp,
+    };
+    use axum::extract::ws::Message;
+
+    fn restore_request(backup_file: &str) -> RestoreRequest {
+        RestoreRequest {
+            hostname: "r1".to_string(),
+            username: Some("netops".to_string()),
+            password: Some("secret".to_string()),
+            backup_file: backup_file.to_string(),
+            rollback_on_failure: false,
+            force: false,
+        }
+    }
+
+    #[test]
+    fn backup_owners_come_from_directory_and_file_name() {
+        assert_eq!(
+            backup_file_owners("r1/20250101_120000_r1_config.xml"),
+            vec!["r1".to_string(), "r1".to_string()]
+        );
+        assert_eq!(backup_file_owners("20250101_120000_core_sw-1_config.set"), vec!["core_sw-1".to_string()]);
+        assert_eq!(backup_file_owners("r2/latest.xml"), vec!["r2".to_string()]);
+        assert!(backup_file_owners("latest.xml").is_empty());
+    }
+
+    #[test]
+    fn container_runs_without_a_result_line_fail() {
+        let simulated = parse_restore_result("Simulated output for RestoreConfig.py");
+        assert!(simulated.is_none());
+        assert_eq!(container_restore_status(true, simulated.as_ref()), "FAILED");
+        assert_eq!(restore_status(true, simulated.as_ref()), "SUCCESS");
+
+        let result = parse_restore_result("connecting\n{\"committed\": true, \"changed\": true}");
+        assert_eq!(container_restore_status(true, result.as_ref()), "SUCCESS");
+    }
+
+    #[tokio::test]
+    async fn failed_restores_roll_back_to_the_snapshot() {
+        let mut app = TestApp::new().await;
+        app.stub_python_api(axum::Router::new().route(
+            "/api/backups/devices",
+            axum::routing::post(|| async { Json(serde_json::json!({ "backup_file": "r1/snapshot.conf" })) }),
+        ))
+        .await;
+        let websocket = &app.state.websocket_service;
+        let (client, _, mut outbound) = websocket.connect_test_client().await;
+        let subscribe = WsMessage::SubscribeToJobs {
+            payload: JobSubscriptionPayload { device_filter: Some("r1".to_string()), job_type_filter: None },
+        };
+        websocket.receive_test_message(client, &subscribe).await.unwrap();
+
+        // RestoreConfig.py is not in the test's working directory, so every run fails
+        let request = RestoreRequest { rollback_on_failure: true, ..restore_request("r1/20250101_120000_r1_config.conf") };
+        let Json(response) = run_restore(State(app.state.clone()), Json(request)).await.unwrap();
+        assert_eq!(response.status, "FAILED");
+        let rollback = response.rollback.unwrap();
+        assert_eq!(rollback.snapshot_file, "r1/snapshot.conf");
+
+        let mut events = Vec::new();
+        while let Ok(Message::Text(text)) = outbound.try_recv() {
+            if let Ok(WsMessage::JobEvent { payload }) = serde_json::from_str(&text) {
+                events.push((payload.event_type, payload.status, payload.data["snapshot_file"].clone()));
+            }
+        }
+        assert_eq!(
+            events,
+            vec![
+                ("rollback_started".to_string(), "in_progress".to_string(), serde_json::json!("r1/snapshot.conf")),
+                ("rollback_completed".to_string(), "failed".to_string(), serde_json::json!("r1/snapshot.conf")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn restores_of_a_locked_device_answer_409() {
+        let app = TestApp::new().await;
+        let _upgrade = app.state.device_lock_service.try_lock("r1", "upgrade", None).await.unwrap();
+
+        let result = run_restore(State(app.state.clone()), Json(restore_request("r1/latest.conf"))).await;
+        assert!(matches!(result, Err(ApiError::Conflict(message)) if message.contains("r1")));
+    }
 }
@@ -1,5 +1,5 @@
 // File Path: src/api/sidebar.rs
-// Version: 1.1.0
+// Version: 1.2.1
 //
 // Description:
 // API handlers for accessing sidebar navigation configurations.
@@ -8,36 +8,168 @@
 // - Loads specific sidebar YAML files using a parameter
 // - Returns structured JSON response
 // - Provides error handling for missing/invalid YAML
+// - Lists every sidebar file with its name, item count and modification time
 //
 // Usage Guide:
 // GET /api/sidebar/{sidebar_id} → returns specific sidebar configuration
+//   `sidebars/<id>.yaml` or, failing that, `sidebars/<id>.yml`; ids are file stems as listed
+// GET /api/sidebars → [{ id, name, item_count, modified, error? }], sorted by id
+//   A sidebar file is either a list of items or a mapping with `name`/`title` and `items`.
+//   When both `<id>.yaml` and `<id>.yml` exist, only the `.yaml` file is listed and served.
+//   Files that cannot be parsed are still listed, with `error` set and no item count.
 //
 // Change Log:
+// - 1.2.1: Sidebars stored as .yml are served; a mapping without `items` is not a sidebar
+// - 1.2.0: Listing all sidebars returns a typed SidebarSummary per file
+// - 1.1.1: Listing all sidebars returns 501 Not Implemented instead of an empty object
 // - 1.1.0: Added parameterized sidebar support
 // - 1.0.0: Initial implementation
 
 use axum::{extract::{State, Path}, response::Json};
+use chrono::{DateTime, Utc};
+use futures_util::{stream, StreamExt};
+use serde::{de::IgnoredAny, Deserialize};
 use serde_json::Value;
+use tokio::fs;
+use tracing::warn;
 
-use crate::{AppState, models::ApiResult};
+use crate::{AppState, models::{ApiError, ApiResult, SidebarSummary}};
+
+/// Data subdirectory holding the sidebar files
+const SIDEBAR_DIR: &str = "sidebars";
+
+/// The parts of a sidebar file read for the listing; item contents are skipped
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SidebarOutline {
+    Items(Vec<IgnoredAny>),
+    Document {
+        name: Option<String>,
+        title: Option<String>,
+        items: Vec<IgnoredAny>,
+    },
+}
 
 /// Handler to return a specific sidebar configuration
 pub async fn get_sidebar(
     State(state): State<AppState>,
     Path(sidebar_id): Path<String>,
 ) -> ApiResult<Json<Value>> {
-    // Load specific sidebar YAML from shared/data/sidebars
+    // Load specific sidebar YAML from shared/data/sidebars, whichever extension it has
+    let file_name = sidebar_files(&state)
+        .await?
+        .into_iter()
+        .find(|path| path.file_stem().and_then(|stem| stem.to_str()) == Some(sidebar_id.as_str()))
+        .and_then(|path| path.file_name().and_then(|name| name.to_str()).map(str::to_string))
+        .ok_or_else(|| ApiError::NotFound(format!("Sidebar '{}' not found", sidebar_id)))?;
     let data = state.yaml_service
-        .get_yaml_data(&format!("sidebars/{}", sidebar_id), None)
+        .get_yaml_data(&format!("{}/{}", SIDEBAR_DIR, sidebar_id), Some(&format!("{}/{}", SIDEBAR_DIR, file_name)))
         .await?;
-    
+
     Ok(Json(data))
 }
 
-/// Handler to return all available sidebar configurations (optional)
-/// Currently returns empty response - implement later if needed
-pub async fn get_all_sidebars() -> ApiResult<Json<Value>> {
-    // This would require implementing a method to list available sidebar files
-    // For now, we'll return an empty object
-    Ok(Json(serde_json::json!({})))
+/// Handler to return a summary of every sidebar configuration
+pub async fn get_all_sidebars(State(state): State<AppState>) -> ApiResult<Json<Vec<SidebarSummary>>> {
+    let files = sidebar_files(&state).await?;
+
+    let mut sidebars: Vec<SidebarSummary> = stream::iter(files)
+        .map(|path| async move {
+            let id = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_string();
+            let modified = fs::metadata(&path)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(DateTime::<Utc>::from);
+            let content = fs::read_to_string(&path).await.map_err(|e| format!("Failed to read file: {}", e));
+            summarize_sidebar(id, modified, content)
+        })
+        .buffer_unordered(state.yaml_service.scan_concurrency())
+        .collect()
+        .await;
+    sidebars.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(Json(sidebars))
+}
+
+/// Sidebar files sorted by name, keeping only the `.yaml` file when an id has both extensions
+async fn sidebar_files(state: &AppState) -> ApiResult<Vec<std::path::PathBuf>> {
+    let mut files = state.yaml_service.data_files(SIDEBAR_DIR).await?;
+    // `<id>.yaml` sorts directly before `<id>.yml`
+    files.dedup_by(|later, earlier| later.file_stem() == earlier.file_stem());
+    Ok(files)
+}
+
+/// Builds a file's summary from its content, or from the reason it could not be read
+fn summarize_sidebar(id: String, modified: Option<DateTime<Utc>>, content: Result<String, String>) -> SidebarSummary {
+    let outline = content
+        .and_then(|content| {
+            serde_yaml::from_str::<serde_yaml::Value>(&content).map_err(|e| format!("YAML parse error: {}", e))
+        })
+        .and_then(|document| {
+            serde_yaml::from_value::<SidebarOutline>(document)
+                .map_err(|_| "Not a sidebar: expected a list of items or a mapping with `items`".to_string())
+        });
+
+    let (name, item_count, error) = match outline {
+        Ok(SidebarOutline::Items(items)) => (None, Some(items.len()), None),
+        Ok(SidebarOutline::Document { name, title, items }) => (name.or(title), Some(items.len()), None),
+        Err(error) => {
+            warn!("Sidebar '{}' could not be summarized: {}", id, error);
+            (None, None, Some(error))
+        }
+    };
+
+    SidebarSummary {
+        name: name.unwrap_or_else(|| id.clone()),
+        id,
+        item_count,
+        modified,
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    #[test]
+    fn sidebars_are_summarized_from_either_shape() {
+        let list = summarize_sidebar("backup".to_string(), None, Ok("- id: a\n- id: b\n".to_string()));
+        assert_eq!((list.name.as_str(), list.item_count), ("backup", Some(2)));
+
+        let document = "title: Operations\nitems:\n  - id: a\n";
+        let document = summarize_sidebar("ops".to_string(), None, Ok(document.to_string()));
+        assert_eq!((document.name.as_str(), document.item_count), ("Operations", Some(1)));
+
+        let broken = summarize_sidebar("broken".to_string(), None, Ok("items: [unclosed".to_string()));
+        assert_eq!(broken.item_count, None);
+        assert!(broken.error.is_some());
+
+        let itemless = summarize_sidebar("settings".to_string(), None, Ok("title: Settings\n".to_string()));
+        assert_eq!(itemless.item_count, None);
+        assert!(itemless.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn listed_sidebars_can_be_fetched_by_id() {
+        let app = TestApp::new().await;
+        app.write_data("sidebars/ops.yml", "- id: a\n").await;
+        app.write_data("sidebars/lab.yaml", "- id: yaml\n").await;
+        app.write_data("sidebars/lab.yml", "- id: yml\n").await;
+
+        let Json(listed) = get_all_sidebars(State(app.state.clone())).await.unwrap();
+        let ids: Vec<&str> = listed.iter().map(|sidebar| sidebar.id.as_str()).collect();
+        assert_eq!(ids, ["lab", "ops"]);
+
+        let Json(ops) = get_sidebar(State(app.state.clone()), Path("ops".to_string())).await.unwrap();
+        assert_eq!(ops, serde_json::json!([{ "id": "a" }]));
+        let Json(lab) = get_sidebar(State(app.state.clone()), Path("lab".to_string())).await.unwrap();
+        assert_eq!(lab, serde_json::json!([{ "id": "yaml" }]));
+        assert!(matches!(
+            get_sidebar(State(app.state.clone()), Path("missing".to_string())).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
 }
@@ -0,0 +1,172 @@
+// =========================================================================================
+// File Path: src/api/upgrade.rs
+// Version: 1.3.0
+//
+// Description:
+// API handlers for firmware/OS upgrades. Forwards the upgrade to the Python API in the
+// background and reports progress through `job_type: "upgrade"` job events, like backups.
+//
+// Key Features:
+// - POST /api/upgrade/run starts an upgrade and returns its job id immediately
+// - Only one upgrade or restore per device at a time (409 Conflict while one is running)
+// - OPERATION_START / OPERATION_COMPLETE job events, tracked and cancellable as a job
+// - Longer default timeout than backups (UPGRADE_TIMEOUT_SECS, default 1800)
+// - A panicking upgrade task is reported on the `errors` topic and fails the job
+//
+// Usage Guide:
+// POST /api/upgrade/run → { hostname, username?, password?, image, inventory_file?, timeout_secs? }
+// Omitted credentials come from the inventory or DEVICE_USERNAME / DEVICE_PASSWORD.
+// Subscribe to job events for the returned job_id to follow the upgrade.
+//
+// Change Log:
+// - 1.3.0: The job is launched through device_jobs, shared with backups
+// - 1.2.0: The device lock records the upgrade job id, shown by GET /api/devices/locks
+// - 1.1.0: Credentials are resolved by the credentials service; request username/password are optional overrides
+// - 1.0.0: Initial implementation
+// =========================================================================================
+
+use axum::{extract::State, response::Json};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::time::Duration;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    config::env_or,
+    api::device_jobs::{launch_device_job, python_api_endpoint, DeviceJob},
+    models::{ApiError, ApiResult},
+    AppState,
+};
+
+/// Python API path that performs the upgrade
+const UPGRADE_PATH: &str = "/api/upgrade/run";
+
+/// Upgrade timeout when UPGRADE_TIMEOUT_SECS is unset or invalid
+const DEFAULT_UPGRADE_TIMEOUT_SECS: u64 = 1800;
+
+/// Reads the default upgrade timeout from UPGRADE_TIMEOUT_SECS
+fn default_timeout() -> Duration {
+    Duration::from_secs(env_or("UPGRADE_TIMEOUT_SECS", DEFAULT_UPGRADE_TIMEOUT_SECS).max(1))
+}
+
+// =========================================================================================
+// SECTION 1: REQUEST STRUCTS
+// =========================================================================================
+
+#[derive(Deserialize)]
+pub struct UpgradeRequest {
+    pub hostname: String,
+    /// Overrides the inventory and environment login; see CredentialsService
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Firmware/OS image to install, as known to the Python API
+    pub image: String,
+    #[serde(default)]
+    pub inventory_file: Option<String>,
+    /// Overrides UPGRADE_TIMEOUT_SECS for this upgrade
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+// =========================================================================================
+// SECTION 2: HANDLER IMPLEMENTATION
+// =========================================================================================
+
+pub async fn run_upgrade(
+    State(state): State<AppState>,
+    Json(payload): Json<UpgradeRequest>,
+) -> ApiResult<Json<Value>> {
+    for (field, value) in [
+        ("hostname", &payload.hostname),
+        ("image", &payload.image),
+    ] {
+        if value.trim().is_empty() {
+            return Err(ApiError::BadRequest(format!("{} cannot be empty", field)));
+        }
+    }
+    let credentials = state
+        .credentials_service
+        .resolve(&payload.hostname, payload.username.as_deref(), payload.password.as_deref())
+        .await?;
+
+    let job_id = Uuid::new_v4().to_string();
+    let device_lock = state
+        .device_lock_service
+        .try_lock(&payload.hostname, "upgrade", Some(&job_id))
+        .await
+        .ok_or_else(|| ApiError::Conflict(format!(
+            "Another upgrade or restore is already running for {}",
+            payload.hostname
+        )))?;
+
+    let device = payload.hostname.clone();
+    let timeout = payload
+        .timeout_secs
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or_else(default_timeout);
+    info!("Upgrade job {} starting for {} (image {}, {})", job_id, device, payload.image, credentials);
+
+    let job = DeviceJob {
+        job_id: job_id.clone(),
+        job_type: "upgrade",
+        device: device.clone(),
+        url: python_api_endpoint(&state, UPGRADE_PATH),
+        request: serde_json::json!({
+            "hostname": payload.hostname,
+            "username": credentials.username,
+            "password": credentials.password(),
+            "image": payload.image,
+            "inventory_file": payload.inventory_file.clone().unwrap_or_default(),
+        }),
+        timeout,
+        pooled: false,
+        context: Map::from_iter([
+            ("image".to_string(), Value::String(payload.image.clone())),
+            ("timeout_secs".to_string(), Value::from(timeout.as_secs())),
+        ]),
+    };
+    // The device lock is held by the job until it finishes, so restores and upgrades never overlap
+    launch_device_job(&state, job, device_lock).await;
+
+    Ok(Json(serde_json::json!({
+        "status": "started",
+        "message": "Upgrade process initiated successfully",
+        "job_id": job_id,
+        "device_id": device,
+        "timeout_secs": timeout.as_secs(),
+        "timestamp": Utc::now().to_rfc3339()
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    fn upgrade_request(image: &str) -> UpgradeRequest {
+        UpgradeRequest {
+            hostname: "r1".to_string(),
+            username: Some("netops".to_string()),
+            password: Some("secret".to_string()),
+            image: image.to_string(),
+            inventory_file: None,
+            timeout_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn upgrades_need_an_image_and_an_unlocked_device() {
+        let app = TestApp::new().await;
+        let result = run_upgrade(State(app.state.clone()), Json(upgrade_request(" "))).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(message)) if message == "image cannot be empty"));
+
+        let _restore = app.state.device_lock_service.try_lock("r1", "restore", None).await.unwrap();
+        let result = run_upgrade(State(app.state.clone()), Json(upgrade_request("junos-23.4.tgz"))).await;
+        assert!(matches!(result, Err(ApiError::Conflict(message)) if message.contains("r1")));
+    }
+}
@@ -9,26 +9,26 @@
 use axum::{
     extract::{
         ws::WebSocketUpgrade,
-        State,
+        Path, Query, State,
         ConnectInfo,
     },
-    response::Response,
+    http::HeaderName,
+    response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use serde::Deserialize;
 use std::{net::SocketAddr, sync::Arc};
 use tracing::{error, info, debug, warn};
-use tokio::task;
 use uuid::Uuid;
 use chrono::Utc;
-use std::time::Duration;
-use tokio::time::sleep;
-use reqwest::Client;
 
 use crate::{
+    api::device_jobs::launch_backup,
+    config::env_or,
+    middleware::{admin::AdminAccess, auth::Principal},
     models::{
-        websocket::{SubscriptionTopic, WsMessage, JobEventPayload},
+        websocket::{ConnectionDetails, DebugLevel, SubscriptionTopic, WsMessage, JobEventPayload},
         ApiError,
     },
     AppState,
@@ -43,7 +43,12 @@ use crate::{
 /// Routes:
 /// - /ws: WebSocket connection endpoint
 /// - /status: Service status check
-/// - /connections: Active connections list
+/// - /connections: Active connections list (?limit, ?offset; shared page-size bounds; ?envelope=true for an object)
+/// - /connections/:connection_id: One connection's details, with GeoIP country/ASN when configured
+/// - /api/ws/config: Effective WebSocket configuration (admin)
+/// - /api/ws/drain, /api/ws/undrain: Stop/resume accepting new connections (admin)
+/// - /api/ws/subscriptions: Subscribers grouped by topic (admin)
+/// - /api/ws/debug-logs: Debug log buffer, filtered and paged (admin)
 /// - /broadcast: Generic message broadcasting
 /// - /jobs/broadcast: Job event broadcasting
 /// - /api/backups/devices: Backup API endpoint (frontend-facing)
@@ -52,6 +57,12 @@ pub fn websocket_routes() -> Router<AppState> {
         .route("/ws", get(ws_handler))
         .route("/status", get(get_status))
         .route("/connections", get(get_connections))
+        .route("/connections/:connection_id", get(get_connection))
+        .route("/api/ws/config", get(get_config))
+        .route("/api/ws/subscriptions", get(get_subscriptions))
+        .route("/api/ws/debug-logs", get(get_debug_logs))
+        .route("/api/ws/drain", post(drain_handler))
+        .route("/api/ws/undrain", post(undrain_handler))
         .route("/broadcast", post(broadcast_handler))
         .route("/jobs/broadcast", post(broadcast_job_event_handler))
         .route("/api/backups/devices", post(backup_handler))
@@ -68,12 +79,33 @@ pub fn websocket_routes() -> Router<AppState> {
 /// - Logs connection attempts
 /// - Handles WebSocket protocol upgrade
 /// - Delegates connection management to WebSocketService
+/// - Refuses upgrades with 503 while the service is draining
+/// - With WS_REQUIRE_AUTH=true, refuses upgrades without an authenticated principal (401);
+///   browsers pass their key as `?api_key=`
 async fn ws_handler(
     ws: WebSocketUpgrade,
     ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    principal: Option<Extension<Principal>>,
     State(state): State<AppState>,
 ) -> Response {
     info!("WebSocket connection attempt from: {}", remote_addr);
+
+    let require_auth = env_or("WS_REQUIRE_AUTH", false);
+    match &principal {
+        Some(Extension(principal)) => info!("WebSocket connection from {} authenticated as {}", remote_addr, principal.id),
+        None if require_auth => {
+            warn!("Refusing unauthenticated WebSocket connection from {}", remote_addr);
+            return ApiError::Unauthorized("Authentication required for WebSocket connections".to_string())
+                .into_response();
+        }
+        None => {}
+    }
+
+    if state.websocket_service.is_draining() {
+        warn!("Refusing WebSocket connection from {}: service is draining", remote_addr);
+        return ApiError::ServiceUnavailable("Server is draining connections for maintenance".to_string())
+            .into_response();
+    }
     
     ws.on_upgrade(move |socket| async move {
         info!("WebSocket upgrade successful for: {}", remote_addr);
@@ -85,6 +117,33 @@ async fn ws_handler(
     })
 }
 
+/// Handler for starting connection draining
+///
+/// New WebSocket upgrades get 503 until /api/ws/undrain; open connections are kept.
+async fn drain_handler(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+) -> Json<serde_json::Value> {
+    let was_draining = state.websocket_service.set_draining(true);
+    Json(serde_json::json!({
+        "draining": true,
+        "was_draining": was_draining,
+        "active_connections": state.websocket_service.get_active_connections().await.len(),
+    }))
+}
+
+/// Handler for resuming new WebSocket connections after draining
+async fn undrain_handler(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+) -> Json<serde_json::Value> {
+    let was_draining = state.websocket_service.set_draining(false);
+    Json(serde_json::json!({
+        "draining": false,
+        "was_draining": was_draining,
+    }))
+}
+
 // =================================================================================================
 // SECTION: SERVICE STATUS & MONITORING
 // =================================================================================================
@@ -108,16 +167,141 @@ async fn get_status(
 /// Handler for getting active WebSocket connections
 /// 
 /// Returns:
-/// - List of active connections
-/// - Connection metadata
+/// - List of active connections, longest-connected first, as a plain array; the
+///   count before paging is in X-Total-Count
+/// - `{ connections, total, limit, offset }` instead with `?envelope=true`
 async fn get_connections(
     State(state): State<AppState>,
-) -> Result<Json<serde_json::Value>, ApiError> {
+    Query(params): Query<PageParams>,
+) -> Response {
     info!("Active connections request received");
     
-    let connections = state.websocket_service.get_active_connections().await;
-    info!("Active connections retrieved: {}", connections.len());
-    Ok(Json(serde_json::json!(connections)))
+    let mut connections = state.websocket_service.get_active_connections().await;
+    connections.sort_by_key(|c| std::cmp::Reverse(c.connected_duration));
+    let page = state.pagination.paginate(connections, params.limit, params.offset);
+    info!("Active connections retrieved: {} of {}", page.items.len(), page.total);
+
+    let total = [(HeaderName::from_static("x-total-count"), page.total.to_string())];
+    if !params.envelope {
+        // Existing clients read a plain array
+        return (total, Json(page.items)).into_response();
+    }
+    (
+        total,
+        Json(serde_json::json!({
+            "connections": page.items,
+            "total": page.total,
+            "limit": page.limit,
+            "offset": page.offset,
+        })),
+    )
+        .into_response()
+}
+
+/// Paging parameters for the connections list
+#[derive(Deserialize, Debug)]
+struct PageParams {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    /// Return the page in an object with its bounds instead of a plain array
+    #[serde(default)]
+    envelope: bool,
+}
+
+/// Handler for searching the debug log buffer (admin)
+///
+/// Returns:
+/// - Matching entries, newest first, paged like the connections list
+/// - `total` matches before paging
+async fn get_debug_logs(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+    Query(params): Query<DebugLogParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let logs = state
+        .websocket_service
+        .search_debug_logs(params.min_level, params.component.as_deref(), params.q.as_deref())
+        .await;
+    let page = state.pagination.paginate(logs, params.limit, params.offset);
+    debug!("Debug logs retrieved: {} of {}", page.items.len(), page.total);
+    Ok(Json(serde_json::json!({
+        "logs": page.items,
+        "total": page.total,
+        "limit": page.limit,
+        "offset": page.offset,
+    })))
+}
+
+/// Filters and paging for the debug log search
+#[derive(Deserialize, Debug)]
+struct DebugLogParams {
+    /// Lowest level to include (verbose, debug, info, warn, error)
+    min_level: Option<DebugLevel>,
+    /// Exact component name, case-insensitive
+    component: Option<String>,
+    /// Free text matched against message and component
+    q: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+/// Handler for getting one connection's details
+///
+/// Returns:
+/// - Address, connect time and user agent
+/// - `geo` (country/ASN) when GeoIP databases are configured
+async fn get_connection(
+    State(state): State<AppState>,
+    Path(connection_id): Path<Uuid>,
+) -> Result<Json<ConnectionDetails>, ApiError> {
+    state
+        .websocket_service
+        .connection_details(connection_id)
+        .await
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Connection '{}' not found", connection_id)))
+}
+
+/// Handler for listing subscribers grouped by topic
+///
+/// Returns:
+/// - Map of topic → connections (id and IP) subscribed to it
+/// - Job subscriptions under `job_events`, with their filters
+async fn get_subscriptions(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let topics = state.websocket_service.subscriptions_by_topic().await;
+    Ok(Json(serde_json::json!({
+        "topic_count": topics.len(),
+        "topics": topics,
+    })))
+}
+
+/// Handler for getting the effective WebSocket configuration
+///
+/// Returns:
+/// - Timing knobs in seconds (ping interval, connection timeout)
+/// - Connection and message size limits
+/// - Debug flags
+async fn get_config(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("WebSocket config request received");
+
+    let config = state.websocket_service.get_config().await;
+    Ok(Json(serde_json::json!({
+        "ping_interval_secs": config.ping_interval.as_secs(),
+        "connection_timeout_secs": config.connection_timeout.as_secs(),
+        "max_connections": config.max_connections,
+        "max_message_size": config.max_message_size,
+        "topic_message_size_limits": config.topic_message_size_limits,
+        "channel_capacity": config.channel_capacity,
+        "congested_queue_depth": config.congestion_threshold(),
+        "broadcast_rate_limit": config.broadcast_rate_limit,
+        "debug": config.debug,
+    })))
 }
 
 // =================================================================================================
@@ -127,7 +311,8 @@ async fn get_connections(
 /// Request body for broadcasting messages
 #[derive(Deserialize, Debug)]
 pub struct BroadcastPayload {
-    topic: SubscriptionTopic,
+    /// Topic string (e.g. "navigation", "data:reports"); unknown topics are rejected
+    topic: String,
     message: String,
 }
 
@@ -143,10 +328,15 @@ async fn broadcast_handler(
 ) -> Result<Json<serde_json::Value>, ApiError> {
     info!(
         "Broadcast request received for topic: {}, message: {}",
-        payload.topic.to_string(),
+        payload.topic,
         payload.message
     );
 
+    ensure_broadcast_admitted(&state).await?;
+
+    // Strict parse so a typo can't fall back to broadcasting to everyone
+    let topic: SubscriptionTopic = payload.topic.parse().map_err(ApiError::BadRequest)?;
+
     // Validate message
     if payload.message.is_empty() {
         return Err(ApiError::WebSocketError("Message cannot be empty".to_string()));
@@ -165,17 +355,27 @@ async fn broadcast_handler(
 
     state
         .websocket_service
-        .broadcast_to_topic(&payload.topic, ws_message)
+        .broadcast_to_topic(&topic, ws_message)
         .await?;
 
-    info!("Broadcast successful for topic: {}", payload.topic.to_string());
+    info!("Broadcast successful for topic: {}", topic.to_string());
     Ok(Json(serde_json::json!({
         "status": "success",
         "message": "Broadcast sent successfully",
-        "topic": payload.topic.to_string()
+        "topic": topic.to_string()
     })))
 }
 
+/// Rejects a REST broadcast with 429 once the global broadcast rate limit is exhausted
+async fn ensure_broadcast_admitted(state: &AppState) -> Result<(), ApiError> {
+    if state.websocket_service.try_admit_broadcast().await {
+        Ok(())
+    } else {
+        warn!("Broadcast rejected: global broadcast rate limit exceeded");
+        Err(ApiError::TooManyRequests("Broadcast rate limit exceeded".to_string()))
+    }
+}
+
 // =================================================================================================
 // SECTION: JOB EVENT BROADCASTING
 // =================================================================================================
@@ -190,6 +390,9 @@ pub struct JobEventBroadcastPayload {
     status: String,
     data: serde_json::Value,
     error: Option<String>,
+    /// Trace id of the Python execution that produced the event (its XAOS_TRACE_ID)
+    #[serde(default)]
+    trace_id: Option<String>,
 }
 
 /// Handler for broadcasting job events for real-time device operation updates
@@ -198,10 +401,21 @@ pub struct JobEventBroadcastPayload {
 /// - Receives job progress events from Python API
 /// - Broadcasts them to all connected WebSocket clients
 /// - Supports real-time backup/restore progress updates
+/// - Rejects job types, event types and statuses outside the known vocabulary with 400
+/// - Events carrying a `trace_id` are recorded on the matching Python execution
 async fn broadcast_job_event_handler(
     State(state): State<AppState>,
     Json(payload): Json<JobEventBroadcastPayload>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    ensure_broadcast_admitted(&state).await?;
+
+    if let Err(message) =
+        JobEventPayload::validate_vocabulary(&payload.job_type, &payload.event_type, &payload.status)
+    {
+        warn!("Rejected job event for job {}: {}", payload.job_id, message);
+        return Err(ApiError::BadRequest(message));
+    }
+
     let job_id_clone = payload.job_id.clone();
     let device_clone = payload.device.clone();
     
@@ -219,8 +433,16 @@ async fn broadcast_job_event_handler(
         timestamp: Utc::now(),
         data: payload.data,
         error: payload.error,
+        trace_id: payload.trace_id,
     };
 
+    if job_event.trace_id.is_some() && !state.python_runner_service.record_trace_event(&job_event).await {
+        warn!(
+            "Job event for job {} has trace id {:?} matching no execution",
+            job_id_clone, job_event.trace_id
+        );
+    }
+
     state
         .websocket_service
         .broadcast_job_event(job_event)
@@ -249,8 +471,14 @@ pub struct StartBackupPayload {
     device_id: String,
     hostname: Option<String>,
     inventory_file: Option<String>,
-    username: String,
-    password: String,
+    /// Overrides the inventory and environment login; see CredentialsService
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    /// Skip the per-device backup cooldown
+    #[serde(default)]
+    force: bool,
 }
 
 /// Main backup handler that coordinates between frontend and Python API
@@ -258,10 +486,9 @@ pub struct StartBackupPayload {
 /// This handler:
 /// 1. Receives backup requests from frontend
 /// 2. Validates input parameters
-/// 3. Generates unique job ID
-/// 4. Forwards request to Python API
-/// 5. Monitors progress via WebSocket events
-/// 6. Returns immediate response to frontend
+/// 3. Launches the backup as a device job (see `device_jobs`), which forwards it to the
+///    Python API and reports progress via WebSocket job events
+/// 4. Returns immediate response to frontend
 async fn backup_handler(
     State(state): State<AppState>,
     Json(payload): Json<StartBackupPayload>,
@@ -274,117 +501,28 @@ async fn backup_handler(
     if payload.device_id.trim().is_empty() {
         return Err(ApiError::WebSocketError("Device ID cannot be empty".to_string()));
     }
-    if payload.username.trim().is_empty() {
-        return Err(ApiError::WebSocketError("Username cannot be empty".to_string()));
-    }
-    if payload.password.trim().is_empty() {
-        return Err(ApiError::WebSocketError("Password cannot be empty".to_string()));
-    }
+    let hostname = payload.hostname.clone().unwrap_or_else(|| payload.device_id.clone());
+    let credentials = state
+        .credentials_service
+        .resolve(&hostname, payload.username.as_deref(), payload.password.as_deref())
+        .await?;
+
+    // Keyed by the device actually backed up, like POST /api/backups/devices
+    state.backup_pool.start_device_backup(&hostname, payload.force)?;
 
     // =========================================================================
-    // STEP 2: JOB INITIALIZATION
+    // STEP 2: LAUNCH THE BACKUP JOB
     // =========================================================================
+    // Queued, tracked and reported like every other backup
     let job_id = Uuid::new_v4().to_string();
-    let service_clone = Arc::clone(&state.websocket_service);
-    let device_id_clone = payload.device_id.clone();
-
     info!("✅ Generated job ID: {}", job_id);
+    info!("📦 Backing up {} ({})", hostname, credentials);
 
-    // =========================================================================
-    // STEP 3: SEND START EVENT
-    // =========================================================================
-    let start_event = JobEventPayload {
-        job_id: job_id.clone(),
-        device: device_id_clone.clone(),
-        job_type: "backup".to_string(),
-        event_type: "OPERATION_START".to_string(),
-        status: "in_progress".to_string(),
-        data: serde_json::json!({
-            "message": "Backup process initiated successfully",
-            "step": 0,
-            "total_steps": 12 // Estimated total steps for backup process
-        }),
-        error: None,
-        timestamp: Utc::now(),
-    };
-    
-    service_clone.broadcast_job_event(start_event).await?;
-    info!("📡 Start event broadcast for job: {}", job_id);
-
-    // =========================================================================
-    // STEP 4: FORWARD TO PYTHON API (BACKGROUND TASK)
-    // =========================================================================
-    task::spawn(async move {
-        info!("🏃 Starting real backup process for job: {}", job_id);
-        
-        let client = Client::new();
-        let python_api_url = "http://python_runner:8000/api/backups/devices";
-        
-        // Prepare request for Python API
-        let backup_request = serde_json::json!({
-            "hostname": payload.hostname.unwrap_or_else(|| payload.device_id.clone()),
-            "inventory_file": payload.inventory_file.unwrap_or_default(),
-            "username": payload.username,
-            "password": payload.password
-        });
-
-        info!("🔗 Forwarding to Python API: {}", python_api_url);
-        info!("📦 Payload: {:?}", backup_request);
-
-        match client.post(python_api_url)
-            .json(&backup_request)
-            .timeout(Duration::from_secs(120)) // 2-minute timeout
-            .send()
-            .await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    match response.json::<serde_json::Value>().await {
-                        Ok(result) => {
-                            info!("✅ Python API response: {:?}", result);
-                            
-                            // Send completion event
-                            let complete_event = JobEventPayload {
-                                job_id: job_id.clone(),
-                                device: device_id_clone.clone(),
-                                job_type: "backup".to_string(),
-                                event_type: "OPERATION_COMPLETE".to_string(),
-                                status: "completed".to_string(),
-                                data: serde_json::json!({
-                                    "message": "Backup completed successfully via Python API",
-                                    "result": result,
-                                    "step": 12,
-                                    "total_steps": 12
-                                }),
-                                error: None,
-                                timestamp: Utc::now(),
-                            };
-                            
-                            service_clone.broadcast_job_event(complete_event).await.ok();
-                            info!("🎉 Backup completed successfully for job: {}", job_id);
-                        }
-                        Err(e) => {
-                            error!("❌ Failed to parse Python API response: {}", e);
-                            send_error_event(&service_clone, &job_id, &device_id_clone, 
-                                &format!("Failed to parse Python API response: {}", e)).await;
-                        }
-                    }
-                } else {
-                    let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-                    error!("❌ Python API returned error: HTTP {}", response.status());
-                    send_error_event(&service_clone, &job_id, &device_id_clone, 
-                        &format!("Python API error: HTTP {} - {}", response.status(), error_text)).await;
-                }
-            }
-            Err(e) => {
-                error!("❌ Failed to call Python API: {}", e);
-                send_error_event(&service_clone, &job_id, &device_id_clone, 
-                    &format!("Failed to connect to Python API: {}", e)).await;
-            }
-        }
-    });
+    let context = serde_json::Map::from_iter([("device_id".to_string(), serde_json::json!(payload.device_id))]);
+    let launch = launch_backup(&state, &job_id, &hostname, &credentials, payload.inventory_file, context).await;
 
     // =========================================================================
-    // STEP 5: RETURN IMMEDIATE RESPONSE TO FRONTEND
+    // STEP 3: RETURN IMMEDIATE RESPONSE TO FRONTEND
     // =========================================================================
     info!("📤 Returning immediate response for job: {}", job_id);
 
@@ -393,37 +531,142 @@ async fn backup_handler(
         "message": "Backup process initiated successfully",
         "job_id": job_id,
         "device_id": payload.device_id,
+        "queue_position": launch.queue_position,
         "timestamp": Utc::now().to_rfc3339()
     })))
 }
 
-// =================================================================================================
-// SECTION: HELPER FUNCTIONS
-// =================================================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{services::BackupPool, test_support::TestApp};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn backup_cooldown_is_keyed_by_hostname() {
+        let mut app = TestApp::new().await;
+        app.state.backup_pool = Arc::new(BackupPool::new(1).with_device_cooldown(Duration::from_secs(60)));
+        app.state.backup_pool.start_device_backup("r1", false).unwrap();
+
+        let payload = StartBackupPayload {
+            device_id: "row-7".to_string(),
+            hostname: Some("r1".to_string()),
+            inventory_file: None,
+            username: Some("netops".to_string()),
+            password: Some("secret".to_string()),
+            force: false,
+        };
+        let result = backup_handler(State(app.state.clone()), Json(payload)).await;
+        assert!(matches!(result, Err(ApiError::TooManyRequests(_))));
+    }
 
-/// Helper function to send error events via WebSocket
-async fn send_error_event(
-    service: &Arc<crate::websocket::WebSocketService>,
-    job_id: &str,
-    device_id: &str,
-    error_msg: &str,
-) {
-    let error_event = JobEventPayload {
-        job_id: job_id.to_string(),
-        device: device_id.to_string(),
-        job_type: "backup".to_string(),
-        event_type: "OPERATION_COMPLETE".to_string(),
-        status: "failed".to_string(),
-        data: serde_json::json!({
-            "message": "Backup process failed",
-            "step": 0,
-            "total_steps": 12
-        }),
-        error: Some(error_msg.to_string()),
-        timestamp: Utc::now(),
-    };
-    
-    if let Err(e) = service.broadcast_job_event(error_event).await {
-        error!("❌ Failed to send error event: {}", e);
+    #[tokio::test]
+    async fn connections_are_a_plain_array_unless_enveloped() {
+        let app = TestApp::new().await;
+        let list = |envelope: bool| PageParams { limit: Some(10), offset: None, envelope };
+
+        let response = get_connections(State(app.state.clone()), Query(list(false))).await;
+        assert_eq!(response.headers()["x-total-count"], "0");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(), serde_json::json!([]));
+
+        let response = get_connections(State(app.state.clone()), Query(list(true))).await;
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+            serde_json::json!({ "connections": [], "total": 0, "limit": 10, "offset": 0 })
+        );
+    }
+
+    #[tokio::test]
+    async fn broadcasts_to_unknown_topics_answer_400() {
+        let app = TestApp::new().await;
+        let broadcast = |topic: &str| {
+            let payload = BroadcastPayload { topic: topic.to_string(), message: "reload".to_string() };
+            broadcast_handler(State(app.state.clone()), Json(payload))
+        };
+
+        assert!(matches!(broadcast("navigaton").await, Err(ApiError::BadRequest(message)) if message.contains("navigaton")));
+        assert!(matches!(broadcast("jobs").await, Err(ApiError::BadRequest(_))));
+
+        let Json(sent) = broadcast("data:reports").await.unwrap();
+        assert_eq!(sent["topic"], "data:reports");
+    }
+
+    #[tokio::test]
+    async fn config_reports_the_effective_congestion_threshold() {
+        use crate::{models::websocket::WsConfig, services::{WebSocketService, WebhookService}};
+
+        let mut app = TestApp::new().await;
+        let config = WsConfig { channel_capacity: 8, congested_queue_depth: 20, ..WsConfig::default() };
+        app.state.websocket_service = Arc::new(WebSocketService::new(Some(config), Arc::new(WebhookService::new(None))));
+
+        let Json(config) = get_config(AdminAccess, State(app.state.clone())).await.unwrap();
+        assert_eq!(config["channel_capacity"], 8);
+        assert_eq!(config["congested_queue_depth"], 8);
+        assert!(config["ping_interval_secs"].is_u64());
+    }
+
+    #[tokio::test]
+    async fn job_events_outside_the_vocabulary_answer_400() {
+        let app = TestApp::new().await;
+        let event = |job_type: &str, event_type: &str, status: &str| JobEventBroadcastPayload {
+            job_id: "job-1".to_string(),
+            device: "r1".to_string(),
+            job_type: job_type.to_string(),
+            event_type: event_type.to_string(),
+            status: status.to_string(),
+            data: serde_json::json!({}),
+            error: None,
+            trace_id: None,
+        };
+        let broadcast = |payload| broadcast_job_event_handler(State(app.state.clone()), Json(payload));
+
+        let result = broadcast(event("backups", "OPERATION_START", "done")).await;
+        let Err(ApiError::BadRequest(message)) = result else { panic!("expected 400, got {:?}", result) };
+        assert!(message.contains("job_type 'backups'") && message.contains("status 'done'"), "{}", message);
+        assert!(!message.contains("event_type"), "{}", message);
+
+        let Json(sent) = broadcast(event("backup", "OPERATION_START", "in_progress")).await.unwrap();
+        assert_eq!(sent["job_id"], "job-1");
+    }
+
+    #[tokio::test]
+    async fn draining_toggles_and_reports_the_previous_state() {
+        let app = TestApp::new().await;
+
+        let Json(drained) = drain_handler(AdminAccess, State(app.state.clone())).await;
+        assert_eq!((drained["draining"].clone(), drained["was_draining"].clone()), (true.into(), false.into()));
+        assert!(app.state.websocket_service.is_draining());
+
+        let Json(undrained) = undrain_handler(AdminAccess, State(app.state.clone())).await;
+        assert_eq!(undrained["was_draining"], true);
+        assert!(!app.state.websocket_service.is_draining());
+    }
+
+    #[tokio::test]
+    async fn subscriptions_are_grouped_by_topic_with_job_filters() {
+        use crate::models::websocket::{JobSubscriptionPayload, SubscribePayload};
+
+        let app = TestApp::new().await;
+        let service = &app.state.websocket_service;
+        let (first, _, _first_outbound) = service.connect_test_client().await;
+        let (second, _, _second_outbound) = service.connect_test_client().await;
+        let subscribe = |topics: &[&str]| WsMessage::Subscribe {
+            payload: SubscribePayload { topics: topics.iter().map(|t| t.to_string()).collect(), min_level: None },
+        };
+        service.receive_test_message(first, &subscribe(&["metrics"])).await.unwrap();
+        service.receive_test_message(second, &subscribe(&["metrics", "errors"])).await.unwrap();
+        let jobs = WsMessage::SubscribeToJobs {
+            payload: JobSubscriptionPayload { device_filter: Some("r1".to_string()), job_type_filter: None },
+        };
+        service.receive_test_message(second, &jobs).await.unwrap();
+
+        let Json(body) = get_subscriptions(AdminAccess, State(app.state.clone())).await.unwrap();
+        assert_eq!(body["topic_count"], 3);
+        assert_eq!(body["topics"]["metrics"].as_array().unwrap().len(), 2);
+        assert_eq!(body["topics"]["errors"][0]["connection_id"], serde_json::json!(second));
+        assert_eq!(body["topics"]["job_events"][0]["device_filter"], "r1");
+        assert!(body["topics"]["metrics"][0].get("device_filter").is_none());
     }
 }
@@ -0,0 +1,55 @@
+// File Path: src/config.rs
+// Version: 1.0.0
+// Description: Reads typed settings from environment variables, so every setting treats
+// unset, empty and malformed values the same way.
+//
+// Usage Guide:
+// ```
+// let limit: usize = env_or("PYTHON_MAX_CONCURRENT", 4);
+// let timeout = Duration::from_secs(env_or("UPGRADE_TIMEOUT_SECS", 1800));
+// ```
+// Unset and empty variables use the default. A value that does not parse is logged and
+// also falls back to the default, so a typo never stops the server from starting.
+//
+// Change Log:
+// - 1.0.0: Initial implementation
+
+use std::{fmt::Display, str::FromStr};
+use tracing::warn;
+
+/// The value of environment variable `name` parsed as `T`, or `default`
+pub fn env_or<T>(name: &str, default: T) -> T
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let Ok(value) = std::env::var(name) else {
+        return default;
+    };
+    let value = value.trim();
+    if value.is_empty() {
+        return default;
+    }
+    value.parse().unwrap_or_else(|e| {
+        warn!("Ignoring invalid {}={:?}: {}", name, value, e);
+        default
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_empty_and_invalid_values_use_the_default() {
+        std::env::set_var("XAOS_TEST_ENV_OR_SET", " 42 ");
+        std::env::set_var("XAOS_TEST_ENV_OR_EMPTY", "");
+        std::env::set_var("XAOS_TEST_ENV_OR_INVALID", "forty-two");
+
+        assert_eq!(env_or::<u64>("XAOS_TEST_ENV_OR_SET", 7), 42);
+        assert_eq!(env_or::<u64>("XAOS_TEST_ENV_OR_UNSET", 7), 7);
+        assert_eq!(env_or::<u64>("XAOS_TEST_ENV_OR_EMPTY", 7), 7);
+        assert_eq!(env_or::<u64>("XAOS_TEST_ENV_OR_INVALID", 7), 7);
+        assert_eq!(env_or("XAOS_TEST_ENV_OR_EMPTY", "image".to_string()), "image");
+    }
+}
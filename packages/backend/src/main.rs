@@ -1,5 +1,5 @@
 // File Path: src/main.rs
-// Version: 1.2.5
+// Version: 1.3.19
 //
 // Description:
 // Main application entry point with Python runner integration.
@@ -10,6 +10,9 @@
 // - WebSocket support for real-time communication
 // - Python script execution in Docker containers
 // - Background task management
+// - Periodic metrics snapshots, with a final one on graceful shutdown
+// - SIGHUP reloads schemas and YAML data without a restart
+// - Optional startup probe waiting for the Python API before serving
 // - Comprehensive logging
 //
 // Usage Guide:
@@ -17,8 +20,42 @@
 // The server will start on http://127.0.0.1:3001
 // WebSocket endpoint: ws://127.0.0.1:3001/ws
 // Python API: http://127.0.0.1:3001/api/python/*
+// Reload schemas and YAML data: kill -HUP <pid>
+// Per-route metrics: http://127.0.0.1:3001/metrics
+// Wait for the Python API before binding: STARTUP_PROBE_TIMEOUT_SECS=60
+//   (PYTHON_API_URL sets the probed URL, STARTUP_PROBE_FAIL_FAST=true exits if it never comes up)
+// Limit concurrent backups (excess backups queue): MAX_CONCURRENT_BACKUPS=4
+// Default device login when neither the request nor the inventory has one: DEVICE_USERNAME, DEVICE_PASSWORD
+// Inventory edit audit log: INVENTORY_AUDIT_LOG (default logs/inventory_audit.jsonl),
+//   rotated to <log>.1 past INVENTORY_AUDIT_MAX_BYTES (default 10MB)
+// Snapshot metrics to a JSON-lines file: METRICS_SNAPSHOT_PATH=logs/metrics.jsonl
+//   (METRICS_SNAPSHOT_INTERVAL_SECS, METRICS_SNAPSHOT_MAX_BYTES, METRICS_SNAPSHOT_MAX_FILES)
 //
 // Change Log:
+// - 1.3.19: The SIGHUP handler is installed before spawn_reload_on_sighup returns
+// - 1.3.18: STARTUP_PROBE_FAIL_FAST is read through config::env_or
+// - 1.3.17: The device list service reaches the Python API at PYTHON_API_URL
+// - 1.3.16: Device jobs reach the Python API at PYTHON_API_URL, shared with the startup probe
+// - 1.3.15: Environment settings are read through config::env_or
+// - 1.3.14: Added inventory audit log recording who changed the inventory (INVENTORY_AUDIT_LOG)
+// - 1.3.13: Added credentials service resolving device logins (DEVICE_USERNAME / DEVICE_PASSWORD defaults)
+// - 1.3.12: Running Python executions get a grace period on shutdown before being cancelled
+// - 1.3.11: Background tasks report heartbeats to the task health service (GET /api/admin/tasks)
+// - 1.3.10: Added shared pagination bounds to AppState
+// - 1.3.9: Backup pool enforces the per-device cooldown from BACKUP_DEVICE_COOLDOWN_SECS
+// - 1.3.8: Added pluggable authenticator (API keys by default) applied to all routes
+// - 1.3.7: Added device list service (circuit breaker around the Python device list)
+// - 1.3.6: Routes are served under API_BASE_PATH when set
+// - 1.3.5: Execution cleanup uses per-status retention from the Python runner config
+// - 1.3.4: Added periodic metrics snapshots and graceful shutdown with a final snapshot
+// - 1.3.3: Added backup pool bounding concurrent backups (MAX_CONCURRENT_BACKUPS)
+// - 1.3.2: Added optional startup probe that waits for the Python API before binding
+// - 1.3.1: Added per-route metrics middleware and registry
+// - 1.3.0: Added job service to application state
+// - 1.2.9: Added query-string length limits middleware
+// - 1.2.8: Added device lock service to application state
+// - 1.2.7: Added SIGHUP handler that reloads schemas and invalidates cached YAML data
+// - 1.2.6: Added webhook service initialization
 // - 1.2.5: Fixed WebSocket service ownership issue and Python runner integration
 // - 1.2.4: Added Python runner service initialization
 // - 1.2.3: Updated YAML service constructor call
@@ -28,16 +65,26 @@
 // - 1.1.0: Initial WebSocket support
 // - 1.0.0: Base application structure
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tower_http::cors::CorsLayer;
-use tracing::{info, Level};
+use tracing::{error, info, warn, Level};
 
+mod config;
 mod models;
 mod services;
 mod api;
 mod routes;
+mod middleware;
+#[cfg(test)]
+mod test_support;
 
-use services::{YamlService, WebSocketService, PythonRunnerService};
+use services::{YamlService, WebSocketService, PythonRunnerService, WebhookService, DeviceLockService, JobService, RouteMetricsService, BackupPool, DeviceListService, device_list_service::DeviceListBreakerConfig, TaskHealthService, CredentialsService, InventoryAuditService};
+use services::credentials_service::CredentialDefaults;
+use services::inventory_audit_service::{inventory_audit_max_bytes_from_env, inventory_audit_path_from_env};
+use services::metrics_snapshot_service::{MetricsSnapshotConfig, MetricsSnapshotService};
+use middleware::auth::{ApiKeyAuthenticator, Authenticator};
+use config::env_or;
+use models::PaginationConfig;
 
 // =============================================================================
 // SECTION 1: APPLICATION STATE
@@ -54,8 +101,33 @@ pub struct AppState {
     pub websocket_service: Arc<WebSocketService>,
     /// Python script execution service
     pub python_runner_service: Arc<PythonRunnerService>,
+    /// Per-device locks serializing conflicting device operations
+    pub device_lock_service: Arc<DeviceLockService>,
+    /// Registry of in-flight device jobs
+    pub job_service: Arc<JobService>,
+    /// Per-route request metrics
+    pub route_metrics_service: Arc<RouteMetricsService>,
+    /// Bounded pool of concurrent backup slots
+    pub backup_pool: Arc<BackupPool>,
+    /// Python API device list behind a circuit breaker
+    pub device_list_service: Arc<DeviceListService>,
+    /// Validates request credentials for the auth middleware and WebSocket upgrades
+    pub authenticator: Arc<dyn Authenticator>,
+    /// Default and maximum page size for list endpoints
+    pub pagination: PaginationConfig,
+    /// Heartbeats of periodic background tasks
+    pub task_health: Arc<TaskHealthService>,
+    /// Resolves device login credentials for backup, restore, upgrade and report runs
+    pub credentials_service: Arc<CredentialsService>,
+    /// Audit trail of inventory edits
+    pub inventory_audit: Arc<InventoryAuditService>,
+    /// Python API base URL (PYTHON_API_URL) that backup and upgrade jobs are forwarded to
+    pub python_api_url: String,
 }
 
+/// Python API base URL when PYTHON_API_URL is unset
+const DEFAULT_PYTHON_API_URL: &str = "http://python_runner:8000";
+
 // =============================================================================
 // SECTION 2: MAIN APPLICATION
 // =============================================================================
@@ -76,14 +148,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize all application services with proper error handling
 
     info!("Initializing YAML service...");
-    let yaml_service = Arc::new(YamlService::new("./shared/schemas", "./shared/data").await?);
+    let yaml_service = Arc::new(YamlService::new("./shared/schemas", "./shared/data", None).await?);
+
+    info!("Initializing Webhook service...");
+    let webhook_service = Arc::new(WebhookService::new(None));
 
     info!("Initializing WebSocket service...");
-    let websocket_service = Arc::new(WebSocketService::new(None));
+    let websocket_service = Arc::new(WebSocketService::new(None, webhook_service));
+
+    let task_health = Arc::new(TaskHealthService::new());
 
     // Start WebSocket background tasks - clone first to avoid ownership issues
     let websocket_service_clone = websocket_service.clone();
-    websocket_service_clone.start_background_tasks().await;
+    websocket_service_clone.start_background_tasks(&task_health).await;
     info!("WebSocket background tasks started");
 
     info!("Initializing Python Runner service...");
@@ -92,13 +169,44 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     info!("Python Runner service initialized");
 
+    let device_lock_service = Arc::new(DeviceLockService::new());
+    let job_service = Arc::new(JobService::new());
+    let route_metrics_service = Arc::new(RouteMetricsService::new());
+    let backup_pool = Arc::new(
+        BackupPool::new(services::backup_pool::max_concurrent_backups_from_env())
+            .with_device_cooldown(services::backup_pool::device_cooldown_from_env()),
+    );
+    let python_api_url = env_or("PYTHON_API_URL", DEFAULT_PYTHON_API_URL.to_string());
+    let device_list_service = Arc::new(DeviceListService::new(DeviceListBreakerConfig::from_env(&python_api_url)));
+    let authenticator: Arc<dyn Authenticator> = Arc::new(ApiKeyAuthenticator::from_env());
+    let credentials_service = Arc::new(CredentialsService::new(yaml_service.clone(), CredentialDefaults::from_env()));
+    let inventory_audit = Arc::new(
+        InventoryAuditService::new(inventory_audit_path_from_env()).with_max_bytes(inventory_audit_max_bytes_from_env()),
+    );
+
     // =========================================================================
     // BACKGROUND TASK MANAGEMENT
     // =========================================================================
     // Start background tasks for maintenance and cleanup
 
     // Start background cleanup task for old executions
-    spawn_cleanup_task(python_runner_service.clone());
+    spawn_cleanup_task(python_runner_service.clone(), &task_health);
+    let shutdown_python_runner = python_runner_service.clone();
+
+    // Reload schemas and YAML data on SIGHUP
+    spawn_reload_on_sighup(yaml_service.clone());
+
+    // Periodically snapshot metrics to disk when METRICS_SNAPSHOT_PATH is set
+    let metrics_snapshots = MetricsSnapshotConfig::from_env().map(|config| {
+        Arc::new(MetricsSnapshotService::new(
+            config,
+            websocket_service.clone(),
+            python_runner_service.clone(),
+        ))
+    });
+    if let Some(snapshots) = &metrics_snapshots {
+        snapshots.clone().spawn(&task_health);
+    }
 
     // =========================================================================
     // APPLICATION STATE SETUP
@@ -110,6 +218,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         yaml_service,
         websocket_service,
         python_runner_service,
+        device_lock_service,
+        job_service,
+        route_metrics_service: route_metrics_service.clone(),
+        backup_pool,
+        device_list_service,
+        authenticator: authenticator.clone(),
+        pagination: PaginationConfig::default(),
+        task_health,
+        credentials_service,
+        inventory_audit,
+        python_api_url,
     };
 
     info!("Application state initialized successfully");
@@ -119,13 +238,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // =========================================================================
     // Set up all API routes and middleware
 
-    info!("Configuring application routes...");
-    let app = routes::create_routes()
+    let base_path = routes::base_path_from_env();
+    if base_path.is_empty() {
+        info!("Configuring application routes...");
+    } else {
+        info!("Configuring application routes under base path {}...", base_path);
+    }
+    let app = routes::create_routes(&base_path)
         .with_state(state)
+        .layer(axum::middleware::from_fn_with_state(
+            route_metrics_service,
+            middleware::route_metrics::record_route_metrics,
+        ))
+        .layer(axum::middleware::from_fn_with_state(authenticator, middleware::auth::authenticate))
+        .layer(axum::middleware::from_fn(middleware::query_limits::limit_query_string))
         .layer(CorsLayer::permissive());
 
     info!("Routes configured successfully");
 
+    // =========================================================================
+    // STARTUP PROBE
+    // =========================================================================
+    // Optionally wait for the Python API so early requests are not accepted
+    // while it cannot serve them
+
+    let probe = StartupProbeConfig::default();
+    if let Some(timeout) = probe.timeout {
+        if !wait_for_python_api(&probe.url, timeout).await {
+            if probe.fail_fast {
+                error!("Python API at {} not ready after {:?}, exiting", probe.url, timeout);
+                return Err(format!("Python API at {} not ready", probe.url).into());
+            }
+            warn!("Python API at {} not ready after {:?}, starting in degraded mode", probe.url, timeout);
+        }
+    }
+
     // =========================================================================
     // SERVER CONFIGURATION
     // =========================================================================
@@ -133,23 +280,97 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3001));
     info!("Server listening on {}", addr);
-    info!("WebSocket endpoint available at ws://{}/ws", addr);
-    info!("Python API endpoints available at http://{}/api/python/*", addr);
-    info!("API documentation available at http://{}/health", addr);
-    info!("YAML validation endpoints available at http://{}/api/yaml/*", addr);
+    info!("WebSocket endpoint available at ws://{}{}/ws", addr, base_path);
+    info!("Python API endpoints available at http://{}{}/api/python/*", addr, base_path);
+    info!("API documentation available at http://{}{}/health", addr, base_path);
+    info!("YAML validation endpoints available at http://{}{}/api/yaml/*", addr, base_path);
 
     // Start the server with proper error handling
     let listener = tokio::net::TcpListener::bind(addr).await?;
     axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>()
-    ).await?;
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
+
+    info!("Server stopped");
+
+    // Let running executions finish, then cancel the rest as a server shutdown
+    shutdown_python_runner.shutdown().await;
+
+    // Record the final state so the snapshot log covers the whole run
+    if let Some(snapshots) = &metrics_snapshots {
+        if let Err(e) = snapshots.write_snapshot().await {
+            warn!("Failed to write final metrics snapshot: {}", e);
+        }
+    }
 
     Ok(())
 }
 
 // =============================================================================
-// SECTION 3: BACKGROUND TASKS
+// SECTION 3: STARTUP PROBE
+// =============================================================================
+// Readiness check for the Python API before the server starts accepting traffic
+
+/// Startup probe settings, read from the environment
+#[derive(Debug, Clone)]
+struct StartupProbeConfig {
+    /// Python API base URL that is polled for readiness
+    url: String,
+    /// How long to wait for readiness; `None` disables the probe
+    timeout: Option<Duration>,
+    /// Exit instead of starting in degraded mode when the API never comes up
+    fail_fast: bool,
+}
+
+impl Default for StartupProbeConfig {
+    fn default() -> Self {
+        Self {
+            url: env_or("PYTHON_API_URL", DEFAULT_PYTHON_API_URL.to_string()),
+            timeout: Some(env_or("STARTUP_PROBE_TIMEOUT_SECS", 0))
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            fail_fast: env_or("STARTUP_PROBE_FAIL_FAST", false),
+        }
+    }
+}
+
+/// Polls the Python API until it answers or the timeout elapses
+///
+/// Any response other than a 5xx counts as ready, since the base URL itself
+/// may not be a routed endpoint.
+///
+/// # Returns
+/// Whether the Python API became ready within the timeout
+async fn wait_for_python_api(url: &str, timeout: Duration) -> bool {
+    let client = reqwest::Client::new();
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut attempt = 0u32;
+
+    info!("Waiting up to {:?} for Python API at {}", timeout, url);
+
+    loop {
+        attempt += 1;
+        match client.get(url).timeout(Duration::from_secs(2)).send().await {
+            Ok(response) if !response.status().is_server_error() => {
+                info!("Python API ready after {} attempt(s)", attempt);
+                return true;
+            }
+            Ok(response) => info!("Python API not ready (attempt {}): HTTP {}", attempt, response.status()),
+            Err(e) => info!("Python API not ready (attempt {}): {}", attempt, e),
+        }
+
+        if tokio::time::Instant::now() + Duration::from_secs(1) > deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+// =============================================================================
+// SECTION 4: BACKGROUND TASKS
 // =============================================================================
 // Background task management for cleanup and maintenance
 
@@ -157,25 +378,171 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///
 /// # Arguments
 /// * `python_runner_service` - Python runner service for cleanup operations
+/// * `task_health` - Registry the task reports its heartbeat to
 ///
 /// # Behavior
 /// - Runs every hour
-/// - Removes executions older than 24 hours
+/// - Removes executions past the retention for their status (see `ExecutionRetention`)
 /// - Logs cleanup operations for monitoring
-fn spawn_cleanup_task(python_runner_service: Arc<PythonRunnerService>) {
+fn spawn_cleanup_task(python_runner_service: Arc<PythonRunnerService>, task_health: &TaskHealthService) {
+    // Use from_secs instead of from_hours to avoid unstable feature
+    let period = Duration::from_secs(3600); // 1 hour
+    let heartbeat = task_health.register("execution_cleanup", period);
+
     tokio::spawn(async move {
         info!("Starting execution cleanup task");
 
-        let mut interval = tokio::time::interval(
-            // Use from_secs instead of from_hours to avoid unstable feature
-            tokio::time::Duration::from_secs(3600) // 1 hour
-        );
+        let mut interval = tokio::time::interval(period);
 
         loop {
             interval.tick().await;
+            heartbeat.beat();
             info!("Running execution cleanup cycle");
-            python_runner_service.cleanup_old_executions(24).await;
+            python_runner_service.cleanup_old_executions().await;
             info!("Completed execution cleanup cycle");
         }
     });
 }
+
+/// Spawns a background task that reloads schemas and YAML data on SIGHUP
+///
+/// # Arguments
+/// * `yaml_service` - YAML service whose schemas and document cache are reloaded
+///
+/// # Behavior
+/// - Recompiles all schemas and drops cached YAML documents so edits are picked up
+/// - Keeps the server and WebSocket connections running
+/// - Logs loaded/skipped schemas and any reload failure
+#[cfg(unix)]
+fn spawn_reload_on_sighup(yaml_service: Arc<YamlService>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    // Installed before returning so a SIGHUP sent right after startup is never fatal
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+    info!("SIGHUP reload handler installed");
+
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, reloading schemas and YAML data");
+            match yaml_service.reload_schemas().await {
+                Ok(report) => {
+                    info!(
+                        loaded = ?report.loaded,
+                        invalidated_documents = report.invalidated_documents,
+                        "Reload completed"
+                    );
+                    for skipped in &report.skipped {
+                        warn!("Schema skipped during reload: {} ({})", skipped.file, skipped.reason);
+                    }
+                }
+                Err(e) => {
+                    error!("Reload failed, keeping previous schemas: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// SIGHUP is not available on this platform; reload through /api/reload instead
+#[cfg(not(unix))]
+fn spawn_reload_on_sighup(_yaml_service: Arc<YamlService>) {}
+
+/// Resolves on Ctrl+C or (on unix) SIGTERM, starting graceful shutdown
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to install SIGTERM handler: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutdown signal received, draining connections");
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    #[tokio::test]
+    async fn sighup_reloads_schemas_added_since_startup() {
+        let app = TestApp::new().await;
+        spawn_reload_on_sighup(app.state.yaml_service.clone());
+        assert!(app.state.yaml_service.require_schema("sites").await.is_err());
+
+        // Written behind the service's back, as an operator editing the schemas directory would
+        tokio::fs::write(app.root.join("schemas/sites.schema.json"), r#"{"type": "object"}"#)
+            .await
+            .unwrap();
+        let status = std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        for _ in 0..100 {
+            if app.state.yaml_service.require_schema("sites").await.is_ok() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("schema added before SIGHUP was not loaded");
+    }
+
+    #[tokio::test]
+    async fn probe_waits_past_server_errors_until_the_api_answers() {
+        use axum::{http::StatusCode, routing::get, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        // The first poll finds the API still starting; any later answer, even a 404, is ready
+        let app = Router::new().route(
+            "/",
+            get(move || async move {
+                match counted.fetch_add(1, Ordering::SeqCst) {
+                    0 => StatusCode::SERVICE_UNAVAILABLE,
+                    _ => StatusCode::NOT_FOUND,
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        assert!(wait_for_python_api(&url, Duration::from_secs(5)).await);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Nothing listens on a dropped listener's port
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        assert!(!wait_for_python_api(&format!("http://{}/", closed), Duration::from_millis(500)).await);
+    }
+}
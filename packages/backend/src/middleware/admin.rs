@@ -0,0 +1,47 @@
+// File Path: src/middleware/admin.rs
+// Version: 1.1.0
+// Description: Extractor restricting admin-scoped endpoints to callers whose principal
+// carries the admin scope.
+//
+// Key Features:
+// - Requires a `Principal` with the `admin` scope, set by the authentication middleware
+// - ADMIN_TOKEN in the `X-Admin-Token` header is still accepted (as an admin-scoped key)
+//
+// Usage Guide:
+// Add `_admin: AdminAccess` as a handler argument to make the handler admin-scoped.
+//
+// Change Log:
+// - 1.1.0: Checks the principal's scopes from the pluggable authenticator
+// - 1.0.0: Initial implementation
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use tracing::warn;
+
+use super::auth::{Principal, ADMIN_SCOPE};
+use crate::models::ApiError;
+
+/// Header carrying the admin token
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+/// Proof that the request was made by an admin-scoped principal
+#[derive(Debug, Clone, Copy)]
+pub struct AdminAccess;
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AdminAccess {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match parts.extensions.get::<Principal>() {
+            Some(principal) if principal.has_scope(ADMIN_SCOPE) => Ok(AdminAccess),
+            Some(principal) => {
+                warn!("Rejected admin request to {} from {}", parts.uri.path(), principal.id);
+                Err(ApiError::Forbidden("Admin scope required".to_string()))
+            }
+            None => {
+                warn!("Rejected admin request to {}", parts.uri.path());
+                Err(ApiError::Forbidden("Admin token required".to_string()))
+            }
+        }
+    }
+}
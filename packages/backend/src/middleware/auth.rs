@@ -0,0 +1,245 @@
+// File Path: src/middleware/auth.rs
+// Version: 1.1.0
+// Description: Pluggable request authentication. An `Authenticator` turns a request's
+// credentials into a `Principal` with scopes; the middleware stores it on the request for
+// extractors such as `AdminAccess` and for the WebSocket upgrade.
+//
+// Key Features:
+// - `Authenticator` trait so deployments can swap API keys for JWT, mTLS, ...
+// - `ApiKeyAuthenticator` (default): keys from API_KEYS plus ADMIN_TOKEN as an admin key
+// - Requests without credentials continue anonymously; invalid credentials get 401
+// - Every presented credential is tried in order; the first valid one wins, so a stale
+//   X-Admin-Token does not mask a valid API key
+//
+// Usage Guide:
+// API_KEYS="ci:s3cret:read|write,ops:0ps:admin" (entries `name:key[:scope|scope...]`).
+// Keys are sent as `X-Api-Key`, `Authorization: Bearer <key>`, or `?api_key=` (WebSocket
+// clients cannot set headers; the value is URL-decoded). ADMIN_TOKEN is still accepted in
+// `X-Admin-Token`.
+// ```
+// let authenticator: Arc<dyn Authenticator> = Arc::new(ApiKeyAuthenticator::from_env());
+// router.layer(axum::middleware::from_fn_with_state(authenticator, middleware::auth::authenticate))
+// ```
+// Handlers read the caller with `Option<Extension<Principal>>`.
+//
+// Change Log:
+// - 1.1.0: An invalid credential falls through to the next one; `api_key` is URL-decoded
+// - 1.0.0: Initial implementation
+
+use axum::{
+    async_trait,
+    extract::{Query, Request, State},
+    http::{header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{collections::HashMap, sync::Arc};
+use tracing::{info, warn};
+
+use super::admin::ADMIN_TOKEN_HEADER;
+use crate::models::ApiError;
+
+// =============================================================================
+// SECTION 1: PRINCIPAL AND TRAIT
+// =============================================================================
+
+/// Scope granting access to admin-scoped endpoints
+pub const ADMIN_SCOPE: &str = "admin";
+
+/// An authenticated caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    /// Name identifying the caller in logs
+    pub id: String,
+    pub scopes: Vec<String>,
+}
+
+impl Principal {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// Validates a request's credentials
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    /// Returns the caller, `None` when the request carries no credentials,
+    /// or an error (401) when the credentials are invalid
+    async fn authenticate(&self, parts: &Parts) -> Result<Option<Principal>, ApiError>;
+}
+
+// =============================================================================
+// SECTION 2: API KEY AUTHENTICATOR
+// =============================================================================
+
+/// Static API keys, each mapped to a principal
+#[derive(Default)]
+pub struct ApiKeyAuthenticator {
+    keys: HashMap<String, Principal>,
+}
+
+impl ApiKeyAuthenticator {
+    /// Loads keys from API_KEYS, plus ADMIN_TOKEN as an admin-scoped key
+    pub fn from_env() -> Self {
+        let mut authenticator = Self::default();
+
+        for entry in std::env::var("API_KEYS").unwrap_or_default().split(',') {
+            let mut fields = entry.trim().splitn(3, ':');
+            let (Some(name), Some(key)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            if name.is_empty() || key.is_empty() {
+                warn!("Ignoring malformed API_KEYS entry");
+                continue;
+            }
+            let scopes = fields
+                .next()
+                .map(|scopes| scopes.split('|').filter(|s| !s.is_empty()).map(String::from).collect())
+                .unwrap_or_default();
+            authenticator.add_key(key, name, scopes);
+        }
+
+        if let Some(token) = std::env::var("ADMIN_TOKEN").ok().filter(|token| !token.is_empty()) {
+            authenticator.add_key(&token, "admin", vec![ADMIN_SCOPE.to_string()]);
+        }
+
+        info!("API key authentication configured with {} key(s)", authenticator.keys.len());
+        authenticator
+    }
+
+    pub fn add_key(&mut self, key: &str, name: &str, scopes: Vec<String>) {
+        self.keys.insert(key.to_string(), Principal { id: name.to_string(), scopes });
+    }
+
+    /// Keys from X-Admin-Token, X-Api-Key, a bearer token and the `api_key` query parameter,
+    /// in that order
+    fn presented_keys(parts: &Parts) -> Vec<String> {
+        let header_value = |name: &str| {
+            parts.headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+        };
+        let query_key = Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(mut params)| params.remove("api_key"));
+
+        [
+            header_value(ADMIN_TOKEN_HEADER),
+            header_value("x-api-key"),
+            header_value(header::AUTHORIZATION.as_str())
+                .and_then(|value| value.strip_prefix("Bearer ").map(str::to_string)),
+            query_key,
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[async_trait]
+impl Authenticator for ApiKeyAuthenticator {
+    async fn authenticate(&self, parts: &Parts) -> Result<Option<Principal>, ApiError> {
+        let keys = Self::presented_keys(parts);
+        if keys.is_empty() {
+            return Ok(None);
+        }
+
+        match keys.iter().find_map(|key| self.keys.get(key)) {
+            Some(principal) => Ok(Some(principal.clone())),
+            None => Err(ApiError::Unauthorized("Invalid API key".to_string())),
+        }
+    }
+}
+
+// =============================================================================
+// SECTION 3: MIDDLEWARE
+// =============================================================================
+
+/// Authenticates the request and stores the `Principal` in its extensions
+///
+/// Anonymous requests pass through without one; invalid credentials are rejected with 401.
+pub async fn authenticate(
+    State(authenticator): State<Arc<dyn Authenticator>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+
+    match authenticator.authenticate(&parts).await {
+        Ok(Some(principal)) => {
+            parts.extensions.insert(principal);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            warn!("Rejected request to {}: {}", parts.uri.path(), e);
+            return e.into_response();
+        }
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::admin::AdminAccess;
+    use axum::{body::Body, http::StatusCode, routing::get, Extension, Router};
+    use tower::Service;
+
+    fn authenticator() -> ApiKeyAuthenticator {
+        let mut authenticator = ApiKeyAuthenticator::default();
+        authenticator.add_key("ci-key", "ci", vec!["read".to_string()]);
+        authenticator.add_key("admin+key/1", "ops", vec![ADMIN_SCOPE.to_string()]);
+        authenticator
+    }
+
+    fn parts(uri: &str, headers: &[(&str, &str)]) -> Parts {
+        let mut request = Request::builder().uri(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(()).unwrap().into_parts().0
+    }
+
+    #[tokio::test]
+    async fn keys_are_read_from_every_credential_location() {
+        let authenticator = &authenticator();
+        let principal = |parts: Parts| async move { authenticator.authenticate(&parts).await };
+
+        assert_eq!(principal(parts("/", &[])).await.unwrap(), None);
+        assert_eq!(principal(parts("/", &[("x-api-key", "ci-key")])).await.unwrap().unwrap().id, "ci");
+        assert_eq!(principal(parts("/", &[("authorization", "Bearer ci-key")])).await.unwrap().unwrap().id, "ci");
+        assert_eq!(principal(parts("/ws?x=1&api_key=admin%2Bkey%2F1", &[])).await.unwrap().unwrap().id, "ops");
+        assert!(matches!(principal(parts("/", &[("x-api-key", "nope")])).await, Err(ApiError::Unauthorized(_))));
+
+        // A stale admin token does not hide a valid API key
+        let both = parts("/", &[(ADMIN_TOKEN_HEADER, "old-token"), ("x-api-key", "ci-key")]);
+        assert_eq!(principal(both).await.unwrap().unwrap().id, "ci");
+    }
+
+    #[tokio::test]
+    async fn middleware_and_admin_extractor_answer_401_and_403() {
+        let authenticator: Arc<dyn Authenticator> = Arc::new(authenticator());
+        let app = Router::new()
+            .route("/admin", get(|_admin: AdminAccess| async { "ok" }))
+            .route(
+                "/whoami",
+                get(|principal: Option<Extension<Principal>>| async move {
+                    principal.map_or_else(|| "anonymous".to_string(), |Extension(principal)| principal.id)
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(authenticator, authenticate));
+        let status = |uri: &str, key: Option<&str>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(key) = key {
+                request = request.header("x-api-key", key);
+            }
+            let mut app = app.clone();
+            async move { app.call(request.body(Body::empty()).unwrap()).await.unwrap().status() }
+        };
+
+        assert_eq!(status("/whoami", None).await, StatusCode::OK);
+        assert_eq!(status("/whoami", Some("nope")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("/admin", None).await, StatusCode::FORBIDDEN);
+        assert_eq!(status("/admin", Some("ci-key")).await, StatusCode::FORBIDDEN);
+        assert_eq!(status("/admin", Some("admin+key/1")).await, StatusCode::OK);
+    }
+}
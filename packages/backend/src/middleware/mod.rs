@@ -0,0 +1,31 @@
+// File Path: src/middleware/mod.rs
+// Version: 1.3.0
+// Description: HTTP middleware applied to all application routes.
+//
+// Key Features:
+// - Query-string length limits rejecting oversized requests with 400
+// - Admin token extractor for admin-scoped endpoints
+// - Per-route request, error and latency metrics
+// - Pluggable authentication (API keys by default) producing a Principal per request
+//
+// Usage Guide:
+// Layers are applied in main.rs after the routes are assembled:
+// `.layer(axum::middleware::from_fn(middleware::query_limits::limit_query_string))`
+//
+// Change Log:
+// - 1.3.0: Added pluggable authentication middleware
+// - 1.2.0: Added per-route metrics middleware
+// - 1.1.0: Added admin access extractor
+// - 1.0.0: Initial version with query-string limits
+
+/// Query-string length validation
+pub mod query_limits;
+
+/// Admin token check for admin-scoped endpoints
+pub mod admin;
+
+/// Per-route request count, error count and latency recording
+pub mod route_metrics;
+
+/// Authenticator trait, API key implementation and authentication middleware
+pub mod auth;
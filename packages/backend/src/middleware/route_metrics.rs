@@ -0,0 +1,74 @@
+// File Path: src/middleware/route_metrics.rs
+// Version: 1.0.0
+// Description: Middleware recording request count, 5xx count and latency per route.
+//
+// Key Features:
+// - Labels requests with the matched route pattern rather than the raw path,
+//   so `/api/python/status/:id` is one series regardless of the id
+// - Requests that match no route share a single `unmatched` label
+//
+// Usage Guide:
+// Applied in main.rs with the shared registry as state:
+// `.layer(axum::middleware::from_fn_with_state(route_metrics, middleware::route_metrics::record_route_metrics))`
+//
+// Change Log:
+// - 1.0.0: Initial implementation
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::{sync::Arc, time::Instant};
+
+use crate::services::route_metrics_service::{RouteMetricsService, UNMATCHED_ROUTE};
+
+/// Records the outcome and latency of every request in the route metrics registry
+pub async fn record_route_metrics(
+    State(route_metrics): State<Arc<RouteMetricsService>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    route_metrics
+        .record(&method, &route, response.status().as_u16(), started.elapsed())
+        .await;
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use tower::Service;
+
+    #[tokio::test]
+    async fn requests_are_counted_per_route_pattern() {
+        let route_metrics = Arc::new(RouteMetricsService::new());
+        let app = Router::new()
+            .route("/items/:id", get(|| async { "ok" }))
+            .route("/fail", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .layer(axum::middleware::from_fn_with_state(route_metrics.clone(), record_route_metrics));
+        for uri in ["/items/1", "/items/2", "/fail", "/missing"] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            app.clone().call(request).await.unwrap();
+        }
+
+        let body = route_metrics.render_prometheus().await;
+        assert!(body.contains("http_requests_total{route=\"/items/:id\",method=\"GET\"} 2"), "{}", body);
+        assert!(body.contains("http_request_errors_total{route=\"/items/:id\",method=\"GET\"} 0"), "{}", body);
+        assert!(body.contains("http_request_errors_total{route=\"/fail\",method=\"GET\"} 1"), "{}", body);
+        assert!(body.contains("http_requests_total{route=\"unmatched\",method=\"GET\"} 1"), "{}", body);
+        assert!(body.contains("http_request_duration_seconds_count{route=\"/items/:id\",method=\"GET\"} 2"), "{}", body);
+    }
+}
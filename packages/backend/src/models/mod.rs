@@ -1,6 +1,6 @@
 // =========================================================================================
 // File Path: src/models/mod.rs
-// Version: 1.3.0
+// Version: 1.27.0
 //
 // Description:
 // Central module for API data models and error handling. Contains all shared data structures
@@ -10,9 +10,37 @@
 // - API Error Handling: Custom error types and response conversion
 // - Navigation Models: UI navigation configuration structures
 // - WebSocket Models: Real-time communication structures
-// - Job Event Models: Real-time job progress tracking structures
+// - Job Control Models: Cancel-all results for executions and jobs
+// - Content Negotiation: JSON or YAML response bodies selected by the Accept header
+// - Inventory Models: Flattened device records and grouped inventory responses
+// - Pagination: Shared page-size bounds for list endpoints
 //
 // Change Log:
+// - 1.27.0: GatewayTimeout carries the id of the job still running, returned as `job_id`
+// - 1.26.1: Removed unused JobEvent, job subscription and restore models (restore has its own in api/restore)
+// - 1.26.0: Added SidebarSummary for the sidebar listing
+// - 1.25.0: Added GatewayTimeout variant (504) for requests that gave up waiting on a job
+// - 1.24.0: BackupRequest credentials are optional overrides, resolved by the credentials service
+// - 1.23.0: Added InvalidDevices variant (400) listing the devices a batch request rejected
+// - 1.22.0: BackupFileList carries the number of files listed
+// - 1.21.0: Added NotImplemented variant (501) for placeholder endpoints
+// - 1.20.0: NavigationConfig reads the settings sidebar layout (`navigation` list, item `type`)
+// - 1.19.0: Added SchemaValidation variant (400) carrying per-error ValidationIssue details
+// - 1.18.0: Added PaginationConfig and Page for bounded list endpoints
+// - 1.17.0: BackupRequest accepts `force` to skip the per-device backup cooldown
+// - 1.16.0: Added Unauthorized variant (401)
+// - 1.15.0: Added BackupResponse.stale for device lists served from cache
+// - 1.14.0: Added ServiceUnavailable variant (503)
+// - 1.13.0: Added DeviceSuggestion and AutocompleteResponse for inventory type-ahead
+// - 1.12.0: Added TooManyRequests variant (429)
+// - 1.11.0: Added DeviceBundle models for the device backup bundle export
+// - 1.10.0: Added SchemaUnavailable variant (503) for schemas that failed to compile
+// - 1.9.0: Added typed Device list parsed from the Python API, and UpstreamError (502)
+// - 1.8.0: Replaced opaque BackupResponse.files with the tagged BackupFiles enum
+// - 1.7.0: Added Forbidden variant and cancel-all result models
+// - 1.6.0: Added inventory device models for grouped inventory responses
+// - 1.5.0: Added Conflict variant to ApiError
+// - 1.4.0: Added ResponseFormat extractor and Negotiated response for YAML/JSON output
 // - 1.3.0: Added JobEvent models for real-time job progress tracking
 // - 1.2.0: Added BadRequest variant to ApiError and implemented From<axum::Error> for ApiError.
 // - 1.1.0: Added ExecutionError variant and organized code into logical sections
@@ -20,12 +48,16 @@
 // =========================================================================================
 
 use axum::{
+    async_trait,
+    extract::FromRequestParts,
     response::{IntoResponse, Response},
-    http::StatusCode,
+    http::{header, request::Parts, StatusCode},
 };
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use crate::config::env_or;
+
 // =========================================================================================
 // SECTION 1: WEB SOCKET MODELS
 // =========================================================================================
@@ -53,6 +85,12 @@ pub enum ApiError {
     #[error("Bad request: {0}")]
     BadRequest(String),
     
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     
@@ -67,6 +105,14 @@ pub enum ApiError {
     
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    /// Document failed schema validation; the response lists each issue
+    #[error("Schema validation failed: {} error(s)", .0.len())]
+    SchemaValidation(Vec<ValidationIssue>),
+
+    /// Batch request refused as a whole; the response lists each rejected device
+    #[error("{} device(s) failed validation", .0.len())]
+    InvalidDevices(Vec<DeviceRejection>),
     
     #[error("Internal server error: {0}")]
     InternalError(String),
@@ -76,6 +122,29 @@ pub enum ApiError {
     
     #[error("Job execution error: {0}")]
     JobExecutionError(String),
+
+    #[error("Upstream error: {0}")]
+    UpstreamError(String),
+
+    #[error("Schema unavailable: {0}")]
+    SchemaUnavailable(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    /// Gave up waiting on a job that keeps running in the background; the body names the job
+    #[error("Gateway timeout: {message}")]
+    GatewayTimeout { message: String, job_id: String },
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// Endpoint exists but is not implemented yet
+    #[error("Not implemented: {0}")]
+    NotImplemented(String),
 }
 
 impl IntoResponse for ApiError {
@@ -85,14 +154,46 @@ impl IntoResponse for ApiError {
             ApiError::FileNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
             ApiError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
             ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            ApiError::Conflict(_) => (StatusCode::CONFLICT, self.to_string()),
+            ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string()),
             ApiError::IoError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string()),
             ApiError::SerializationError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Serialization failed".to_string()),
             ApiError::DeserializationError(_) => (StatusCode::BAD_REQUEST, "Invalid request format".to_string()),
             ApiError::WebSocketError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "WebSocket error".to_string()),
             ApiError::ValidationError(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            ApiError::SchemaValidation(issues) => {
+                let body = serde_json::json!({
+                    "error": self.to_string(),
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "errors": issues,
+                });
+                return (StatusCode::BAD_REQUEST, axum::Json(body)).into_response();
+            }
+            ApiError::InvalidDevices(devices) => {
+                let body = serde_json::json!({
+                    "error": self.to_string(),
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "devices": devices,
+                });
+                return (StatusCode::BAD_REQUEST, axum::Json(body)).into_response();
+            }
             ApiError::InternalError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string()),
             ApiError::ExecutionError(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
             ApiError::JobExecutionError(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            ApiError::UpstreamError(_) => (StatusCode::BAD_GATEWAY, self.to_string()),
+            ApiError::SchemaUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            ApiError::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            ApiError::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            ApiError::GatewayTimeout { job_id, .. } => {
+                let body = serde_json::json!({
+                    "error": self.to_string(),
+                    "status": StatusCode::GATEWAY_TIMEOUT.as_u16(),
+                    "job_id": job_id,
+                });
+                return (StatusCode::GATEWAY_TIMEOUT, axum::Json(body)).into_response();
+            }
+            ApiError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
+            ApiError::NotImplemented(_) => (StatusCode::NOT_IMPLEMENTED, self.to_string()),
         };
 
         let body = serde_json::json!({
@@ -104,6 +205,36 @@ impl IntoResponse for ApiError {
     }
 }
 
+/// One schema validation error, located in the document
+///
+/// For inventory documents (`locations.<location>.<category>[i]`) the failing device is
+/// identified so editors can jump to it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    /// JSON pointer to the failing value, e.g. `/locations/BASEMENT/routers/2/ip_address`
+    pub path: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Index of the device within its category list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_hostname: Option<String>,
+    /// Device field at fault: the invalid one, or the missing one for `required` errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+/// A device refused by a batch request, with why
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceRejection {
+    pub device: String,
+    pub reason: String,
+}
+
 // Implement the From trait for `axum::Error` to `ApiError`
 impl From<axum::Error> for ApiError {
     fn from(inner: axum::Error) -> Self {
@@ -112,77 +243,90 @@ impl From<axum::Error> for ApiError {
 }
 
 // =========================================================================================
-// SECTION 3: JOB EVENT MODELS
-// Real-time job progress tracking structures for device operations
+// SECTION 2.1: CONTENT NEGOTIATION
+// Selects JSON (default) or YAML output from the request's Accept header
 // =========================================================================================
 
-/// Standardized job event for real-time progress tracking across all device operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JobEvent {
-    /// Unique identifier for the job instance
-    pub job_id: String,
-    /// Device hostname or identifier
-    pub device: String,
-    /// Type of job: backup, restore, validation, upgrade, etc.
-    pub job_type: String,
-    /// Event type: started, progress, completed, failed, etc.
-    pub event_type: String,
-    /// Current status: queued, in_progress, completed, failed
-    pub status: String,
-    /// ISO 8601 timestamp of the event
-    pub timestamp: DateTime<Utc>,
-    /// Job-specific data and progress details
-    pub data: serde_json::Value,
-    /// Optional error information for failed jobs
-    pub error: Option<String>,
+/// Output format requested through the `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    Yaml,
 }
 
-impl JobEvent {
-    /// Create a new job event with current timestamp
-    pub fn new(job_id: &str, device: &str, job_type: &str, event_type: &str, status: &str, data: serde_json::Value) -> Self {
-        Self {
-            job_id: job_id.to_string(),
-            device: device.to_string(),
-            job_type: job_type.to_string(),
-            event_type: event_type.to_string(),
-            status: status.to_string(),
-            timestamp: Utc::now(),
-            data,
-            error: None,
-        }
+impl ResponseFormat {
+    /// Returns Yaml when the Accept header asks for text/yaml (or application/yaml, application/x-yaml)
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let wants_yaml = accept.is_some_and(|accept| {
+            accept.split(',').any(|media| {
+                let media = media.split(';').next().unwrap_or("").trim();
+                matches!(media, "text/yaml" | "application/yaml" | "application/x-yaml")
+            })
+        });
+
+        if wants_yaml { Self::Yaml } else { Self::Json }
     }
-    
-    /// Create a job event with error information
-    pub fn with_error(job_id: &str, device: &str, job_type: &str, error: &str, data: serde_json::Value) -> Self {
-        Self {
-            job_id: job_id.to_string(),
-            device: device.to_string(),
-            job_type: job_type.to_string(),
-            event_type: "failed".to_string(),
-            status: "failed".to_string(),
-            timestamp: Utc::now(),
-            data,
-            error: Some(error.to_string()),
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let accept = parts
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok());
+        Ok(Self::from_accept(accept))
+    }
+}
+
+/// Response body rendered as JSON or YAML according to the negotiated format
+pub struct Negotiated<T> {
+    pub format: ResponseFormat,
+    pub body: T,
+}
+
+impl<T> Negotiated<T> {
+    pub fn new(format: ResponseFormat, body: T) -> Self {
+        Self { format, body }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        match self.format {
+            ResponseFormat::Json => axum::Json(self.body).into_response(),
+            ResponseFormat::Yaml => match serde_yaml::to_string(&self.body) {
+                Ok(yaml) => (
+                    [(header::CONTENT_TYPE, "text/yaml; charset=utf-8")],
+                    yaml,
+                ).into_response(),
+                Err(e) => ApiError::SerializationError(e.to_string()).into_response(),
+            },
         }
     }
 }
 
-/// Request structure for subscribing to job events
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JobSubscriptionRequest {
-    /// Optional device filter to receive events only for specific devices
-    pub device_filter: Option<String>,
-    /// Optional job type filter (backup, restore, etc.)
-    pub job_type_filter: Option<String>,
+// =========================================================================================
+// SECTION 3: JOB CONTROL MODELS
+// Results of bulk operations on executions and jobs
+// =========================================================================================
+
+/// Result of a cancel-all operation on executions or jobs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CancelAllResult {
+    /// Ids that were cancelled
+    pub cancelled: Vec<String>,
+    /// Ids that could not be cancelled, with the reason
+    pub failed: Vec<CancelFailure>,
 }
 
-/// Response structure for job subscription confirmation
+/// An execution or job that could not be cancelled
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JobSubscriptionResponse {
-    /// Subscription ID for managing subscriptions
-    pub subscription_id: String,
-    /// List of topics subscribed to
-    pub topics: Vec<String>,
+pub struct CancelFailure {
+    pub id: String,
+    pub error: String,
 }
 
 // =========================================================================================
@@ -192,6 +336,8 @@ pub struct JobSubscriptionResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigationConfig {
+    /// Top-level entries; read from `navigation` in settingsSidebarNavigation.yaml
+    #[serde(alias = "navigation")]
     pub items: Vec<NavigationItem>,
     pub settings: Option<NavigationSettings>,
 }
@@ -200,12 +346,22 @@ pub struct NavigationConfig {
 pub struct NavigationItem {
     pub id: String,
     pub label: String,
+    /// Section (groups pages) or page; untyped entries are treated as pages by the UI
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<NavigationItemType>,
     pub icon: Option<String>,
     pub path: Option<String>,
     pub children: Option<Vec<NavigationItem>>,
     pub metadata: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NavigationItemType {
+    Section,
+    Page,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigationSettings {
     pub theme: Option<String>,
@@ -213,6 +369,75 @@ pub struct NavigationSettings {
     pub collapsible: Option<bool>,
 }
 
+/// One sidebar file as listed by GET /api/sidebars
+#[derive(Debug, Clone, Serialize)]
+pub struct SidebarSummary {
+    /// File stem, as passed to GET /api/sidebar/:sidebar_id
+    pub id: String,
+    /// The file's `name` or `title`, falling back to the id
+    pub name: String,
+    /// Number of top-level items; `None` when the file could not be parsed
+    pub item_count: Option<usize>,
+    pub modified: Option<DateTime<Utc>>,
+    /// Why the file could not be parsed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// =========================================================================================
+// SECTION 4.1: INVENTORY MODELS
+// Flattened device records parsed from the locations/categories inventory layout
+// =========================================================================================
+
+/// A single inventory device with its location (site) and category (role)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryDevice {
+    pub host_name: String,
+    pub vendor: String,
+    pub ip_address: String,
+    pub platform: String,
+    /// Location key the device is listed under (e.g. "BASEMENT")
+    pub site: String,
+    /// Category key the device is listed under (e.g. "routers")
+    pub role: String,
+}
+
+/// Devices sharing the same value of the grouping attribute
+#[derive(Debug, Clone, Serialize)]
+pub struct InventoryGroup {
+    pub count: usize,
+    pub devices: Vec<InventoryDevice>,
+}
+
+/// Response for GET /api/inventory/grouped
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupedInventoryResponse {
+    /// Attribute used for grouping
+    pub by: String,
+    /// Total number of devices across all groups
+    pub total: usize,
+    /// Groups keyed by attribute value, sorted by key
+    pub groups: std::collections::BTreeMap<String, InventoryGroup>,
+}
+
+/// A device matching an autocomplete query
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceSuggestion {
+    pub host_name: String,
+    pub role: String,
+    pub site: String,
+    pub ip_address: String,
+}
+
+/// Response for GET /api/inventory/autocomplete
+#[derive(Debug, Clone, Serialize)]
+pub struct AutocompleteResponse {
+    pub query: String,
+    pub count: usize,
+    /// Best matches first: exact, then prefix, then substring matches
+    pub suggestions: Vec<DeviceSuggestion>,
+}
+
 // =========================================================================================
 // SECTION 5: BACKUP & RESTORE MODELS
 // Data structures for backup and restore operations
@@ -221,9 +446,15 @@ pub struct NavigationSettings {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupRequest {
     pub hostname: String,
-    pub username: String,
-    pub password: String,
+    /// Overrides the inventory and environment login; see CredentialsService
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
     pub inventory_file: Option<String>,
+    /// Skip the per-device backup cooldown; not forwarded to the Python API
+    #[serde(default, skip_serializing)]
+    pub force: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -231,20 +462,304 @@ pub struct BackupResponse {
     pub status: String,
     pub message: String,
     pub logs: Option<String>,
-    pub files: Option<serde_json::Value>,
+    pub files: Option<BackupFiles>,
+    /// Set when the payload is a cached copy because the Python API is unavailable
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+}
+
+/// Payload of a BackupResponse, serialized as `{"kind": ..., "data": ...}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum BackupFiles {
+    DeviceList(DeviceList),
+    BackupFileList(BackupFileList),
+    BackupResult(BackupResult),
+    /// Upstream payload that did not match any known shape
+    Raw(serde_json::Value),
 }
 
+/// Devices known to the Python API
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RestoreRequest {
+pub struct DeviceList {
+    pub count: usize,
+    pub devices: Vec<Device>,
+}
+
+/// A device as reported by the Python API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    #[serde(alias = "name")]
     pub hostname: String,
-    pub username: String,
-    pub password: String,
-    pub backup_file: String,
+    #[serde(default)]
+    pub vendor: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub reachable: Option<bool>,
+    /// Most recent backup file name
+    #[serde(default)]
+    pub last_backup: Option<String>,
+    /// Backup file names, sorted
+    #[serde(default)]
+    pub backups: Vec<String>,
 }
 
+impl DeviceList {
+    /// Parses the Python API's device listing
+    ///
+    /// Accepts `devices` either as a map of hostname to backup file names
+    /// or as a list of device objects.
+    ///
+    /// # Returns
+    /// The device list, or a description of why the payload was not understood
+    pub fn from_upstream(value: &serde_json::Value) -> Result<Self, String> {
+        if value.get("status").and_then(|s| s.as_str()) == Some("error") {
+            let reason = value.get("error").and_then(|e| e.as_str()).unwrap_or("unknown error");
+            return Err(format!("Python API reported an error listing devices: {}", reason));
+        }
+
+        let mut devices = match value.get("devices") {
+            Some(serde_json::Value::Object(by_hostname)) => by_hostname
+                .iter()
+                .map(|(hostname, files)| {
+                    let mut backups: Vec<String> = serde_json::from_value(files.clone())
+                        .map_err(|e| format!("Backup files for '{}' are not a list of names: {}", hostname, e))?;
+                    backups.sort();
+                    Ok(Device {
+                        hostname: hostname.clone(),
+                        vendor: None,
+                        model: None,
+                        reachable: None,
+                        last_backup: backups.last().cloned(),
+                        backups,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+            Some(list @ serde_json::Value::Array(_)) => serde_json::from_value(list.clone())
+                .map_err(|e| format!("Device entries have an unexpected shape: {}", e))?,
+            Some(_) => return Err("'devices' is neither an object nor a list".to_string()),
+            None => return Err("Response has no 'devices' field".to_string()),
+        };
+
+        devices.sort_by(|a: &Device, b: &Device| a.hostname.cmp(&b.hostname));
+        Ok(Self { count: devices.len(), devices })
+    }
+}
+
+/// Backup files available for a single device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFileList {
+    pub device: String,
+    pub files: Vec<String>,
+    /// Number of files listed, after any date filtering
+    #[serde(default)]
+    pub count: usize,
+}
+
+/// Acknowledgement of a backup started by the Python API
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RestoreResponse {
+pub struct BackupResult {
     pub status: String,
-    pub message: String,
-    pub logs: Option<String>,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    #[serde(default)]
+    pub device: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl BackupFiles {
+    /// Parses an upstream payload as `T`, keeping it raw if the shape does not match
+    pub fn parse<T>(value: serde_json::Value, wrap: fn(T) -> Self) -> Self
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        match serde_json::from_value::<T>(value.clone()) {
+            Ok(parsed) => wrap(parsed),
+            Err(e) => {
+                tracing::warn!("Unrecognized backup payload shape, passing through raw: {}", e);
+                Self::Raw(value)
+            }
+        }
+    }
+}
+
+/// Everything known about a device, for migration and offboarding
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceBundle {
+    pub device: String,
+    /// Inventory entry, if the device is still listed in the inventory
+    pub inventory: Option<InventoryDevice>,
+    /// Backup files, oldest first
+    pub backups: Vec<BackupFileInfo>,
+    /// Content of the most recent backup file
+    pub latest_backup: Option<BackupFileContent>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A backup file with its size and modification time
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupFileInfo {
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// A backup file's name and text content
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupFileContent {
+    pub name: String,
+    pub content: String,
+}
+
+// =========================================================================================
+// SECTION 6: PAGINATION
+// Page-size bounds shared by every list endpoint
+// =========================================================================================
+
+/// Default and maximum page size for list endpoints
+///
+/// Every list endpoint takes `limit` and `offset` query parameters. A missing or zero
+/// `limit` uses `default_page_size`; a larger one is clamped to `max_page_size`.
+#[derive(Debug, Clone, Copy)]
+pub struct PaginationConfig {
+    pub default_page_size: usize,
+    pub max_page_size: usize,
+}
+
+impl Default for PaginationConfig {
+    /// Reads PAGE_SIZE_DEFAULT (default 50) and PAGE_SIZE_MAX (default 500)
+    fn default() -> Self {
+        let max_page_size = env_or("PAGE_SIZE_MAX", 500).max(1);
+        Self {
+            default_page_size: env_or("PAGE_SIZE_DEFAULT", 50).clamp(1, max_page_size),
+            max_page_size,
+        }
+    }
+}
+
+impl PaginationConfig {
+    /// The page size to serve for a requested `limit`
+    pub fn page_size(&self, limit: Option<usize>) -> usize {
+        match limit {
+            None | Some(0) => self.default_page_size,
+            Some(limit) => limit.min(self.max_page_size),
+        }
+    }
+
+    /// Cuts one page out of `items`
+    pub fn paginate<T>(&self, items: Vec<T>, limit: Option<usize>, offset: Option<usize>) -> Page<T> {
+        let total = items.len();
+        let limit = self.page_size(limit);
+        let offset = offset.unwrap_or(0);
+        Page {
+            items: items.into_iter().skip(offset).take(limit).collect(),
+            total,
+            limit,
+            offset,
+        }
+    }
+}
+
+/// One page of a list, with the bounds actually applied
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of items before paging
+    pub total: usize,
+    /// Page size used, after clamping
+    pub limit: usize,
+    pub offset: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGINATION: PaginationConfig = PaginationConfig { default_page_size: 3, max_page_size: 5 };
+
+    #[test]
+    fn page_sizes_default_and_clamp() {
+        assert_eq!(PAGINATION.page_size(None), 3);
+        assert_eq!(PAGINATION.page_size(Some(0)), 3);
+        assert_eq!(PAGINATION.page_size(Some(4)), 4);
+        assert_eq!(PAGINATION.page_size(Some(5)), 5);
+        assert_eq!(PAGINATION.page_size(Some(1000)), 5);
+    }
+
+    #[test]
+    fn pages_report_the_bounds_applied() {
+        let items: Vec<u32> = (0..7).collect();
+
+        let first = PAGINATION.paginate(items.clone(), None, None);
+        assert_eq!((first.items, first.total, first.limit, first.offset), (vec![0, 1, 2], 7, 3, 0));
+
+        let clamped = PAGINATION.paginate(items.clone(), Some(100), Some(4));
+        assert_eq!((clamped.items, clamped.limit), (vec![4, 5, 6], 5));
+
+        // A zero limit means the default page, not an empty one
+        let zero = PAGINATION.paginate(items.clone(), Some(0), Some(6));
+        assert_eq!((zero.items, zero.limit), (vec![6], 3));
+
+        let past_end = PAGINATION.paginate(items, Some(2), Some(50));
+        assert!(past_end.items.is_empty());
+        assert_eq!((past_end.total, past_end.offset), (7, 50));
+    }
+
+    #[tokio::test]
+    async fn yaml_is_returned_only_when_accepted() {
+        assert_eq!(ResponseFormat::from_accept(None), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept(Some("application/json")), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept(Some("text/html, text/yaml;q=0.9")), ResponseFormat::Yaml);
+        assert_eq!(ResponseFormat::from_accept(Some("application/x-yaml")), ResponseFormat::Yaml);
+
+        let body = serde_json::json!({ "name": "core-1" });
+        let yaml = Negotiated::new(ResponseFormat::Yaml, &body).into_response();
+        assert_eq!(yaml.headers()[header::CONTENT_TYPE], "text/yaml; charset=utf-8");
+        let bytes = axum::body::to_bytes(yaml.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], b"name: core-1\n");
+
+        let json = Negotiated::new(ResponseFormat::Json, &body).into_response();
+        assert_eq!(json.headers()[header::CONTENT_TYPE], "application/json");
+    }
+
+    #[test]
+    fn backup_payloads_are_tagged_or_passed_through_raw() {
+        let started = serde_json::json!({ "status": "started", "job_id": "job-1" });
+        let files = BackupFiles::parse(started, BackupFiles::BackupResult);
+        assert!(matches!(&files, BackupFiles::BackupResult(result) if result.job_id.as_deref() == Some("job-1")));
+        assert_eq!(
+            serde_json::to_value(&files).unwrap(),
+            serde_json::json!({ "kind": "backup_result", "data": { "status": "started", "job_id": "job-1", "device": null, "message": null } })
+        );
+
+        // Upstream changed shape: nothing is lost, it is passed through as-is
+        let unexpected = serde_json::json!({ "device": "r1", "files": "r1.conf" });
+        let files = BackupFiles::parse(unexpected.clone(), BackupFiles::BackupFileList);
+        assert_eq!(serde_json::to_value(&files).unwrap(), serde_json::json!({ "kind": "raw", "data": unexpected }));
+    }
+
+    #[test]
+    fn device_listings_parse_from_either_shape() {
+        let by_hostname = serde_json::json!({ "devices": {
+            "r2": ["20250102_r2.conf", "20250101_r2.conf"],
+            "r1": [],
+        } });
+        let list = DeviceList::from_upstream(&by_hostname).unwrap();
+        assert_eq!(list.count, 2);
+        assert_eq!((list.devices[0].hostname.as_str(), list.devices[0].last_backup.as_deref()), ("r1", None));
+        assert_eq!(list.devices[1].backups, ["20250101_r2.conf", "20250102_r2.conf"]);
+        assert_eq!(list.devices[1].last_backup.as_deref(), Some("20250102_r2.conf"));
+
+        let objects = serde_json::json!({ "devices": [{ "name": "sw1", "vendor": "arista", "reachable": true }] });
+        let list = DeviceList::from_upstream(&objects).unwrap();
+        assert_eq!((list.devices[0].hostname.as_str(), list.devices[0].reachable), ("sw1", Some(true)));
+
+        let failed = serde_json::json!({ "status": "error", "error": "inventory missing" });
+        assert!(DeviceList::from_upstream(&failed).unwrap_err().contains("inventory missing"));
+        assert!(DeviceList::from_upstream(&serde_json::json!({ "devices": "r1" })).is_err());
+        assert!(DeviceList::from_upstream(&serde_json::json!({ "devices": { "r1": "r1.conf" } })).unwrap_err().contains("'r1'"));
+        assert!(DeviceList::from_upstream(&serde_json::json!({})).is_err());
+    }
 }
@@ -1,11 +1,34 @@
 // File: backend/src/models/websocket.rs
-// Version: 3.1.0
+// Version: 3.1.1
 // Key Features:
 // - Added REQUEST_CONNECTION_INFO and REQUEST_ACTIVE_CONNECTIONS message types
 // - Fixed message type consistency between frontend and backend
 // - Ensure proper Pong message serialization format
 // - FIXED: toString typo changed to to_string
 // - Added job event handling for real-time device operation updates
+// - Added CloseReason for connection close-reason metrics
+// - Added allowlist of permitted Custom event names to WsConfig
+// - Added optional server banner (version, MOTD, feature flags) to the welcome message
+// - Added outbound queue depth and congestion flag to ConnectionSummary
+// - Added strict FromStr parsing for SubscriptionTopic (From<&str> stays lenient)
+// - Added resumable session token to the welcome message and Resume/SessionResumed messages
+// - Added SubscriptionResult message with a per-topic outcome for Subscribe/Unsubscribe
+// - Added debug log TTL and configurable drop batch size to DebugConfig
+// - Added configurable per-connection outbound channel capacity
+// - Added the allowed job event vocabulary (job types, event types, statuses)
+// - Added optional trace_id on job events linking them to a Python execution
+// - Added a global rate limit for broadcasts requested over REST
+// - Added per-topic inbound message size limits overriding max_message_size
+// - Added the `errors` topic and BackgroundError message for background task failures
+// - Added TopicSubscriber for listing subscribers grouped by topic
+// - Connections carry optional GeoIP country/ASN context
+// - Added the `heartbeat` topic and an optional heartbeat interval to WsConfig
+// - Added the `connections` topic and ConnectionEvent message for connect/disconnect events
+// - Added inbound JSON nesting depth and element count limits to WsConfig
+// - Added DebugLevel and a per-connection minimum level for the `debug` topic
+// - Added welcome-message send retry attempts and delay to WsConfig
+// - SubscriptionTopic implements Display; removed the unused FileChangePayload
+// - Channel capacity and congestion depth are read from WS_CHANNEL_CAPACITY and WS_CONGESTED_QUEUE_DEPTH
 //
 // How to Guide:
 // 1. Frontend should send REQUEST_CONNECTION_INFO to get connection details
@@ -13,13 +36,17 @@
 // 3. Backend responds with CONNECTION_INFO and ACTIVE_CONNECTIONS respectively
 // 4. Pong messages are serialized as {type: "Pong"} for frontend compatibility
 // 5. Job events are broadcast for real-time device operation updates
+// 6. After reconnecting, send Resume with the previous session_token to restore subscriptions
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::net::SocketAddr;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::config::env_or;
+
 // ═══════════════════════════════════════════════════════════════════════════════════
 // CONNECTION TYPES AND IDENTIFIERS
 // ═══════════════════════════════════════════════════════════════════════════════════
@@ -27,6 +54,20 @@ use chrono::{DateTime, Utc};
 /// Unique identifier for WebSocket connections
 pub type ConnectionId = Uuid;
 
+/// Reason a connection was closed, used for close-reason metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseReason {
+    /// Client sent a close frame or ended the stream
+    ClientClose,
+    /// No activity within the connection timeout
+    StaleTimeout,
+    /// Rejected because the connection limit was reached
+    LimitRejected,
+    /// Socket read/write or handler error
+    Error,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════════
 // DEBUG CONFIGURATION
 // ═══════════════════════════════════════════════════════════════════════════════════
@@ -40,20 +81,23 @@ pub struct DebugConfig {
     pub log_performance: bool,
     pub save_to_file: bool,
     pub max_log_size: usize,
+    /// Oldest entries dropped at once when `max_log_size` is exceeded
+    pub drop_batch_size: usize,
+    /// Entries older than this many seconds are pruned on write; `None` keeps them until evicted by size
+    pub log_ttl_secs: Option<u64>,
 }
 
 impl Default for DebugConfig {
     fn default() -> Self {
         Self {
-            enabled: std::env::var("WEBSOCKET_DEBUG")
-                .unwrap_or_else(|_| "false".to_string())
-                .parse()
-                .unwrap_or(false),
+            enabled: env_or("WEBSOCKET_DEBUG", false),
             log_messages: true,
             log_connections: true,
             log_performance: true,
             save_to_file: false,
             max_log_size: 10_000,
+            drop_batch_size: 100,
+            log_ttl_secs: Some(3600),
         }
     }
 }
@@ -99,6 +143,11 @@ pub enum WsMessage {
         payload: UnsubscribePayload,
     },
 
+    #[serde(rename = "SubscriptionResult")]
+    SubscriptionResult {
+        payload: SubscriptionResultPayload,
+    },
+
     // Status and metrics
     #[serde(rename = "ACTIVE_CONNECTIONS")]
     ActiveConnections {
@@ -134,6 +183,17 @@ pub enum WsMessage {
         payload: JobUnsubscribePayload,
     },
 
+    // Session resumption after a reconnect
+    #[serde(rename = "Resume")]
+    Resume {
+        payload: ResumePayload,
+    },
+
+    #[serde(rename = "SessionResumed")]
+    SessionResumed {
+        payload: SessionResumedPayload,
+    },
+
     // Debug messages
     #[serde(rename = "Debug")]
     Debug {
@@ -146,6 +206,18 @@ pub enum WsMessage {
         payload: ErrorPayload,
     },
 
+    // Background task failures, broadcast on the `errors` topic
+    #[serde(rename = "BackgroundError")]
+    BackgroundError {
+        payload: BackgroundErrorPayload,
+    },
+
+    // Connects and disconnects, broadcast on the `connections` topic
+    #[serde(rename = "ConnectionEvent")]
+    ConnectionEvent {
+        payload: ConnectionEventPayload,
+    },
+
     // Custom events
     #[serde(rename = "Custom")]
     Custom {
@@ -154,6 +226,21 @@ pub enum WsMessage {
     },
 }
 
+impl WsMessage {
+    /// Topic key used to look up the message's size limit in
+    /// `WsConfig::topic_message_size_limits`
+    pub fn size_limit_topic(&self) -> &'static str {
+        match self {
+            Self::DataUpdate { .. } => "data",
+            Self::NavigationUpdated { .. } => "navigation",
+            Self::JobEvent { .. } | Self::SubscribeToJobs { .. } | Self::UnsubscribeFromJobs { .. } => "jobs",
+            Self::Debug { .. } => "debug",
+            Self::Custom { .. } => "custom",
+            _ => "control",
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════════
 // MESSAGE PAYLOAD STRUCTURES
 // ═══════════════════════════════════════════════════════════════════════════════════
@@ -165,11 +252,62 @@ pub struct ConnectionDetails {
     #[serde(rename = "connectedAt")]
     pub connected_at: DateTime<Utc>,
     pub user_agent: Option<String>,
+    /// Country/ASN of the client address, when GeoIP is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo: Option<GeoInfo>,
+    /// Server banner, sent with the welcome message only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<ServerBanner>,
+    /// Token the client presents in a `Resume` message after reconnecting,
+    /// sent with the welcome message only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+}
+
+/// Location context for a client address from the GeoIP databases
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeoInfo {
+    /// ISO 3166-1 alpha-2 code, e.g. "DE"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asn: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_organization: Option<String>,
+}
+
+/// Server capabilities announced to clients at connect time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerBanner {
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motd: Option<String>,
+    /// Feature flags the client should honor (e.g. "compression", "binary_frames")
+    #[serde(default)]
+    pub features: BTreeMap<String, bool>,
+}
+
+impl Default for ServerBanner {
+    fn default() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            motd: None,
+            features: BTreeMap::from([
+                ("compression".to_string(), false),
+                ("binary_frames".to_string(), false),
+            ]),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubscribePayload {
     pub topics: Vec<String>,
+    /// Lowest level of `debug` topic messages to receive; all levels when omitted
+    #[serde(default)]
+    pub min_level: Option<DebugLevel>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -177,6 +315,27 @@ pub struct UnsubscribePayload {
     pub topics: Vec<String>,
 }
 
+/// Per-topic outcome of a Subscribe or Unsubscribe request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionResultPayload {
+    /// "subscribe" or "unsubscribe"
+    pub action: String,
+    pub results: BTreeMap<String, TopicResult>,
+}
+
+/// Outcome for a single topic; topics are applied independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopicResult {
+    Ok,
+    /// Not a recognized topic name
+    UnknownTopic,
+    /// Recognized but not permitted for this connection (e.g. another connection's direct topic)
+    Denied,
+    /// Unsubscribe from a topic the connection was not subscribed to
+    NotSubscribed,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionStats {
     pub count: usize,
@@ -191,19 +350,32 @@ pub struct ConnectionSummary {
     pub message_count: u64,
     pub bytes_sent: u64,
     pub bytes_received: u64,
+    /// Messages waiting in the connection's outbound queue
+    #[serde(default)]
+    pub queue_depth: usize,
+    /// Whether `queue_depth` is at or above `WsConfig::congested_queue_depth`
+    #[serde(default)]
+    pub congested: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo: Option<GeoInfo>,
 }
 
+/// A connection subscribed to a topic, as listed by GET /api/ws/subscriptions
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NavigationPayload {
-    pub schema: String,
-    pub data: serde_json::Value,
+pub struct TopicSubscriber {
+    pub connection_id: ConnectionId,
+    pub ip: String,
+    /// Job subscription filters; only set for entries under `job_events`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_type_filter: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileChangePayload {
-    pub path: String,
-    pub event_type: String,
-    pub timestamp: DateTime<Utc>,
+pub struct NavigationPayload {
+    pub schema: String,
+    pub data: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -224,6 +396,53 @@ pub struct JobEventPayload {
     pub timestamp: DateTime<Utc>,
     pub data: serde_json::Value,
     pub error: Option<String>,
+    /// Trace id of the Python execution that produced the event, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+}
+
+/// Job types accepted from external producers on `/jobs/broadcast`
+pub const ALLOWED_JOB_TYPES: &[&str] = &["backup", "restore", "upgrade", "validation"];
+
+/// Event types accepted from external producers on `/jobs/broadcast`
+pub const ALLOWED_JOB_EVENT_TYPES: &[&str] = &[
+    "OPERATION_START",
+    "OPERATION_PROGRESS",
+    "OPERATION_QUEUED",
+    "OPERATION_COMPLETE",
+    "STEP_START",
+    "STEP_UPDATE",
+    "STEP_COMPLETE",
+    "started",
+    "progress",
+    "completed",
+    "failed",
+    "cancelled",
+];
+
+/// Statuses accepted from external producers on `/jobs/broadcast`
+pub const ALLOWED_JOB_STATUSES: &[&str] = &["queued", "in_progress", "completed", "failed", "cancelled"];
+
+impl JobEventPayload {
+    /// Checks job_type, event_type and status against the allowed vocabulary,
+    /// naming every offending field and the accepted values
+    pub fn validate_vocabulary(job_type: &str, event_type: &str, status: &str) -> Result<(), String> {
+        let problems: Vec<String> = [
+            ("job_type", job_type, ALLOWED_JOB_TYPES),
+            ("event_type", event_type, ALLOWED_JOB_EVENT_TYPES),
+            ("status", status, ALLOWED_JOB_STATUSES),
+        ]
+        .iter()
+        .filter(|(_, value, allowed)| !allowed.contains(value))
+        .map(|(field, value, allowed)| format!("unknown {} '{}' (expected one of: {})", field, value, allowed.join(", ")))
+        .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
 }
 
 /// Payload for subscribing to job events with optional filtering
@@ -239,6 +458,44 @@ pub struct JobUnsubscribePayload {
     pub subscription_id: Option<String>,
 }
 
+/// Payload for resuming a previous session on a new connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumePayload {
+    pub session_token: String,
+}
+
+/// Subscriptions restored by a successful `Resume`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionResumedPayload {
+    pub session_token: String,
+    pub subscriptions: Vec<String>,
+    pub job_subscriptions: Vec<JobSubscription>,
+}
+
+/// Severity of a debug log entry, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DebugLevel {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl DebugLevel {
+    /// Level of a `DebugPayload::level` string; unknown levels count as `Info`
+    pub fn from_level(level: &str) -> Self {
+        match level.to_ascii_lowercase().as_str() {
+            "verbose" | "trace" => Self::Verbose,
+            "debug" => Self::Debug,
+            "warn" | "warning" => Self::Warn,
+            "error" => Self::Error,
+            _ => Self::Info,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebugPayload {
     pub level: String,
@@ -255,6 +512,41 @@ pub struct ErrorPayload {
     pub details: Option<String>,
 }
 
+/// Kind of connection lifecycle event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionEventKind {
+    Connected,
+    Disconnected,
+}
+
+/// A connection opening or closing, for live connection logs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionEventPayload {
+    pub event: ConnectionEventKind,
+    pub connection_id: ConnectionId,
+    /// Client address, when known
+    pub ip: Option<String>,
+    /// Why the connection closed; only set on `disconnected`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<CloseReason>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A background task that failed or panicked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackgroundErrorPayload {
+    /// Task that failed (e.g. "backup", "restore_rollback")
+    pub source: String,
+    pub job_id: Option<String>,
+    pub device: Option<String>,
+    /// Error message with credentials and tokens redacted
+    pub message: String,
+    /// Whether the task panicked rather than returning an error
+    pub panicked: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════════
 // CONNECTION INFO WITH METRICS
 // ═══════════════════════════════════════════════════════════════════════════════════
@@ -278,6 +570,11 @@ pub struct ConnectionInfo {
     pub ping_latency_ms: Option<u64>,
     // Job event subscriptions
     pub job_subscriptions: Vec<JobSubscription>,
+    /// Country/ASN of `remote_addr`, when GeoIP is configured
+    pub geo: Option<GeoInfo>,
+    /// Lowest level of `debug` topic messages sent to this connection; `None` sends all
+    #[serde(default)]
+    pub debug_min_level: Option<DebugLevel>,
 }
 
 /// Job subscription details for connection tracking
@@ -308,6 +605,8 @@ impl ConnectionInfo {
             bytes_received: 0,
             ping_latency_ms: None,
             job_subscriptions: Vec::new(),
+            geo: None,
+            debug_min_level: None,
         }
     }
 
@@ -353,6 +652,9 @@ impl ConnectionInfo {
             message_count: self.messages_sent + self.messages_received,
             bytes_sent: self.bytes_sent,
             bytes_received: self.bytes_received,
+            queue_depth: 0,
+            congested: false,
+            geo: self.geo.clone(),
         }
     }
 
@@ -384,12 +686,12 @@ impl ConnectionInfo {
 
         self.job_subscriptions.iter().any(|sub| {
             // Check device filter
-            let device_match = sub.device_filter.as_ref().map_or(true, |filter| {
+            let device_match = sub.device_filter.as_ref().is_none_or(|filter| {
                 filter == "*" || filter == &job_event.device
             });
             
             // Check job type filter
-            let job_type_match = sub.job_type_filter.as_ref().map_or(true, |filter| {
+            let job_type_match = sub.job_type_filter.as_ref().is_none_or(|filter| {
                 filter == "*" || filter == &job_event.job_type
             });
 
@@ -413,23 +715,32 @@ pub enum SubscriptionTopic {
     JobEventsForType(String),
     Debug,
     Metrics,
+    /// Background task failures (admin UI)
+    Errors,
+    /// Connect/disconnect events (admin UI connection log)
+    Connections,
+    /// Periodic server status beats (see `WsConfig::heartbeat_interval`)
+    Heartbeat,
     All,
     Direct(ConnectionId),
 }
 
-impl ToString for SubscriptionTopic {
-    fn to_string(&self) -> String {
+impl fmt::Display for SubscriptionTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Navigation => "navigation".to_string(),
-            Self::FileSystem => "filesystem".to_string(),
-            Self::DataUpdates(source) => format!("data:{}", source),
-            Self::JobEvents => "jobs:all".to_string(),
-            Self::JobEventsForDevice(device) => format!("jobs:device:{}", device),
-            Self::JobEventsForType(job_type) => format!("jobs:type:{}", job_type),
-            Self::Debug => "debug".to_string(),
-            Self::Metrics => "metrics".to_string(),
-            Self::All => "all".to_string(),
-            Self::Direct(id) => format!("direct:{}", id),
+            Self::Navigation => f.write_str("navigation"),
+            Self::FileSystem => f.write_str("filesystem"),
+            Self::DataUpdates(source) => write!(f, "data:{}", source),
+            Self::JobEvents => f.write_str("jobs:all"),
+            Self::JobEventsForDevice(device) => write!(f, "jobs:device:{}", device),
+            Self::JobEventsForType(job_type) => write!(f, "jobs:type:{}", job_type),
+            Self::Debug => f.write_str("debug"),
+            Self::Metrics => f.write_str("metrics"),
+            Self::Errors => f.write_str("errors"),
+            Self::Connections => f.write_str("connections"),
+            Self::Heartbeat => f.write_str("heartbeat"),
+            Self::All => f.write_str("all"),
+            Self::Direct(id) => write!(f, "direct:{}", id),
         }
     }
 }
@@ -441,6 +752,9 @@ impl From<&str> for SubscriptionTopic {
             "filesystem" => Self::FileSystem,
             "debug" => Self::Debug,
             "metrics" => Self::Metrics,
+            "errors" => Self::Errors,
+            "connections" => Self::Connections,
+            "heartbeat" => Self::Heartbeat,
             "jobs:all" => Self::JobEvents,
             s if s.starts_with("data:") => {
                 Self::DataUpdates(s.strip_prefix("data:").unwrap_or("").to_string())
@@ -463,6 +777,38 @@ impl From<&str> for SubscriptionTopic {
     }
 }
 
+/// Strict parsing: unknown topics are an error instead of falling back to `All`
+impl std::str::FromStr for SubscriptionTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let non_empty = |rest: Option<&str>| rest.filter(|rest| !rest.is_empty()).map(str::to_string);
+
+        let topic = match lower.as_str() {
+            "navigation" => Some(Self::Navigation),
+            "filesystem" => Some(Self::FileSystem),
+            "debug" => Some(Self::Debug),
+            "metrics" => Some(Self::Metrics),
+            "errors" => Some(Self::Errors),
+            "connections" => Some(Self::Connections),
+            "heartbeat" => Some(Self::Heartbeat),
+            "jobs:all" => Some(Self::JobEvents),
+            "all" => Some(Self::All),
+            other => non_empty(other.strip_prefix("data:")).map(Self::DataUpdates)
+                .or_else(|| non_empty(other.strip_prefix("jobs:device:")).map(Self::JobEventsForDevice))
+                .or_else(|| non_empty(other.strip_prefix("jobs:type:")).map(Self::JobEventsForType))
+                .or_else(|| {
+                    other.strip_prefix("direct:")
+                        .and_then(|id| Uuid::parse_str(id).ok())
+                        .map(Self::Direct)
+                }),
+        };
+
+        topic.ok_or_else(|| format!("Unknown topic '{}'", s))
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════════
 // SERVICE CONFIGURATION
 // ═══════════════════════════════════════════════════════════════════════════════════
@@ -478,10 +824,53 @@ pub struct WsConfig {
     pub collect_metrics: bool,
     pub max_message_size: usize,
     pub job_event_history_size: usize,
+    /// Inbound `Custom` event names clients may send; `None` permits any name (dev default)
+    pub allowed_custom_events: Option<HashSet<String>>,
+    /// Banner included in the welcome message; `None` sends the plain welcome
+    pub banner: Option<ServerBanner>,
+    /// Capacity of each connection's outbound message queue.
+    ///
+    /// Senders wait once the queue is full, so a slow client applies backpressure to
+    /// whoever is broadcasting. A larger queue absorbs longer bursts at the cost of
+    /// memory per connection; a smaller one saves memory but makes broadcasts stall sooner.
+    /// Set with WS_CHANNEL_CAPACITY.
+    pub channel_capacity: usize,
+    /// Outbound queue depth at which a connection is reported as congested; values above
+    /// `channel_capacity` are capped to it (see `congestion_threshold`).
+    /// Set with WS_CONGESTED_QUEUE_DEPTH; defaults to three quarters of `channel_capacity`.
+    pub congested_queue_depth: usize,
+    /// How long a disconnected session can still be resumed
+    pub session_idle_timeout: std::time::Duration,
+    /// Broadcasts per second accepted from the `/broadcast` and `/jobs/broadcast`
+    /// endpoints, across all callers; `None` disables the limit.
+    /// Set with BROADCAST_RATE_LIMIT (0 disables).
+    pub broadcast_rate_limit: Option<u32>,
+    /// Inbound size limits in bytes per message topic (see `WsMessage::size_limit_topic`);
+    /// topics not listed use `max_message_size`. Defaults:
+    /// - `data`: 4MB, large data updates
+    /// - `custom`: 16KB, client events fanned out to every connection
+    /// - `debug`: 16KB
+    /// - `control`: 64KB (ping, subscribe, resume, connection requests)
+    pub topic_message_size_limits: HashMap<String, usize>,
+    /// Interval of the `heartbeat` Custom event sent to `heartbeat` topic subscribers;
+    /// `None` (the default) disables it. Set with WS_HEARTBEAT_INTERVAL_SECS (0 disables).
+    pub heartbeat_interval: Option<std::time::Duration>,
+    /// Deepest array/object nesting accepted in an inbound message, checked before parsing.
+    /// Set with WS_MAX_MESSAGE_DEPTH.
+    pub max_message_depth: usize,
+    /// Most array items and object members accepted in an inbound message, checked before
+    /// parsing. Set with WS_MAX_MESSAGE_ELEMENTS.
+    pub max_message_elements: usize,
+    /// Attempts at sending the welcome message before the connection is dropped (at least 1).
+    /// Set with WS_WELCOME_SEND_ATTEMPTS.
+    pub welcome_send_attempts: u32,
+    /// Pause between welcome-message send attempts. Set with WS_WELCOME_SEND_RETRY_DELAY_MS.
+    pub welcome_send_retry_delay: std::time::Duration,
 }
 
 impl Default for WsConfig {
     fn default() -> Self {
+        let channel_capacity = env_or("WS_CHANNEL_CAPACITY", 100).max(1);
         Self {
             ping_interval: std::time::Duration::from_secs(30),
             connection_timeout: std::time::Duration::from_secs(300),
@@ -491,6 +880,55 @@ impl Default for WsConfig {
             collect_metrics: true,
             max_message_size: 1024 * 1024, // 1MB
             job_event_history_size: 1000,   // Keep last 1000 job events
+            allowed_custom_events: None,    // Permissive for development
+            banner: Some(ServerBanner::default()),
+            channel_capacity,
+            congested_queue_depth: env_or("WS_CONGESTED_QUEUE_DEPTH", channel_capacity * 3 / 4).max(1),
+            session_idle_timeout: std::time::Duration::from_secs(300),
+            broadcast_rate_limit: Some(env_or("BROADCAST_RATE_LIMIT", 100)).filter(|limit| *limit > 0),
+            topic_message_size_limits: HashMap::from([
+                ("data".to_string(), 4 * 1024 * 1024),
+                ("custom".to_string(), 16 * 1024),
+                ("debug".to_string(), 16 * 1024),
+                ("control".to_string(), 64 * 1024),
+            ]),
+            heartbeat_interval: Some(env_or("WS_HEARTBEAT_INTERVAL_SECS", 0))
+                .filter(|secs| *secs > 0)
+                .map(std::time::Duration::from_secs),
+            max_message_depth: env_or("WS_MAX_MESSAGE_DEPTH", 32).max(1),
+            max_message_elements: env_or("WS_MAX_MESSAGE_ELEMENTS", 10_000).max(1),
+            welcome_send_attempts: env_or("WS_WELCOME_SEND_ATTEMPTS", 3).max(1),
+            welcome_send_retry_delay: std::time::Duration::from_millis(env_or("WS_WELCOME_SEND_RETRY_DELAY_MS", 200)),
         }
     }
 }
+
+impl WsConfig {
+    /// Whether an inbound `Custom` event with this name may be forwarded
+    pub fn is_custom_event_allowed(&self, event: &str) -> bool {
+        self.allowed_custom_events
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(event))
+    }
+
+    /// Inbound size limit for a message topic, falling back to `max_message_size`
+    pub fn message_size_limit(&self, topic: &str) -> usize {
+        self.topic_message_size_limits
+            .get(topic)
+            .copied()
+            .unwrap_or(self.max_message_size)
+    }
+
+    /// Queue depth at which a connection counts as congested, never above the queue's capacity
+    pub fn congestion_threshold(&self) -> usize {
+        self.congested_queue_depth.clamp(1, self.channel_capacity.max(1))
+    }
+
+    /// Largest size any inbound message may have, checked before parsing
+    pub fn largest_message_size_limit(&self) -> usize {
+        self.topic_message_size_limits
+            .values()
+            .copied()
+            .fold(self.max_message_size, usize::max)
+    }
+}
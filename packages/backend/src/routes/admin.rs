@@ -0,0 +1,49 @@
+// =========================================================================================
+// File Path: src/routes/admin.rs
+// Version: 1.0.0
+//
+// Description:
+// Admin-scoped operational endpoints for the service itself.
+//
+// Key Features:
+// - Background task health: last tick per periodic task and a healthy/stalled flag
+// - Process uptime
+//
+// Usage Guide:
+// - GET /api/admin/tasks → { started_at, uptime_secs, healthy, tasks: [...] } (requires admin)
+//   A task is stalled when it missed two of its expected intervals or its loop has exited.
+//
+// Change Log:
+// - 1.0.0: Initial implementation with background task health
+// =========================================================================================
+
+use axum::{extract::State, routing::get, Json, Router};
+use tracing::warn;
+
+use crate::{middleware::admin::AdminAccess, AppState};
+
+/// Lists background tasks with their last heartbeat and health
+async fn get_task_health(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+) -> Json<serde_json::Value> {
+    let tasks = state.task_health.statuses();
+    let healthy = tasks.iter().all(|task| task.healthy);
+    if !healthy {
+        for task in tasks.iter().filter(|task| !task.healthy) {
+            warn!("Background task '{}' is stalled (running: {})", task.name, task.running);
+        }
+    }
+
+    Json(serde_json::json!({
+        "started_at": state.task_health.started_at().to_rfc3339(),
+        "uptime_secs": state.task_health.uptime().as_secs(),
+        "healthy": healthy,
+        "tasks": tasks,
+    }))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/admin/tasks", get(get_task_health))
+}
@@ -1,6 +1,6 @@
 // =============================================================================
 // File Path: src/routes/backups.rs
-// Version: 1.3.0
+// Version: 1.8.0
 //
 // Description:
 // API router for all backup-related endpoints.
@@ -9,14 +9,19 @@
 // - Aggregates routes for listing devices, listing files, getting content, and running backups.
 //
 // Change Log:
+// - 1.8.0: Added synchronous backup route.
+// - 1.7.0: Added cancel-backup-by-device route.
+// - 1.6.0: Added batch backup route.
+// - 1.5.0: Added device bundle export route.
+// - 1.4.0: Added device backup archive download route.
 // - 1.3.0: Removed unused imports to fix compiler warnings.
 // - 1.2.0: Unified GET and POST routes for /api/backups/devices to a single handler.
 // - 1.1.0: Added explicit GET and POST routes for backup and restore.
 // - 1.0.0: Initial implementation of the backups router.
 // =============================================================================
 
-use axum::{routing::get, Router};
-use crate::{api::backups, AppState};
+use axum::{routing::{delete, get, post}, Router};
+use crate::{api::{backup_batch, backups}, AppState};
 
 // =============================================================================
 // Route Configuration
@@ -27,6 +32,11 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         // Unified handler for both GET (list) and POST (run) for /api/backups/devices
         .route("/api/backups/devices", get(backups::backups_handler).post(backups::backups_handler))
+        .route("/api/backups/batch", post(backup_batch::run_backup_batch))
+        .route("/api/backups/run-sync", post(backups::run_backup_sync))
         .route("/api/backups/device/:device_name", get(backups::list_device_backups))
+        .route("/api/backups/device/:device_name/archive", get(backups::download_device_archive))
+        .route("/api/backups/device/:device_name/bundle", get(backups::get_device_bundle))
+        .route("/api/backups/device/:device_name/job", delete(backups::cancel_device_backup))
         .route("/api/backups/file/:device_name/:filename", get(backups::get_backup_file))
 }
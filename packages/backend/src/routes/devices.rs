@@ -0,0 +1,84 @@
+// =========================================================================================
+// File Path: src/routes/devices.rs
+// Version: 1.1.0
+//
+// Description:
+// Device-level operational state across operations.
+//
+// Key Features:
+// - Lists the device locks currently held, so operators can see why a new restore or
+//   upgrade on a device is rejected
+// - Lists the in-flight backup jobs alongside them
+//
+// Usage Guide:
+// - GET /api/devices/locks → { total, locks: [{ device, operation, job_id, acquired_at, age_secs }],
+//   backups: [{ device, job_id, started_at, age_secs }] }
+//   Oldest first. Restores and upgrades hold a device's lock while they run; restores
+//   are not tracked jobs, so their `job_id` is null. Backups do not take the device lock:
+//   the backup pool only caps how many run at once and enforces a per-device cooldown
+//   between backups (skipped with `force`), so a backup can overlap a restore or upgrade.
+//   `backups` lists the running and queued backup jobs so that overlap is visible.
+//
+// Change Log:
+// - 1.1.0: In-flight backup jobs are listed under `backups`
+// - 1.0.0: Initial implementation with device lock status
+// =========================================================================================
+
+use axum::{extract::State, routing::get, Json, Router};
+use chrono::Utc;
+
+use crate::AppState;
+
+/// Job type of backups in the job service
+const BACKUP_JOB_TYPE: &str = "backup";
+
+/// Lists the devices whose lock is held, with the holding operation and its age,
+/// and the backup jobs in flight
+async fn get_device_locks(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let locks = state.device_lock_service.holders();
+
+    let now = Utc::now();
+    let mut backups = state.job_service.list_jobs().await;
+    backups.retain(|job| job.job_type == BACKUP_JOB_TYPE);
+    backups.sort_by(|a, b| a.started_at.cmp(&b.started_at).then_with(|| a.device.cmp(&b.device)));
+    let backups: Vec<serde_json::Value> = backups
+        .into_iter()
+        .map(|job| serde_json::json!({
+            "device": job.device,
+            "job_id": job.job_id,
+            "started_at": job.started_at,
+            "age_secs": (now - job.started_at).num_seconds().max(0),
+        }))
+        .collect();
+
+    Json(serde_json::json!({
+        "total": locks.len(),
+        "locks": locks,
+        "backups": backups,
+    }))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/devices/locks", get(get_device_locks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    #[tokio::test]
+    async fn running_backups_are_listed_beside_the_locks() {
+        let app = TestApp::new().await;
+        let _restore = app.state.device_lock_service.try_lock("r1", "restore", None).await.unwrap();
+        app.state.job_service.register("job-1", "r2", BACKUP_JOB_TYPE).await;
+        app.state.job_service.register("job-2", "r3", "upgrade").await;
+
+        let Json(body) = get_device_locks(State(app.state.clone())).await;
+        assert_eq!(body["total"], 1);
+        assert_eq!(body["locks"][0]["operation"], "restore");
+        assert_eq!(body["backups"].as_array().unwrap().len(), 1);
+        assert_eq!((body["backups"][0]["device"].as_str(), body["backups"][0]["job_id"].as_str()), (Some("r2"), Some("job-1")));
+    }
+}
@@ -1,8 +1,15 @@
 //! Health Check Routes
 //! 
-//! Provides health monitoring and system status endpoints
+//! Provides health monitoring and system status endpoints,
+//! including per-route request metrics in the Prometheus text format
 
-use axum::{routing::get, Router};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
 use crate::AppState;
 
 /// Health check endpoint
@@ -11,8 +18,53 @@ pub async fn health_check() -> &'static str {
     "OK"
 }
 
+/// Readiness endpoint
+/// Returns 503 while WebSocket connections are draining, so load balancers
+/// stop routing new clients here during maintenance
+pub async fn readiness(State(state): State<AppState>) -> impl IntoResponse {
+    let draining = state.websocket_service.is_draining();
+    let status = if draining { StatusCode::SERVICE_UNAVAILABLE } else { StatusCode::OK };
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if draining { "draining" } else { "ready" },
+            "draining": draining,
+        })),
+    )
+}
+
+/// Per-route metrics endpoint
+/// Returns request counts, 5xx counts and latency histograms labelled by route,
+/// plus the device list circuit breaker state
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let mut body = state.route_metrics_service.render_prometheus().await;
+    body.push_str(&state.device_list_service.render_prometheus().await);
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body,
+    )
+}
+
 /// Creates health-related routes
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/health", get(health_check))
+        .route("/health/ready", get(readiness))
+        .route("/metrics", get(metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    #[tokio::test]
+    async fn readiness_answers_503_while_draining() {
+        let app = TestApp::new().await;
+        let ready = || async { readiness(State(app.state.clone())).await.into_response().status() };
+        assert_eq!(ready().await, StatusCode::OK);
+
+        app.state.websocket_service.set_draining(true);
+        assert_eq!(ready().await, StatusCode::SERVICE_UNAVAILABLE);
+    }
 }
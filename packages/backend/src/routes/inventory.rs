@@ -1,5 +1,5 @@
 // File Path: src/routes/inventory.rs
-// Version: 1.1.0
+// Version: 1.5.0
 //
 // Description:
 // Defines routes for network inventory API.
@@ -13,8 +13,16 @@
 // - GET /api/inventory → returns full inventory
 // - GET /api/inventory/list → lists all YAML files in inventories directory
 // - GET /api/inventory/file/:filename → returns specific inventory file
+// - PUT /api/inventory/file/:filename → validates and writes specific inventory file
+// - GET /api/inventory/grouped?by=site|role|vendor|platform → devices grouped by attribute
+// - GET /api/inventory/autocomplete?q=cor&limit=10 → ranked hostname suggestions
+// - GET /api/inventory/audit?limit=50 → recorded inventory edits, newest first (admin only)
 //
 // Change Log:
+// - 1.5.0: Added inventory audit log route
+// - 1.4.0: Added device autocomplete route
+// - 1.3.0: Added grouped inventory route
+// - 1.2.0: Added PUT route for writing inventory files
 // - 1.1.0: Added routes for listing and accessing inventory files
 // - 1.0.0: Initial implementation
  
@@ -31,9 +39,19 @@ pub fn routes() -> Router<AppState> {
         // Main inventory endpoint
         .route("/api/inventory", get(inventory::get_inventory))
  
+        // Devices grouped by site, role, vendor or platform
+        .route("/api/inventory/grouped", get(inventory::get_grouped_inventory))
+
+        // Hostname type-ahead for device pickers
+        .route("/api/inventory/autocomplete", get(inventory::autocomplete_devices))
+        .route("/api/inventory/audit", get(inventory::get_inventory_audit))
+ 
         // List all inventory files
         .route("/api/inventory/list", get(inventory::list_inventory_files))
  
-        // Get specific inventory file
-        .route("/api/inventory/file/:filename", get(inventory::get_inventory_file))
+        // Get or update specific inventory file
+        .route(
+            "/api/inventory/file/:filename",
+            get(inventory::get_inventory_file).put(inventory::update_inventory_file),
+        )
 }
\ No newline at end of file
@@ -0,0 +1,296 @@
+// =========================================================================================
+// File Path: src/routes/jobs.rs
+// Version: 1.3.2
+//
+// Description:
+// Routes for managing in-flight device jobs (backup, restore, ...).
+//
+// Key Features:
+// - Admin-scoped cancel-all for runaway workloads
+// - Broadcasts a cancellation job event for every cancelled job
+// - Dev-only synthetic job that emits started → progress → completed events
+// - Per-type activity summary across device jobs and Python executions
+// - Latest retained event per job for clients reconnecting mid-job
+//
+// Usage Guide:
+// - POST /api/jobs/cancel-all → cancels all in-flight jobs (requires X-Admin-Token)
+// - POST /api/jobs/test → { device?, job_type?, duration_secs?, steps? } emits a scripted
+//   job event sequence (requires ENABLE_TEST_JOBS=true)
+// - GET /api/jobs/summary → per job type in_progress/completed/failed/cancelled counts
+// - GET /api/jobs/:job_id/latest → most recent retained job event for the job (404 if none)
+//
+// Change Log:
+// - 1.3.2: ENABLE_TEST_JOBS is read through config::env_or
+// - 1.3.1: Report runs are counted under `report` instead of `python`
+// - 1.3.0: Added latest-event endpoint for re-hydrating a job's progress after a reconnect
+// - 1.2.0: Added job activity summary endpoint
+// - 1.1.0: Added dev-only synthetic job endpoint for exercising job-event subscriptions
+// - 1.0.0: Initial implementation with cancel-all
+// =========================================================================================
+
+use axum::{extract::{Path, State}, http::StatusCode, routing::{get, post}, Json, Router};
+use chrono::Utc;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    config::env_or,
+    middleware::admin::AdminAccess,
+    routes::reports::REPORT_RUNNER_SCRIPT,
+    models::{websocket::JobEventPayload, ApiError, ApiResult, CancelAllResult},
+    services::{job_service::JobTypeSummary, ExecutionStatus},
+    AppState,
+};
+
+/// Job types always present in the summary, even with no activity
+const SUMMARY_JOB_TYPES: [&str; 4] = ["backup", "restore", "python", "report"];
+
+/// Longest sequence a synthetic job may run for
+const MAX_TEST_JOB_DURATION_SECS: u64 = 300;
+
+/// Most progress events a synthetic job may emit
+const MAX_TEST_JOB_STEPS: u32 = 100;
+
+/// Request body for a synthetic test job
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TestJobRequest {
+    /// Fake device name; subscriptions filtered on it receive the events
+    pub device: Option<String>,
+    /// Job type; subscriptions filtered on it receive the events
+    pub job_type: Option<String>,
+    /// Total time from the start to the completion event
+    pub duration_secs: Option<u64>,
+    /// Number of progress events between start and completion
+    pub steps: Option<u32>,
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Cancels all in-flight jobs (admin-scoped)
+///
+/// Jobs that cannot be cancelled are reported in `failed` instead of aborting the operation.
+pub async fn cancel_all_jobs(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+) -> Json<CancelAllResult> {
+    warn!("Cancel-all requested for jobs");
+
+    let (cancelled_jobs, result) = state.job_service.cancel_all().await;
+
+    for job in cancelled_jobs {
+        let event = JobEventPayload {
+            job_id: job.job_id.clone(),
+            device: job.device,
+            job_type: job.job_type,
+            event_type: "OPERATION_COMPLETE".to_string(),
+            status: "cancelled".to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({ "message": "Job cancelled by administrator" }),
+            error: None,
+            trace_id: None,
+        };
+        if let Err(e) = state.websocket_service.broadcast_job_event(event).await {
+            warn!("Failed to broadcast cancellation for job {}: {}", job.job_id, e);
+        }
+    }
+
+    info!(
+        "Cancel-all jobs completed: {} cancelled, {} failed",
+        result.cancelled.len(),
+        result.failed.len()
+    );
+    Json(result)
+}
+
+/// Emits a scripted started → progress → completed job event sequence (dev-only)
+///
+/// Disabled unless the ENABLE_TEST_JOBS environment variable is `true`. Events go
+/// through the normal job-event broadcast, so subscription filters apply.
+pub async fn run_test_job(
+    State(state): State<AppState>,
+    body: Option<Json<TestJobRequest>>,
+) -> ApiResult<(StatusCode, Json<serde_json::Value>)> {
+    if !env_or("ENABLE_TEST_JOBS", false) {
+        return Err(ApiError::Forbidden("Test jobs are disabled".to_string()));
+    }
+
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    let job_id = format!("test-{}", Uuid::new_v4());
+    let device = request.device.unwrap_or_else(|| "test-device".to_string());
+    let job_type = request.job_type.unwrap_or_else(|| "test".to_string());
+    let duration_secs = request.duration_secs.unwrap_or(5).min(MAX_TEST_JOB_DURATION_SECS);
+    let steps = request.steps.unwrap_or(5).clamp(1, MAX_TEST_JOB_STEPS);
+
+    info!("Starting test job {} for {} ({} steps over {}s)", job_id, device, steps, duration_secs);
+
+    let websocket_service = state.websocket_service.clone();
+    let (task_job_id, task_device, task_job_type) = (job_id.clone(), device.clone(), job_type.clone());
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(duration_secs) / (steps + 1);
+        let event = |event_type: &str, status: &str, step: u32| JobEventPayload {
+            job_id: task_job_id.clone(),
+            device: task_device.clone(),
+            job_type: task_job_type.clone(),
+            event_type: event_type.to_string(),
+            status: status.to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({
+                "message": "Synthetic test job",
+                "step": step,
+                "total_steps": steps,
+            }),
+            error: None,
+            trace_id: None,
+        };
+
+        let mut sequence = vec![event("OPERATION_START", "in_progress", 0)];
+        for step in 1..=steps {
+            sequence.push(event("OPERATION_PROGRESS", "in_progress", step));
+        }
+        sequence.push(event("OPERATION_COMPLETE", "completed", steps));
+
+        for (index, mut job_event) in sequence.into_iter().enumerate() {
+            if index > 0 {
+                tokio::time::sleep(interval).await;
+            }
+            job_event.timestamp = Utc::now();
+            if let Err(e) = websocket_service.broadcast_job_event(job_event).await {
+                warn!("Failed to broadcast test job event for {}: {}", task_job_id, e);
+            }
+        }
+
+        info!("Test job {} completed", task_job_id);
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "job_id": job_id,
+            "device": device,
+            "job_type": job_type,
+            "duration_secs": duration_secs,
+            "steps": steps,
+        })),
+    ))
+}
+
+/// Per job type activity counts
+///
+/// Device jobs come from the job registry; Python executions are counted from
+/// the runner's execution records, under `report` for report runs and `python` otherwise.
+pub async fn get_jobs_summary(
+    State(state): State<AppState>,
+) -> Json<BTreeMap<String, JobTypeSummary>> {
+    let mut summary = state.job_service.summary().await;
+    for job_type in SUMMARY_JOB_TYPES {
+        summary.entry(job_type.to_string()).or_default();
+    }
+
+    let now = SystemTime::now();
+    for execution in state.python_runner_service.list_executions(None, None, None).await {
+        let job_type = if execution.script_path == REPORT_RUNNER_SCRIPT { "report" } else { "python" };
+        let counts = summary.entry(job_type.to_string()).or_default();
+        match execution.status {
+            ExecutionStatus::Pending | ExecutionStatus::Running => {
+                counts.in_progress += 1;
+                let age = execution.start_time
+                    .and_then(|started| now.duration_since(started).ok())
+                    .map(|age| age.as_secs() as i64);
+                if let Some(age) = age {
+                    counts.oldest_in_flight_secs = Some(counts.oldest_in_flight_secs.map_or(age, |oldest| oldest.max(age)));
+                }
+            }
+            ExecutionStatus::Completed => counts.completed += 1,
+            ExecutionStatus::Failed | ExecutionStatus::TimedOut => counts.failed += 1,
+            ExecutionStatus::Cancelled => counts.cancelled += 1,
+        }
+    }
+
+    Json(summary)
+}
+
+/// Most recent retained event for a job
+///
+/// Cheaper than a full timeline; lets a reconnecting client restore a single progress view.
+pub async fn get_latest_job_event(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobEventPayload>, ApiError> {
+    state
+        .websocket_service
+        .latest_job_event(&job_id)
+        .await
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("No retained events for job '{}'", job_id)))
+}
+
+// =============================================================================
+// Route Configuration
+// =============================================================================
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/jobs/cancel-all", post(cancel_all_jobs))
+        .route("/api/jobs/test", post(run_test_job))
+        .route("/api/jobs/summary", get(get_jobs_summary))
+        .route("/api/jobs/:job_id/latest", get(get_latest_job_event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    #[tokio::test]
+    async fn report_runs_are_summarized_apart_from_python() {
+        let app = TestApp::new().await;
+        let runner = &app.state.python_runner_service;
+        runner.insert_finished_execution(REPORT_RUNNER_SCRIPT, ExecutionStatus::Completed, "").await;
+        runner.insert_finished_execution("scripts/run.py", ExecutionStatus::Failed, "").await;
+
+        let Json(summary) = get_jobs_summary(State(app.state.clone())).await;
+        assert_eq!((summary["report"].completed, summary["report"].failed), (1, 0));
+        assert_eq!((summary["python"].completed, summary["python"].failed), (0, 1));
+        assert_eq!(summary["backup"].completed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_jobs_are_forbidden_unless_enabled() {
+        let app = TestApp::new().await;
+        let result = run_test_job(State(app.state.clone()), None).await;
+        assert!(matches!(result, Err(ApiError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn latest_event_is_the_last_broadcast_or_404() {
+        let app = TestApp::new().await;
+        let missing = get_latest_job_event(State(app.state.clone()), Path("job-1".to_string())).await;
+        assert!(matches!(missing, Err(ApiError::NotFound(_))));
+
+        for status in ["running", "completed"] {
+            let event = JobEventPayload {
+                job_id: "job-1".to_string(),
+                device: "r1".to_string(),
+                job_type: "backup".to_string(),
+                event_type: "progress".to_string(),
+                status: status.to_string(),
+                timestamp: Utc::now(),
+                data: serde_json::Value::Null,
+                error: None,
+                trace_id: None,
+            };
+            app.state.websocket_service.broadcast_job_event(event).await.unwrap();
+        }
+
+        let Json(latest) = get_latest_job_event(State(app.state.clone()), Path("job-1".to_string())).await.unwrap();
+        assert_eq!(latest.status, "completed");
+    }
+}
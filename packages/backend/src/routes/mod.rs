@@ -1,7 +1,7 @@
 
 // =========================================================================================
 // File Path: src/routes/mod.rs
-// Version: 1.4.0
+// Version: 1.9.0
 //
 // Description:
 // Routes module that organizes all API routes into logical groups.
@@ -19,7 +19,15 @@
 // 2. Import it here
 // 3. Add it to the merge chain in create_routes()
 //
+// Set API_BASE_PATH (e.g. `/backend`) to serve every route, including /ws and
+// /health, under that prefix when running behind a reverse proxy path.
+//
 // Change Log:
+// - 1.9.0: Added device routes
+// - 1.8.0: Added admin routes
+// - 1.7.0: Added upgrade routes
+// - 1.6.0: Added configurable base path (API_BASE_PATH) applied to every route
+// - 1.5.0: Added jobs routes
 // - 1.4.0: Added restore routes
 // - 1.3.0: Added sidebar and backups routes
 // =========================================================================================
@@ -38,19 +46,59 @@ mod inventory;
 mod sidebar;   // Sidebar routes
 mod backups;   // Backup routes
 mod restore;   // ✅ New restore routes
+mod jobs;      // Job management routes
+mod upgrade;   // Firmware/OS upgrade routes
+mod admin;     // Admin operational routes
+mod devices;   // Device lock status routes
+
+/// Reads the route prefix from API_BASE_PATH
+///
+/// Normalized to a leading slash and no trailing slash; empty means routes
+/// are served from the root.
+pub fn base_path_from_env() -> String {
+    normalize_base_path(&std::env::var("API_BASE_PATH").unwrap_or_default())
+}
+
+fn normalize_base_path(raw: &str) -> String {
+    let trimmed = raw.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
 
 /// Creates and configures all application routes
 ///
 /// This function assembles all route modules into a single router,
 /// making it easy to manage and extend the API surface.
 ///
+/// # Arguments
+/// * `base_path` - Prefix every route is served under (see `base_path_from_env`); empty for none
+///
 /// # Returns
 /// A configured Router with all application routes
-pub fn create_routes() -> Router<AppState> {
+pub fn create_routes(base_path: &str) -> Router<AppState> {
+    with_base_path(all_routes(), base_path)
+}
+
+/// Nests `routes` under `base_path`, or returns them unchanged when it is empty
+fn with_base_path(routes: Router<AppState>, base_path: &str) -> Router<AppState> {
+    if base_path.is_empty() {
+        routes
+    } else {
+        Router::new().nest(base_path, routes)
+    }
+}
+
+fn all_routes() -> Router<AppState> {
     Router::new()
         // Health monitoring routes
         .merge(health::routes())
 
+        // Admin operational routes
+        .merge(admin::routes())
+
         // YAML data management routes
         .merge(yaml::routes())
 
@@ -63,6 +111,15 @@ pub fn create_routes() -> Router<AppState> {
         // Restore Routes
         .merge(restore::routes())
 
+        // Upgrade Routes
+        .merge(upgrade::routes())
+
+        // Job management routes
+        .merge(jobs::routes())
+
+        // Device lock status routes
+        .merge(devices::routes())
+
         // Sidebar configuration routes
         .merge(sidebar::routes())
 
@@ -78,3 +135,29 @@ pub fn create_routes() -> Router<AppState> {
         // Python script execution routes
         .merge(python::routes())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+    use axum::{body::Body, http::{Request, StatusCode}};
+    use tower::Service;
+
+    #[test]
+    fn base_paths_get_one_leading_slash_and_no_trailing_one() {
+        assert_eq!(normalize_base_path(""), "");
+        assert_eq!(normalize_base_path(" / "), "");
+        assert_eq!(normalize_base_path("backend/"), "/backend");
+        assert_eq!(normalize_base_path("/api/v2/"), "/api/v2");
+    }
+
+    #[tokio::test]
+    async fn routes_are_served_only_under_the_base_path() {
+        let app = TestApp::new().await;
+        let mut router = with_base_path(health::routes(), "/backend").with_state(app.state.clone());
+        let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        assert_eq!(router.call(request("/backend/health")).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(router.call(request("/health")).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,5 +1,5 @@
 // File Path: backend/src/routes/navigation.rs
-// Version: 3.0.0
+// Version: 3.1.0
 // Description: Defines Axum routes for navigation-related API endpoints.
 // Key Features:
 // - Exposes endpoints for retrieving navigation configurations.
@@ -10,6 +10,7 @@
 // 2. Place navigation.yaml and navigation.schema.json in shared/data and shared/schemas, respectively.
 // 3. Access endpoints like /api/navigation/yaml to retrieve validated navigation data.
 // Change Log:
+// - 3.1.0 (2026-10-16): Added /api/navigation/form-schema.
 // - 3.0.0 (2025-09-13): Integrated schema validation through yaml_service.
 // - 2.0.0 (2025-09-13): Added YAML backend loading.
 // - 1.0.0 (2025-09-10): Initial placeholder navigation API.
@@ -29,4 +30,5 @@ pub fn routes() -> Router<AppState> {
         .route("/api/navigation", get(crate::api::navigation::get_navigation))
         .route("/api/navigation/yaml", get(crate::api::navigation::get_navigation_from_yaml))
         .route("/api/navigation/settings", get(crate::api::navigation::get_settings_navigation))
+        .route("/api/navigation/form-schema", get(crate::api::navigation::get_navigation_form_schema))
 }
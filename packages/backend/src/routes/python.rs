@@ -1,5 +1,5 @@
 // File Path: src/routes/python.rs
-// Version: 1.0.6
+// Version: 1.2.2
 // Description: Python execution routes module.
 // Updated to work with the new PythonRunnerService interface.
 //
@@ -15,10 +15,32 @@
 // POST   /api/python/execute       - Execute a Python script
 // GET    /api/python/status/:id    - Check execution status
 // GET    /api/python/execution/:id - Get full execution details
-// GET    /api/python/executions    - List all executions
+// GET    /api/python/execution/:id/output - Get raw output bytes
+// GET    /api/python/execution/:id/events - Job events linked to the execution by trace id
+// GET    /api/python/executions    - List all executions (?status, ?limit, ?offset, ?since, ?format=json|ndjson,
+//                                     ?envelope=true for { executions, total, limit, offset, queued, as_of })
 // DELETE /api/python/execution/:id - Cancel a running execution
+// POST   /api/python/cancel-all    - Cancel all running executions (admin)
 //
 // Change Log:
+// - 1.2.2: Queued executions can be cancelled; 409 only for ones already finished
+// - 1.2.1: The executions list is a plain array again, as before `since`; `as_of`, `total` and
+//   `queued` move to X-As-Of, X-Total-Count and X-Queued-Executions. `envelope=true` returns the object
+// - 1.2.0: execute accepts `priority` (low, normal, high, urgent) for the execution queue; the
+//   executions list reports how many executions are `queued`
+// - 1.1.9: Executions list is paginated; `limit` defaults to and is clamped by the shared page-size bounds
+// - 1.1.8: Reject requests whose args/env_vars exceed the runner's input limits with 400
+// - 1.1.7: Execution details include the execution environment (container, image digest, host)
+// - 1.1.6: Added execution events endpoint listing job events linked by trace id
+// - 1.1.5: Status filter parses through ExecutionStatus so it accepts exactly the emitted names
+// - 1.1.4: Added run_as container user override, restricted to the runner's allowlist
+// - 1.1.3: Cancel returns 404 for unknown executions and 409 for ones not running or already finished
+// - 1.1.2: Added raw output endpoint for executions with binary output
+// - 1.1.1: Added `format=ndjson` streaming variant of the executions list
+// - 1.1.0: execute returns ApiResult with the shared ApiError envelope; removed ErrorResponse
+// - 1.0.9: Added admin-scoped cancel-all endpoint
+// - 1.0.8: Added env_preset to execution requests
+// - 1.0.7: Added `since` filter and `as_of` timestamp to the executions list
 // - 1.0.6: Fixed type consistency in get_execution_details
 // - 1.0.5: Fixed type mismatches and missing warn import
 // - 1.0.4: Fixed return type issues and improved error handling
@@ -29,18 +51,22 @@
 
 use axum::{
     Router,
+    body::{Body, Bytes},
     routing::{get, post, delete},
     extract::{Path, Query, State},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
-    http::StatusCode,
+    http::{header, StatusCode},
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, convert::Infallible};
+use tokio_stream::StreamExt;
 use tracing::{info, error, debug, warn};
 
 use crate::AppState;
-use crate::services::ExecutionStatus;
+use crate::middleware::admin::AdminAccess;
+use crate::models::{ApiError, ApiResult, CancelAllResult};
+use crate::services::{python_runner::CancelError, ExecutionPriority, ExecutionStatus};
 
 // =============================================================================
 // SECTION 1: REQUEST AND RESPONSE TYPES
@@ -64,9 +90,24 @@ pub struct ExecutePythonRequest {
     #[serde(default)]
     pub env_vars: HashMap<String, String>,
 
+    /// Optional named environment preset from the runner configuration
+    /// Inline `env_vars` are merged on top and take precedence
+    /// Example: "prod", "lab"
+    pub env_preset: Option<String>,
+
+    /// Optional container user (`uid[:gid]`) to run the script as
+    /// Must be the configured default or listed in PYTHON_RUNNER_ALLOWED_USERS
+    /// Example: "1001:1001"
+    pub run_as: Option<String>,
+
     /// Optional WebSocket client ID for real-time output streaming
-    /// If provided, execution output will be streamed via WebSocket
+    /// If provided, the client receives a `running` JobEvent when the execution leaves the queue
     pub websocket_client_id: Option<String>,
+
+    /// Queue priority: "low", "normal" (default), "high" or "urgent"
+    /// Higher priorities take the next free execution slot first
+    #[serde(default)]
+    pub priority: ExecutionPriority,
 }
 
 /// Execution response containing execution ID and status
@@ -91,21 +132,27 @@ pub struct ListExecutionsQuery {
     /// Example: "running", "completed", "failed"
     pub status: Option<String>,
     
-    /// Optional limit on number of results to return
+    /// Page size; defaults to and is clamped by the shared page-size bounds
     /// Example: 10, 25, 50
     pub limit: Option<usize>,
-}
 
-/// Standard error response format for API errors
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    /// Error type or category for client handling
-    /// Example: "Invalid request", "Execution not found", "Failed to execute script"
-    pub error: String,
-    
-    /// Detailed error information for debugging (optional)
-    /// Example: "Script path cannot be empty", "Path traversal not allowed"
-    pub details: Option<String>,
+    /// Number of executions to skip (most recent first)
+    pub offset: Option<usize>,
+
+    /// Optional RFC 3339 timestamp; only executions started or ended after it are returned
+    /// Example: "2025-09-26T10:20:45Z" (use `as_of` from the previous response)
+    pub since: Option<String>,
+
+    /// Optional response format: "json" (default) or "ndjson"
+    /// With "ndjson" each execution is streamed as one JSON object per line and
+    /// `as_of` is returned in the `X-As-Of` header
+    pub format: Option<String>,
+
+    /// Return `{ executions, count, total, limit, offset, queued, as_of }` instead of the
+    /// plain array; the array carries the same values in X-As-Of, X-Total-Count and
+    /// X-Queued-Executions
+    #[serde(default)]
+    pub envelope: bool,
 }
 
 // =============================================================================
@@ -114,10 +161,13 @@ pub struct ErrorResponse {
 // Implementation of route handlers for Python execution endpoints
 
 /// Execute a Python script
+///
+/// Returns 202 with the execution id on success. Errors use the shared
+/// `ApiError` envelope: 400 for invalid requests, 500 when the script cannot be started.
 async fn execute_python_script(
     State(state): State<AppState>,
     Json(request): Json<ExecutePythonRequest>,
-) -> Result<impl IntoResponse, impl IntoResponse> {
+) -> ApiResult<(StatusCode, Json<ExecutePythonResponse>)> {
     info!("Python execution request: {}", request.script_path);
 
     // ========================================================================
@@ -127,64 +177,67 @@ async fn execute_python_script(
     // Validate script path is not empty
     if request.script_path.is_empty() {
         error!("Empty script path provided");
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Invalid request".to_string(),
-                details: Some("Script path cannot be empty".to_string()),
-            }),
-        ));
+        return Err(ApiError::BadRequest("Script path cannot be empty".to_string()));
     }
 
     // Security: prevent path traversal attacks
     if request.script_path.contains("..") {
         error!("Path traversal attempt detected: {}", request.script_path);
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Invalid script path".to_string(),
-                details: Some("Path traversal not allowed".to_string()),
-            }),
-        ));
+        return Err(ApiError::BadRequest("Invalid script path: path traversal not allowed".to_string()));
     }
 
+    // Cap argument and environment variable count and size before launching anything
+    state.python_runner_service
+        .check_input_limits(&request.args, &request.env_vars)
+        .map_err(|e| {
+            error!("Execution request rejected: {}", e);
+            ApiError::BadRequest(e)
+        })?;
+
+    // Resolve the environment preset and merge inline variables over it
+    let env_vars = state.python_runner_service
+        .resolve_env_vars(request.env_preset.as_deref(), request.env_vars)
+        .map_err(|e| {
+            error!("Invalid environment preset: {}", e);
+            ApiError::BadRequest(format!("Invalid environment preset: {}", e))
+        })?;
+
+    // Resolve the container user, rejecting users outside the allowlist
+    let container_user = state.python_runner_service
+        .resolve_container_user(request.run_as.as_deref())
+        .map_err(|e| {
+            error!("Invalid container user: {}", e);
+            ApiError::BadRequest(format!("Invalid container user: {}", e))
+        })?;
+
     // ========================================================================
     // EXECUTION PROCESSING
     // ========================================================================
 
     // Execute the script through the Python runner service
-    match state.python_runner_service.execute_script(
+    let execution_id = state.python_runner_service.execute_script(
         &request.script_path,
         request.args,
-        request.env_vars,
+        env_vars,
         request.websocket_client_id,
-    ).await {
-        Ok(execution_id) => {
-            info!("Script execution started successfully: {}", execution_id);
-
-            // Return success response with execution details
-            Ok((
-                StatusCode::ACCEPTED,
-                Json(ExecutePythonResponse {
-                    execution_id,
-                    status: "pending".to_string(),
-                    message: "Script execution started".to_string(),
-                }),
-            ))
-        }
-        Err(e) => {
-            error!("Failed to execute script {}: {}", request.script_path, e);
-
-            // Return error response with details
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: "Failed to execute script".to_string(),
-                    details: Some(e.to_string()),
-                }),
-            ))
-        }
-    }
+        Some(container_user),
+        request.priority,
+    ).await.map_err(|e| {
+        error!("Failed to execute script {}: {}", request.script_path, e);
+        ApiError::ExecutionError(format!("Failed to execute script: {}", e))
+    })?;
+
+    info!("Script execution started successfully: {}", execution_id);
+
+    // Return success response with execution details
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ExecutePythonResponse {
+            execution_id,
+            status: "pending".to_string(),
+            message: "Script execution started".to_string(),
+        }),
+    ))
 }
 
 /// Get execution status
@@ -223,6 +276,8 @@ async fn get_execution_status(
 }
 
 /// Get full execution details
+///
+/// `execution.environment` records the container id, image digest and host the run used.
 async fn get_execution_details(
     State(state): State<AppState>,
     Path(execution_id): Path<String>,
@@ -259,36 +314,144 @@ async fn get_execution_details(
     }
 }
 
+/// Get the raw output bytes of an execution
+///
+/// Serves non-UTF-8 output (flagged by `output_binary`) unmodified as
+/// `application/octet-stream`.
+async fn get_execution_output(
+    State(state): State<AppState>,
+    Path(execution_id): Path<String>,
+) -> ApiResult<Response> {
+    let output = state.python_runner_service
+        .get_raw_output(&execution_id)
+        .await
+        .map_err(|_| ApiError::NotFound(format!("Execution '{}' not found", execution_id)))?
+        .ok_or_else(|| ApiError::NotFound(format!("Execution '{}' has no output", execution_id)))?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/octet-stream")],
+        output,
+    ).into_response())
+}
+
+/// Job events linked to an execution through its trace id, oldest first
+async fn get_execution_events(
+    State(state): State<AppState>,
+    Path(execution_id): Path<String>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let execution = state.python_runner_service
+        .get_execution(&execution_id)
+        .await
+        .map_err(|_| ApiError::NotFound(format!("Execution '{}' not found", execution_id)))?;
+    let events = state.python_runner_service
+        .get_execution_events(&execution_id)
+        .await
+        .map_err(|_| ApiError::NotFound(format!("Execution '{}' not found", execution_id)))?;
+
+    Ok(Json(serde_json::json!({
+        "execution_id": execution_id,
+        "trace_id": execution.trace_id,
+        "count": events.len(),
+        "events": events,
+    })))
+}
+
 /// List executions with optional filtering
 async fn list_executions(
     State(state): State<AppState>,
     Query(params): Query<ListExecutionsQuery>,
-) -> impl IntoResponse {
+) -> Response {
     debug!("Listing executions with filter: {:?}", params);
 
-    // Parse status filter from query parameter
-    let status_filter = params.status.and_then(|s| {
-        match s.to_lowercase().as_str() {
-            "pending" => Some(ExecutionStatus::Pending),
-            "running" => Some(ExecutionStatus::Running),
-            "completed" => Some(ExecutionStatus::Completed),
-            "failed" => Some(ExecutionStatus::Failed),
-            "cancelled" => Some(ExecutionStatus::Cancelled),
-            "timedout" => Some(ExecutionStatus::TimedOut),
-            _ => None,
+    let ndjson = match params.format.as_deref() {
+        None | Some("json") => false,
+        Some("ndjson") => true,
+        Some(other) => {
+            warn!("Invalid format parameter: {}", other);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "Invalid format parameter",
+                    "details": format!("Expected 'json' or 'ndjson', got '{}'", other),
+                })),
+            ).into_response();
+        }
+    };
+
+    // Capture the poll timestamp before reading so no update falls between polls
+    let as_of = chrono::Utc::now();
+
+    // Parse updated-since filter from query parameter
+    let since = match params.since.as_deref().map(chrono::DateTime::parse_from_rfc3339) {
+        Some(Ok(since)) => Some(std::time::SystemTime::from(since)),
+        Some(Err(e)) => {
+            warn!("Invalid since parameter: {}", e);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "Invalid since parameter",
+                    "details": format!("Expected an RFC 3339 timestamp: {}", e),
+                })),
+            ).into_response();
         }
-    });
+        None => None,
+    };
 
-    // Retrieve filtered executions from service
-    let executions = state.python_runner_service.list_executions(
-        status_filter,
+    // Parse status filter from query parameter
+    let status_filter = params.status.and_then(|s| s.parse::<ExecutionStatus>().ok());
+
+    // Retrieve filtered executions from service and cut out the requested page
+    let page = state.pagination.paginate(
+        state.python_runner_service.list_executions(status_filter, since, None).await,
         params.limit,
-    ).await;
+        params.offset,
+    );
+    let executions = page.items;
 
-    debug!("Returning {} executions", executions.len());
-    
-    // Return list of executions
-    (StatusCode::OK, Json(executions))
+    debug!("Returning {} of {} executions", executions.len(), page.total);
+
+    let queued = state.python_runner_service.queued_executions();
+    let headers = [
+        (header::HeaderName::from_static("x-as-of"), as_of.to_rfc3339()),
+        (header::HeaderName::from_static("x-total-count"), page.total.to_string()),
+        (header::HeaderName::from_static("x-queued-executions"), queued.to_string()),
+    ];
+
+    if ndjson {
+        // Serialize one execution per line as the body is polled
+        let lines = tokio_stream::iter(executions).map(|execution| {
+            let mut line = serde_json::to_vec(&execution).unwrap_or_default();
+            line.push(b'\n');
+            Ok::<_, Infallible>(Bytes::from(line))
+        });
+
+        return (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/x-ndjson")],
+            headers,
+            Body::from_stream(lines),
+        ).into_response();
+    }
+
+    if !params.envelope {
+        // Existing clients read a plain array; paging and poll metadata go in headers
+        return (StatusCode::OK, headers, Json(executions)).into_response();
+    }
+
+    // Return list of executions with the timestamp to use as the next `since`
+    (
+        StatusCode::OK,
+        headers,
+        Json(serde_json::json!({
+            "executions": executions,
+            "count": executions.len(),
+            "total": page.total,
+            "limit": page.limit,
+            "offset": page.offset,
+            "queued": queued,
+            "as_of": as_of.to_rfc3339(),
+        })),
+    ).into_response()
 }
 
 /// Cancel a running execution
@@ -314,12 +477,18 @@ async fn cancel_execution(
         }
         Err(e) => {
             error!("Failed to cancel execution {}: {}", execution_id, e);
+
+            let (status, reason) = match &e {
+                CancelError::NotFound => (StatusCode::NOT_FOUND, "not_found"),
+                CancelError::AlreadyTerminal(_) => (StatusCode::CONFLICT, "already_terminal"),
+            };
             
             // Return error response using the same Json type for consistency
             (
-                StatusCode::BAD_REQUEST,
+                status,
                 Json(serde_json::json!({
                     "error": "Failed to cancel execution",
+                    "reason": reason,
                     "execution_id": execution_id,
                     "details": e.to_string(),
                 })),
@@ -328,6 +497,15 @@ async fn cancel_execution(
     }
 }
 
+/// Cancel all running executions (admin-scoped)
+async fn cancel_all_executions(
+    _admin: AdminAccess,
+    State(state): State<AppState>,
+) -> Json<CancelAllResult> {
+    warn!("Cancel-all requested for Python executions");
+    Json(state.python_runner_service.cancel_all_executions().await)
+}
+
 // =============================================================================
 // SECTION 3: ROUTE CONFIGURATION
 // =============================================================================
@@ -344,11 +522,111 @@ async fn cancel_execution(
 /// - GET    /api/python/execution/:id - Get execution details
 /// - GET    /api/python/executions    - List executions
 /// - DELETE /api/python/execution/:id - Cancel execution
+/// - POST   /api/python/cancel-all    - Cancel all running executions (admin)
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/api/python/execute", post(execute_python_script))
         .route("/api/python/status/:id", get(get_execution_status))
         .route("/api/python/execution/:id", get(get_execution_details))
+        .route("/api/python/execution/:id/output", get(get_execution_output))
+        .route("/api/python/execution/:id/events", get(get_execution_events))
         .route("/api/python/executions", get(list_executions))
         .route("/api/python/execution/:id", delete(cancel_execution))
+        .route("/api/python/cancel-all", post(cancel_all_executions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    fn request(script_path: &str) -> ExecutePythonRequest {
+        ExecutePythonRequest {
+            script_path: script_path.to_string(),
+            args: Vec::new(),
+            env_vars: HashMap::new(),
+            env_preset: None,
+            run_as: None,
+            websocket_client_id: None,
+            priority: ExecutionPriority::Normal,
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_env_presets_are_rejected_with_400() {
+        let app = TestApp::new().await;
+        let request = ExecutePythonRequest { env_preset: Some("staging".to_string()), ..request("scripts/run.py") };
+
+        let result = execute_python_script(State(app.state.clone()), Json(request)).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(message)) if message.contains("'staging'")));
+        assert!(app.state.python_runner_service.list_executions(None, None, None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_script_paths_answer_400_in_the_error_envelope() {
+        let app = TestApp::new().await;
+        for script_path in ["", "../etc/passwd.py"] {
+            let response = execute_python_script(State(app.state.clone()), Json(request(script_path)))
+                .await
+                .into_response();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["status"], 400);
+            assert!(body["error"].as_str().is_some_and(|error| error.contains("path")), "{}", body);
+        }
+
+        let (status, Json(accepted)) =
+            execute_python_script(State(app.state.clone()), Json(request("scripts/run.py"))).await.unwrap();
+        assert_eq!((status, accepted.status.as_str()), (StatusCode::ACCEPTED, "pending"));
+    }
+
+    #[tokio::test]
+    async fn executions_stream_as_ndjson_and_unknown_formats_answer_400() {
+        let app = TestApp::new().await;
+        let runner = &app.state.python_runner_service;
+        runner.insert_finished_execution("scripts/a.py", ExecutionStatus::Completed, "").await;
+        runner.insert_finished_execution("scripts/b.py", ExecutionStatus::Failed, "").await;
+        let list = |format: &str| {
+            let params = ListExecutionsQuery {
+                status: None,
+                limit: None,
+                offset: None,
+                since: None,
+                format: Some(format.to_string()),
+                envelope: false,
+            };
+            list_executions(State(app.state.clone()), Query(params))
+        };
+
+        let response = list("ndjson").await;
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+        assert_eq!(response.headers()["x-total-count"], "2");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let lines: Vec<serde_json::Value> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let mut scripts: Vec<_> = lines.iter().map(|line| line["script_path"].as_str().unwrap()).collect();
+        scripts.sort();
+        assert_eq!(scripts, ["scripts/a.py", "scripts/b.py"]);
+
+        assert_eq!(list("csv").await.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn raw_output_of_unknown_executions_answers_404() {
+        let app = TestApp::new().await;
+        let result = get_execution_output(State(app.state.clone()), Path("missing".to_string())).await;
+        assert!(matches!(result, Err(ApiError::NotFound(message)) if message.contains("'missing'")));
+
+        let id = app.state.python_runner_service
+            .insert_finished_execution("scripts/run.py", ExecutionStatus::Completed, "done")
+            .await;
+        let response = get_execution_output(State(app.state.clone()), Path(id)).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/octet-stream");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"done");
+    }
 }
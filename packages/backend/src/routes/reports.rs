@@ -1,16 +1,34 @@
 //! Reports Management Routes
 //! 
-//! Handles report configuration, retrieval, and filtering
+//! Handles report configuration, retrieval, and filtering.
+//! Responses are JSON by default, or YAML when requested with `Accept: text/yaml`.
+//! Single reports can be edited in place with a JSON merge patch; the rest of reports.yaml,
+//! comments included, is left as written.
+//! Listing accepts `modified_since=<rfc3339>` and answers `304` when the reports file is unchanged.
+//! Reports run through the Python runner after their arguments are checked against the
+//! report's `arg_schema` (or, without one, against the types of its default `rpc_args`).
+//! Device credentials are resolved by the credentials service; the request's are overrides.
+//! A report may name an `output_schema`; the rows of a finished run are validated against it
+//! when fetched from `GET /api/reports/:report_id/runs/:execution_id`, and rows that do not
+//! match (device/report drift) are answered with `422` and one issue per schema error.
 
 use axum::{
-    extract::{Path, State},
-    response::Json,
-    routing::get,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
     Router,
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use crate::{AppState, models};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, warn};
+use crate::{AppState, models::{self, Negotiated, ResponseFormat, ValidationIssue}, services::{ExecutionPriority, ExecutionStatus}};
+use crate::models::websocket::{DataUpdatePayload, SubscriptionTopic, WsMessage};
+
+/// Schema (and default file) holding all report definitions
+const REPORTS_SCHEMA: &str = "reports";
 
 /// Individual report configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,8 +46,97 @@ pub struct Report {
     /// Optional RPC arguments
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rpc_args: Option<HashMap<String, serde_json::Value>>,
+    /// Optional argument schema; run-time arguments are validated against it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arg_schema: Option<HashMap<String, ReportArgSpec>>,
+    /// Optional schema name; each device's mapped rows must validate against it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<String>,
+}
+
+/// Type of a report argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportArgType {
+    String,
+    Boolean,
+    Number,
+    Integer,
+}
+
+impl ReportArgType {
+    /// Type hint inferred from a default value
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(_) => Some(Self::String),
+            Value::Bool(_) => Some(Self::Boolean),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Some(Self::Integer),
+            Value::Number(_) => Some(Self::Number),
+            _ => None,
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Boolean => value.is_boolean(),
+            Self::Number => value.is_number(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+        }
+    }
+}
+
+/// Declared type and constraints of one report argument
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportArgSpec {
+    #[serde(rename = "type")]
+    pub arg_type: ReportArgType,
+    /// Must be provided at run time or through `rpc_args`
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values, if restricted
+    #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl ReportArgSpec {
+    /// Checks a single value, returning the field-level error
+    fn check(&self, value: &Value) -> Result<(), String> {
+        if !self.arg_type.matches(value) {
+            return Err(format!("expected {:?}, got {}", self.arg_type, value).to_lowercase());
+        }
+        match &self.allowed {
+            Some(allowed) if !allowed.contains(value) => Err(format!(
+                "must be one of {}",
+                serde_json::to_string(allowed).unwrap_or_default()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Request body for running a report against a device
+#[derive(Debug, Deserialize)]
+pub struct RunReportRequest {
+    pub hostname: String,
+    /// Overrides the inventory and environment login; see CredentialsService
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Run-time RPC arguments, merged over the report's `rpc_args`
+    #[serde(default)]
+    pub args: Map<String, Value>,
 }
 
+/// Report runner script, relative to the Python runner's pipeline directory
+pub(crate) const REPORT_RUNNER_SCRIPT: &str = "scripts/jsnapy_runner/run.py";
+
+/// Environment variable carrying the validated RPC arguments to the runner script
+const REPORT_RPC_ARGS_ENV: &str = "REPORT_RPC_ARGS";
+
 /// Response structure for listing all reports
 #[derive(Serialize)]
 pub struct ReportsListResponse {
@@ -52,11 +159,33 @@ pub struct FilteredReportsResponse {
     pub reports: HashMap<String, Report>,
 }
 
+/// Query parameters for listing reports
+#[derive(Debug, Deserialize)]
+pub struct ReportsQuery {
+    /// RFC 3339 timestamp; when the reports file is unchanged since then, `304` is returned
+    pub modified_since: Option<String>,
+}
+
 /// Get all available reports
 /// Returns a comprehensive list of all reports with metadata
+///
+/// All reports live in one file, so with `modified_since` either every report is
+/// returned (the file changed after the timestamp) or none, as `304 Not Modified`.
 pub async fn get_all_reports(
     State(state): State<AppState>,
-) -> models::ApiResult<Json<ReportsListResponse>> {
+    Query(query): Query<ReportsQuery>,
+    format: ResponseFormat,
+) -> models::ApiResult<Response> {
+    if let Some(since) = query.modified_since.as_deref() {
+        let since = chrono::DateTime::parse_from_rfc3339(since).map_err(|e| {
+            models::ApiError::BadRequest(format!("Invalid modified_since (expected RFC 3339): {}", e))
+        })?;
+        let modified = state.yaml_service.data_modified(REPORTS_SCHEMA, None).await?;
+        if modified <= std::time::SystemTime::from(since) {
+            return Ok(StatusCode::NOT_MODIFIED.into_response());
+        }
+    }
+
     // Load reports from YAML file
     let reports_data = state.yaml_service.get_yaml_data("reports", None).await?;
     
@@ -79,7 +208,7 @@ pub async fn get_all_reports(
         reports,
     };
     
-    Ok(Json(response))
+    Ok(Negotiated::new(format, response).into_response())
 }
 
 /// Get a specific report by ID
@@ -87,7 +216,8 @@ pub async fn get_all_reports(
 pub async fn get_report_by_id(
     Path(report_id): Path<String>,
     State(state): State<AppState>,
-) -> models::ApiResult<Json<Report>> {
+    format: ResponseFormat,
+) -> models::ApiResult<Negotiated<Report>> {
     // Load reports from YAML file
     let reports_data = state.yaml_service.get_yaml_data("reports", None).await?;
     let reports: HashMap<String, Report> = serde_json::from_value(reports_data)
@@ -95,7 +225,7 @@ pub async fn get_report_by_id(
     
     // Find the specific report
     match reports.get(&report_id) {
-        Some(report) => Ok(Json(report.clone())),
+        Some(report) => Ok(Negotiated::new(format, report.clone())),
         None => Err(models::ApiError::NotFound(format!("Report '{}' not found", report_id))),
     }
 }
@@ -105,7 +235,8 @@ pub async fn get_report_by_id(
 pub async fn filter_reports_by_category(
     Path(category): Path<String>,
     State(state): State<AppState>,
-) -> models::ApiResult<Json<FilteredReportsResponse>> {
+    format: ResponseFormat,
+) -> models::ApiResult<Negotiated<FilteredReportsResponse>> {
     // Load reports from YAML file
     let reports_data = state.yaml_service.get_yaml_data("reports", None).await?;
     let all_reports: HashMap<String, Report> = serde_json::from_value(reports_data)
@@ -127,13 +258,566 @@ pub async fn filter_reports_by_category(
         reports: filtered_reports,
     };
     
-    Ok(Json(response))
+    Ok(Negotiated::new(format, response))
+}
+
+/// Update a single report by ID
+/// Merges a partial report into the existing definition (JSON merge patch:
+/// `null` removes a field), validates the result and writes reports.yaml atomically.
+/// Only the patched fields' lines change; comments and the order of keys are kept.
+pub async fn patch_report(
+    Path(report_id): Path<String>,
+    State(state): State<AppState>,
+    Json(patch): Json<Value>,
+) -> models::ApiResult<Json<Report>> {
+    let Some(patch) = patch.as_object() else {
+        return Err(models::ApiError::BadRequest("Report patch must be a JSON object".to_string()));
+    };
+
+    let mut reports_data = state.yaml_service.get_yaml_data(REPORTS_SCHEMA, None).await?;
+    let existing = reports_data
+        .get_mut(&report_id)
+        .ok_or_else(|| models::ApiError::NotFound(format!("Report '{}' not found", report_id)))?;
+
+    merge_patch(existing, &Value::Object(patch.clone()));
+
+    // The merged entry must still be a complete report
+    let report: Report = serde_json::from_value(existing.clone())
+        .map_err(|e| models::ApiError::ValidationError(format!("Invalid report '{}': {}", report_id, e)))?;
+
+    // Default arguments must satisfy the report's own argument schema
+    if let (Some(schema), Some(rpc_args)) = (&report.arg_schema, &report.rpc_args) {
+        for (name, value) in rpc_args {
+            let check = match schema.get(name) {
+                Some(spec) => spec.check(value),
+                None => Err("unknown argument".to_string()),
+            };
+            if let Err(e) = check {
+                return Err(models::ApiError::ValidationError(format!(
+                    "Invalid rpc_args.{} for report '{}': {}",
+                    name, report_id, e
+                )));
+            }
+        }
+    }
+
+    // Validates the whole file against the reports schema, then edits only this report's lines
+    state.yaml_service
+        .write_yaml_patch(REPORTS_SCHEMA, None, &report_id, patch, reports_data)
+        .await?;
+
+    info!("Updated report '{}'", report_id);
+
+    let msg = WsMessage::DataUpdate {
+        payload: DataUpdatePayload {
+            source: REPORTS_SCHEMA.to_string(),
+            data: serde_json::json!({ "report_id": report_id, "report": report }),
+            timestamp: Utc::now(),
+        },
+    };
+    let topic = SubscriptionTopic::DataUpdates(REPORTS_SCHEMA.to_string());
+    if let Err(e) = state.websocket_service.broadcast_to_topic(&topic, msg).await {
+        warn!("Failed to broadcast report update: {}", e);
+    }
+
+    Ok(Json(report))
+}
+
+/// Validates a report run and dispatches it to the Python runner
+///
+/// Arguments are checked before anything touches a device. Invalid arguments
+/// return `422` with a `fields` object mapping each argument to its error.
+/// `202` only means the run was queued; its rows come from `get_report_run`, which
+/// answers `502` when the runner's output carries no `results_by_host` line.
+pub async fn run_report(
+    Path(report_id): Path<String>,
+    State(state): State<AppState>,
+    Json(request): Json<RunReportRequest>,
+) -> models::ApiResult<Response> {
+    let report = load_report(&state, &report_id).await?;
+
+    if request.hostname.trim().is_empty() {
+        return Err(models::ApiError::BadRequest("hostname is required".to_string()));
+    }
+    // A report whose output cannot be checked is a configuration error; fail before the run
+    if let Some(schema) = &report.output_schema {
+        state.yaml_service.require_schema(schema).await?;
+    }
+    let credentials = state
+        .credentials_service
+        .resolve(&request.hostname, request.username.as_deref(), request.password.as_deref())
+        .await?;
+
+    let rpc_args = match resolve_report_args(&report, &request.args) {
+        Ok(rpc_args) => rpc_args,
+        Err(fields) => {
+            warn!("Rejected arguments for report '{}': {:?}", report_id, fields);
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({
+                    "error": format!("Invalid arguments for report '{}'", report_id),
+                    "status": StatusCode::UNPROCESSABLE_ENTITY.as_u16(),
+                    "fields": fields,
+                })),
+            ).into_response());
+        }
+    };
+
+    let args = vec![
+        "--hostname".to_string(), request.hostname.clone(),
+        "--username".to_string(), credentials.username.clone(),
+        "--password".to_string(), credentials.password().to_string(),
+        "--tests".to_string(), report_id.clone(),
+    ];
+    let env_vars = HashMap::from([(
+        REPORT_RPC_ARGS_ENV.to_string(),
+        serde_json::to_string(&rpc_args).map_err(|e| models::ApiError::SerializationError(e.to_string()))?,
+    )]);
+
+    let execution_id = state
+        .python_runner_service
+        .execute_script(REPORT_RUNNER_SCRIPT, args, env_vars, None, None, ExecutionPriority::Normal)
+        .await
+        .map_err(|e| models::ApiError::ExecutionError(format!("Failed to start report '{}': {}", report_id, e)))?;
+
+    info!("Report '{}' for {} running as execution {}", report_id, request.hostname, execution_id);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "execution_id": execution_id,
+            "report_id": report_id,
+            "hostname": request.hostname,
+            "rpc_args": rpc_args,
+            "output_schema": report.output_schema,
+        })),
+    ).into_response())
+}
+
+/// Returns a report run's mapped rows per device, validated against the report's output schema
+///
+/// `202` with the execution status while the run is pending or running; `409` when it
+/// failed, timed out or was cancelled. Rows that do not match the output schema return
+/// `422` with the rows and one `errors` entry per schema error, naming the device.
+pub async fn get_report_run(
+    Path((report_id, execution_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> models::ApiResult<Response> {
+    let report = load_report(&state, &report_id).await?;
+    let execution = state
+        .python_runner_service
+        .get_execution(&execution_id)
+        .await
+        .ok()
+        .filter(|execution| execution.script_path == REPORT_RUNNER_SCRIPT)
+        .ok_or_else(|| models::ApiError::NotFound(format!("Report run '{}' not found", execution_id)))?;
+
+    match execution.status {
+        ExecutionStatus::Completed => {}
+        ExecutionStatus::Pending | ExecutionStatus::Running => {
+            return Ok((
+                StatusCode::ACCEPTED,
+                Json(serde_json::json!({
+                    "execution_id": execution_id,
+                    "report_id": report_id,
+                    "status": execution.status.as_str(),
+                })),
+            ).into_response());
+        }
+        status => {
+            return Err(models::ApiError::Conflict(format!(
+                "Report run '{}' ended as {} and has no result",
+                execution_id,
+                status.as_str()
+            )));
+        }
+    }
+
+    let results = execution
+        .output
+        .as_deref()
+        .and_then(report_results)
+        .ok_or_else(|| {
+            models::ApiError::UpstreamError(format!(
+                "Report run '{}' produced no result: its output has no results_by_host line",
+                execution_id
+            ))
+        })?;
+
+    let mut errors = Vec::new();
+    if let Some(schema) = &report.output_schema {
+        for result in &results {
+            match state.yaml_service.validate_value(schema, &result["rows"]).await {
+                Ok(()) => {}
+                Err(models::ApiError::SchemaValidation(issues)) => {
+                    let hostname = result["hostname"].as_str().map(str::to_string);
+                    errors.extend(issues.into_iter().map(|issue| ValidationIssue {
+                        device_hostname: hostname.clone(),
+                        ..issue
+                    }));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    let mut body = serde_json::json!({
+        "execution_id": execution_id,
+        "report_id": report_id,
+        "output_schema": report.output_schema,
+        "valid": errors.is_empty(),
+        "results": results,
+    });
+    if errors.is_empty() {
+        return Ok(Json(body).into_response());
+    }
+
+    warn!("Report '{}' run {} does not match its output schema: {} error(s)", report_id, execution_id, errors.len());
+    body["error"] = Value::from(format!("Result of report '{}' does not match its output schema", report_id));
+    body["status"] = Value::from(StatusCode::UNPROCESSABLE_ENTITY.as_u16());
+    body["errors"] = serde_json::to_value(&errors).unwrap_or_default();
+    Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response())
+}
+
+/// Looks up one report definition by id
+async fn load_report(state: &AppState, report_id: &str) -> models::ApiResult<Report> {
+    let reports_data = state.yaml_service.get_yaml_data(REPORTS_SCHEMA, None).await?;
+    let mut reports: HashMap<String, Report> = serde_json::from_value(reports_data)
+        .map_err(|e| models::ApiError::ValidationError(format!("Failed to parse reports: {}", e)))?;
+    reports
+        .remove(report_id)
+        .ok_or_else(|| models::ApiError::NotFound(format!("Report '{}' not found", report_id)))
+}
+
+/// Per-device mapped rows from the runner's final `{"results_by_host": [...]}` output line
+///
+/// Progress lines printed before it are skipped. Each entry carries `hostname`, `status`,
+/// `rows` (the mapped rows of the report's test) and `error`.
+fn report_results(output: &str) -> Option<Vec<Value>> {
+    let final_output = output
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+        .find(|value| value.get("results_by_host").is_some())?;
+
+    let results = final_output["results_by_host"]
+        .as_array()?
+        .iter()
+        .map(|host| {
+            // The run targets a single report, so a host has at most one test result
+            let test = host.get("test_results").and_then(|tests| tests.get(0));
+            serde_json::json!({
+                "hostname": host.get("hostname"),
+                "status": host.get("status"),
+                "rows": test.and_then(|test| test.get("data")).cloned().unwrap_or_else(|| Value::Array(Vec::new())),
+                "error": test.and_then(|test| test.get("error")).or_else(|| host.get("message")),
+            })
+        })
+        .collect();
+    Some(results)
+}
+
+/// Merges run-time arguments over the report's defaults and validates the result
+///
+/// With an `arg_schema`, every argument must be declared, match its type and
+/// allowed values, and required arguments must be present. Without one, only
+/// arguments that have a default may be given, with the default's type.
+/// Returns field-level errors keyed by argument name.
+fn resolve_report_args(
+    report: &Report,
+    provided: &Map<String, Value>,
+) -> Result<Map<String, Value>, BTreeMap<String, String>> {
+    let mut merged: Map<String, Value> = report
+        .rpc_args
+        .iter()
+        .flatten()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    let mut errors = BTreeMap::new();
+
+    match &report.arg_schema {
+        Some(schema) => {
+            for name in provided.keys().filter(|name| !schema.contains_key(*name)) {
+                errors.insert(name.clone(), "unknown argument".to_string());
+            }
+            merged.extend(provided.clone());
+            for (name, spec) in schema {
+                match merged.get(name) {
+                    Some(value) => {
+                        if let Err(e) = spec.check(value) {
+                            errors.insert(name.clone(), e);
+                        }
+                    }
+                    None if spec.required => {
+                        errors.insert(name.clone(), "is required".to_string());
+                    }
+                    None => {}
+                }
+            }
+        }
+        None => {
+            for (name, value) in provided {
+                let expected = merged.get(name).and_then(ReportArgType::of);
+                match expected {
+                    None => {
+                        errors.insert(name.clone(), "unknown argument".to_string());
+                    }
+                    Some(arg_type) if !arg_type.matches(value) => {
+                        errors.insert(name.clone(), format!("expected {:?}, got {}", arg_type, value).to_lowercase());
+                    }
+                    Some(_) => {}
+                }
+            }
+            merged.extend(provided.clone());
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(merged)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Applies a JSON merge patch (RFC 7386) to `target` in place
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
 }
 
 /// Creates reports-related routes
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/api/reports", get(get_all_reports))
-        .route("/api/reports/:report_id", get(get_report_by_id))
+        .route("/api/reports/:report_id", get(get_report_by_id).patch(patch_report))
+        .route("/api/reports/:report_id/run", post(run_report))
+        .route("/api/reports/:report_id/runs/:execution_id", get(get_report_run))
         .route("/api/reports/filter/:category", get(filter_reports_by_category))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    const REPORTS: &str = r#"
+bgp:
+  title: BGP Neighbor
+  category: Routing
+  rpc: get-bgp-summary-information
+  xpath: .//bgp-peer
+  output_schema: peers
+  fields: { Address: peer-address }
+"#;
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn run_results_come_from_the_final_output_line() {
+        let output = [
+            r#"{"event_type": "OPERATION_START", "data": {"total_steps": 2}}"#,
+            r#"{"results_by_host": [{"hostname": "r1", "status": "success", "test_results": [{"title": "BGP Neighbor for r1", "headers": ["Address"], "data": [{"Address": "10.0.0.2"}], "error": null}]}, {"hostname": "r2", "status": "error", "message": "Authentication Failed for r2."}]}"#,
+        ]
+        .join("\n");
+
+        let results = report_results(&output).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["rows"], serde_json::json!([{ "Address": "10.0.0.2" }]));
+        assert_eq!(results[1]["rows"], serde_json::json!([]));
+        assert_eq!(results[1]["error"], "Authentication Failed for r2.");
+        assert!(report_results(r#"{"type": "error", "message": "boom"}"#).is_none());
+    }
+
+    #[tokio::test]
+    async fn listings_answer_304_when_unchanged_since_the_timestamp() {
+        let app = TestApp::new().await;
+        app.write_data("reports.yaml", REPORTS).await;
+        let list = |since: &str| {
+            let query = ReportsQuery { modified_since: Some(since.to_string()) };
+            get_all_reports(State(app.state.clone()), Query(query), ResponseFormat::Json)
+        };
+
+        let response = list("2000-01-01T00:00:00Z").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await["total"], 1);
+
+        let future = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        assert_eq!(list(&future).await.unwrap().status(), StatusCode::NOT_MODIFIED);
+
+        assert!(matches!(list("yesterday").await, Err(models::ApiError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn report_runs_are_validated_from_the_execution_output() {
+        let app = TestApp::new().await;
+        app.write_data("reports.yaml", REPORTS).await;
+        app.write_schema("peers", &serde_json::json!({
+            "type": "array",
+            "items": { "type": "object", "required": ["Address"], "properties": { "Address": { "type": "string" } } }
+        }))
+        .await;
+        let runner = &app.state.python_runner_service;
+        let fetch = |execution_id: String| get_report_run(Path(("bgp".to_string(), execution_id)), State(app.state.clone()));
+
+        let output = r#"{"results_by_host": [{"hostname": "r1", "status": "success", "test_results": [{"data": [{"Address": "10.0.0.2"}]}]}]}"#;
+        let execution_id = runner.insert_finished_execution(REPORT_RUNNER_SCRIPT, ExecutionStatus::Completed, output).await;
+        let response = fetch(execution_id).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await["results"][0]["rows"][0]["Address"], "10.0.0.2");
+
+        let output = r#"{"results_by_host": [{"hostname": "r1", "status": "success", "test_results": [{"data": [{"Address": 5}]}]}]}"#;
+        let execution_id = runner.insert_finished_execution(REPORT_RUNNER_SCRIPT, ExecutionStatus::Completed, output).await;
+        let response = fetch(execution_id).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body_json(response).await["errors"][0]["device_hostname"], "r1");
+
+        let simulated = format!("Simulated output for {}", REPORT_RUNNER_SCRIPT);
+        let execution_id = runner.insert_finished_execution(REPORT_RUNNER_SCRIPT, ExecutionStatus::Completed, &simulated).await;
+        assert!(matches!(fetch(execution_id).await, Err(models::ApiError::UpstreamError(_))));
+
+        let execution_id = runner.insert_finished_execution(REPORT_RUNNER_SCRIPT, ExecutionStatus::Failed, "").await;
+        assert!(matches!(fetch(execution_id).await, Err(models::ApiError::Conflict(_))));
+    }
+
+    #[test]
+    fn report_args_are_merged_over_defaults_and_checked() {
+        let mut reports: HashMap<String, Report> = serde_yaml::from_str(REPORTS).unwrap();
+        let mut report = reports.remove("bgp").unwrap();
+        report.rpc_args = Some(HashMap::from([("terse".to_string(), Value::Bool(true))]));
+        let args = |value: Value| value.as_object().cloned().unwrap();
+
+        // Without an arg_schema, only defaulted arguments of the same type are accepted
+        let merged = resolve_report_args(&report, &args(serde_json::json!({ "terse": false }))).unwrap();
+        assert_eq!(merged["terse"], false);
+        let errors = resolve_report_args(&report, &args(serde_json::json!({ "terse": "no", "extra": 1 }))).unwrap_err();
+        assert_eq!(errors["terse"], "expected boolean, got \"no\"");
+        assert_eq!(errors["extra"], "unknown argument");
+
+        report.arg_schema = Some(serde_json::from_value(serde_json::json!({
+            "terse": { "type": "boolean" },
+            "interface": { "type": "string", "required": true, "enum": ["ge-0/0/0", "ge-0/0/1"] }
+        })).unwrap());
+        let merged = resolve_report_args(&report, &args(serde_json::json!({ "interface": "ge-0/0/1" }))).unwrap();
+        assert_eq!((merged["terse"].clone(), merged["interface"].clone()), (Value::Bool(true), Value::from("ge-0/0/1")));
+        let errors = resolve_report_args(&report, &Map::new()).unwrap_err();
+        assert_eq!(errors["interface"], "is required");
+        let errors = resolve_report_args(&report, &args(serde_json::json!({ "interface": "xe-1/0/0" }))).unwrap_err();
+        assert!(errors["interface"].starts_with("must be one of"));
+    }
+
+    #[tokio::test]
+    async fn report_runs_are_dispatched_only_with_valid_arguments() {
+        let app = TestApp::new().await;
+        app.write_data("reports.yaml", REPORTS).await;
+        app.write_schema("peers", &serde_json::json!({ "type": "array" })).await;
+        let request = |args: Value| RunReportRequest {
+            hostname: "r1".to_string(),
+            username: Some("netops".to_string()),
+            password: Some("secret".to_string()),
+            args: args.as_object().cloned().unwrap(),
+        };
+
+        let response = run_report(Path("bgp".to_string()), State(app.state.clone()), Json(request(serde_json::json!({ "vrf": "x" }))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body_json(response).await["fields"]["vrf"], "unknown argument");
+
+        let response = run_report(Path("bgp".to_string()), State(app.state.clone()), Json(request(serde_json::json!({}))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body = body_json(response).await;
+        let execution = app.state.python_runner_service.get_execution(body["execution_id"].as_str().unwrap()).await.unwrap();
+        assert_eq!(execution.script_path, REPORT_RUNNER_SCRIPT);
+        assert_eq!(body["output_schema"], "peers");
+
+        let missing = run_report(Path("nope".to_string()), State(app.state.clone()), Json(request(serde_json::json!({})))).await;
+        assert!(matches!(missing, Err(models::ApiError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn report_patches_keep_comments_and_key_order() {
+        let app = TestApp::new().await;
+        let source = r#"# Runnable reports
+
+bgp:
+  title: "BGP Neighbor"
+  category: "Routing"
+  # Arguments passed to the RPC
+  rpc: get-bgp-summary-information
+  rpc_args:
+    # Terse output is enough for the table
+    terse: true
+  xpath: ".//bgp-peer"
+  fields:
+    Address: "peer-address" # shown first
+    "Remote AS": "peer-as"
+
+# Interfaces keep their own comment
+interfaces:
+  title: "Interface Status"
+  category: "Interfaces"
+  rpc: get-interface-information
+  xpath: ".//physical-interface"
+  fields:
+    Name: "name"
+"#;
+        app.write_data("reports.yaml", source).await;
+
+        let patch = serde_json::json!({
+            "title": "BGP Peers",
+            "rpc_args": { "detail": true },
+            "fields": { "Remote AS": null, "State": "peer-state" },
+            "description": "Peers per device",
+        });
+        let Json(report) = patch_report(Path("bgp".to_string()), State(app.state.clone()), Json(patch)).await.unwrap();
+        assert_eq!(report.title, "BGP Peers");
+
+        let written = std::fs::read_to_string(app.root.join("data/reports.yaml")).unwrap();
+        assert_eq!(written, r#"# Runnable reports
+
+bgp:
+  title: BGP Peers
+  category: "Routing"
+  # Arguments passed to the RPC
+  rpc: get-bgp-summary-information
+  rpc_args:
+    # Terse output is enough for the table
+    terse: true
+    detail: true
+  xpath: ".//bgp-peer"
+  fields:
+    Address: "peer-address" # shown first
+    State: peer-state
+  description: Peers per device
+
+# Interfaces keep their own comment
+interfaces:
+  title: "Interface Status"
+  category: "Interfaces"
+  rpc: get-interface-information
+  xpath: ".//physical-interface"
+  fields:
+    Name: "name"
+"#);
+    }
+}
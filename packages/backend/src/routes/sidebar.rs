@@ -1,15 +1,15 @@
-// Version: 1.1.0
+// Version: 1.2.0
 //
 // Description:
 // Defines routes for sidebar configuration API.
 //
 // Key Features:
 // - Parameterized endpoint to fetch specific sidebar configurations
-// - Optional endpoint to list all available sidebars
+// - Endpoint listing every sidebar with its name, item count and modification time
 //
 // Usage Guide:
 // - GET /api/sidebar/{sidebar_id} - Get specific sidebar config
-// - GET /api/sidebars - List all available sidebars (optional)
+// - GET /api/sidebars - List all sidebars as summaries
 
 use axum::{routing::get, Router};
 use crate::{api::sidebar, AppState};
@@ -17,5 +17,5 @@ use crate::{api::sidebar, AppState};
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/api/sidebar/:sidebar_id", get(sidebar::get_sidebar))
-        .route("/api/sidebars", get(sidebar::get_all_sidebars)) // Optional
+        .route("/api/sidebars", get(sidebar::get_all_sidebars))
 }
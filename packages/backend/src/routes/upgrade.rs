@@ -0,0 +1,30 @@
+// =========================================================================================
+// File Path: src/routes/upgrade.rs
+// Version: 1.0.0
+//
+// Description:
+// Defines routes for the firmware/OS upgrade API.
+//
+// Key Features:
+// - Endpoint to start an upgrade, followed through `upgrade` job events
+//
+// Usage Guide:
+// - POST /api/upgrade/run → starts an upgrade and returns its job id
+//
+// Change Log:
+// - 1.0.0: Initial implementation
+// =========================================================================================
+
+use axum::{routing::post, Router};
+use crate::{api::upgrade, AppState};
+
+// =============================================================================
+// Route Configuration
+// =============================================================================
+// Define all upgrade-related routes and their handlers
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        // Start upgrade operation
+        .route("/api/upgrade/run", post(upgrade::run_upgrade))
+}
@@ -4,11 +4,18 @@
 
 use axum::{
     extract::{Path, Query, State},
-    response::Json,
-    routing::get,
-    Router,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Extension, Router,
 };
-use crate::{AppState, models};
+use crate::{
+    AppState, models,
+    api::inventory::{audit_inventory_write, request_id, INVENTORY_SCHEMA, REQUEST_ID_HEADER},
+    middleware::{admin::AdminAccess, auth::Principal},
+    services::yaml_service::{FailedSchema, ValidationReport},
+};
+use serde::Serialize;
 
 /// Validate YAML data against a specific schema
 /// 
@@ -25,20 +32,201 @@ pub async fn validate_yaml_data(
     Ok(Json(validation_result))
 }
 
+/// Validate and write YAML data for a specific schema
+/// 
+/// # Parameters
+/// - `schema_name`: Name of the schema to validate against
+/// - `file_path`: Optional path to YAML file (uses default if not provided)
+/// - `preview`: When `true`, validate and return the diff without writing
+/// - Body: JSON document to be written as YAML
+///
+/// The response includes a structural diff against the existing file, and the request id
+/// (the caller's X-Request-Id or a generated one) in the X-Request-Id header.
+/// Inventory writes require admin access and are recorded in the inventory audit log.
+pub async fn write_yaml_data(
+    Path(schema_name): Path<String>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    principal: Option<Extension<Principal>>,
+    admin: Result<AdminAccess, models::ApiError>,
+    Json(data): Json<serde_json::Value>,
+) -> models::ApiResult<Response> {
+    // The inventory names device password variables, so only admins may change it
+    if schema_name == INVENTORY_SCHEMA {
+        admin?;
+    }
+    let file_path = params.get("file").cloned();
+    let preview = params.get("preview").is_some_and(|value| value == "true");
+    let request_id = request_id(&headers);
+
+    let outcome = if preview {
+        state.yaml_service.preview_yaml_data(&schema_name, file_path.as_deref(), data).await?
+    } else {
+        state.yaml_service.write_yaml_data(&schema_name, file_path.as_deref(), data).await?
+    };
+    if schema_name == INVENTORY_SCHEMA {
+        let file = file_path.unwrap_or_else(|| format!("{}.yaml", schema_name));
+        audit_inventory_write(&state, &request_id, principal.as_ref().map(|Extension(principal)| principal), &file, &outcome).await;
+    }
+    Ok(([(REQUEST_ID_HEADER, request_id)], Json(outcome)).into_response())
+}
+
+/// Response for GET /api/schemas
+#[derive(Debug, Serialize)]
+pub struct SchemaListing {
+    /// Names of schemas that compiled and can be used
+    pub schemas: Vec<String>,
+    /// Schemas that failed to compile, with the compile error
+    pub failed: Vec<FailedSchema>,
+}
+
 /// List all available schemas
-/// Returns a JSON array of schema names
+/// Returns the usable schema names and the schemas that failed to compile
 pub async fn list_schemas(
     State(state): State<AppState>,
-) -> models::ApiResult<Json<Vec<String>>> {
-    let schemas = state.yaml_service.list_available_schemas().await?;
-    Ok(Json(schemas))
+) -> models::ApiResult<Json<SchemaListing>> {
+    let mut schemas = state.yaml_service.list_available_schemas().await?;
+    schemas.sort();
+    let failed = state.yaml_service.list_failed_schemas().await;
+    Ok(Json(SchemaListing { schemas, failed }))
+}
+
+/// Get a schema document exactly as loaded
+///
+/// Lets clients validate forms with the same schema the server uses. Responds
+/// with `application/schema+json` and an ETag; a matching `If-None-Match`
+/// gets `304 Not Modified`.
+pub async fn get_raw_schema(
+    Path(schema_name): Path<String>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> models::ApiResult<Response> {
+    let raw = state.yaml_service.raw_schema(&schema_name).await?;
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == raw.etag)
+        });
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, raw.etag)]).into_response());
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/schema+json".to_string()),
+            (header::ETAG, raw.etag),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        raw.source.to_string(),
+    )
+        .into_response())
+}
+
+/// Validate every data file against its schema
+///
+/// Returns a per-file report and an overall `passed` flag, so CI can gate
+/// config deploys on a single call.
+pub async fn validate_all(
+    State(state): State<AppState>,
+) -> models::ApiResult<Json<ValidationReport>> {
+    Ok(Json(state.yaml_service.validate_all().await?))
 }
 
 /// Creates YAML-related routes
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/api/yaml/:schema_name", get(crate::api::handlers::get_yaml_by_schema))
+        .route("/api/yaml/:schema_name", get(crate::api::handlers::get_yaml_by_schema).put(write_yaml_data))
         .route("/api/yaml/:schema_name/validate", get(validate_yaml_data))
         .route("/api/schemas", get(list_schemas))
+        .route("/api/schemas/:schema_name/raw", get(get_raw_schema))
+        .route("/api/validate-all", post(validate_all))
         .route("/api/reload", get(crate::api::handlers::reload_schemas))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    #[tokio::test]
+    async fn inventory_writes_are_audited_unless_nothing_changed() {
+        let app = TestApp::new().await;
+        let write = |data: serde_json::Value, admin: Result<AdminAccess, models::ApiError>| {
+            let mut headers = HeaderMap::new();
+            headers.insert(REQUEST_ID_HEADER, "req-42".parse().unwrap());
+            write_yaml_data(
+                Path(INVENTORY_SCHEMA.to_string()),
+                Query(Default::default()),
+                State(app.state.clone()),
+                headers,
+                None,
+                admin,
+                Json(data),
+            )
+        };
+        let inventory = serde_json::json!({ "locations": { "LAB": { "routers": [{ "host_name": "r1" }] } } });
+
+        let denied = write(inventory.clone(), Err(models::ApiError::Forbidden("Admin token required".to_string()))).await;
+        assert!(matches!(denied, Err(models::ApiError::Forbidden(_))));
+        assert!(app.state.inventory_audit.recent(10).await.unwrap().is_empty());
+
+        let response = write(inventory.clone(), Ok(AdminAccess)).await.unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-42");
+        let entries = app.state.inventory_audit.recent(10).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].request_id.as_str(), entries[0].file.as_str()), ("req-42", "inventory.yaml"));
+
+        // Writing the same document again changes nothing and is not recorded
+        let response = write(inventory, Ok(AdminAccess)).await.unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-42");
+        assert_eq!(app.state.inventory_audit.recent(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn raw_schemas_carry_an_etag_and_answer_304_or_404() {
+        let app = TestApp::new().await;
+        app.write_schema("sites", &serde_json::json!({ "type": "object" })).await;
+        let fetch = |name: &str, if_none_match: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(tag) = if_none_match {
+                headers.insert(header::IF_NONE_MATCH, tag.parse().unwrap());
+            }
+            get_raw_schema(Path(name.to_string()), headers, State(app.state.clone()))
+        };
+
+        let response = fetch("sites", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/schema+json");
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap()["type"], "object");
+
+        let weak = format!("\"other\", W/{}", etag);
+        assert_eq!(fetch("sites", Some(&weak)).await.unwrap().status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(fetch("sites", Some("\"other\"")).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(fetch("missing", None).await.unwrap_err().into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn schemas_that_fail_to_compile_are_listed_and_answer_503() {
+        let app = TestApp::new().await;
+        app.write_schema("sites", &serde_json::json!({ "type": "object" })).await;
+        app.write_schema("broken", &serde_json::json!({ "type": 12 })).await;
+        app.write_data("broken.yaml", "name: r1\n").await;
+
+        let Json(listing) = list_schemas(State(app.state.clone())).await.unwrap();
+        assert_eq!(listing.schemas, ["sites"]);
+        assert_eq!(listing.failed.len(), 1);
+        assert_eq!(listing.failed[0].name, "broken");
+
+        let error = app.state.yaml_service.get_yaml_data("broken", None).await.unwrap_err();
+        assert!(matches!(&error, models::ApiError::SchemaUnavailable(message) if message.contains("'broken'")));
+        assert_eq!(error.into_response().status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,299 @@
+// File Path: src/services/backup_pool.rs
+// Version: 1.2.0
+// Description: Bounded pool limiting how many backups run against the Python API at once.
+// Protects both the Python service and the devices from a burst of backup requests.
+//
+// Key Features:
+// - Semaphore-bounded concurrency, configurable via MAX_CONCURRENT_BACKUPS (default 4)
+// - Backups over the limit wait in FIFO order and report their queue position
+// - Queue entries are dropped when the waiting task finishes or is aborted
+// - Per-device cooldown (BACKUP_DEVICE_COOLDOWN_SECS, default 30) between backups of one device
+// - Batches admit all of their devices or none of them
+//
+// Usage Guide:
+// ```
+// let admission = match backup_pool.try_acquire() {
+//     Some(permit) => Ok(permit),
+//     None => Err(backup_pool.enqueue(&job_id)), // ticket.position() for the `queued` event
+// };
+// tokio::spawn(async move {
+//     let _permit = match admission { Ok(permit) => permit, Err(ticket) => ticket.wait().await };
+//     // ... run the backup, the slot is released when `_permit` is dropped
+// });
+// ```
+// Before admitting a backup, check the device cooldown (skipped for `force` requests):
+// ```
+// backup_pool.start_device_backup(&device, force)?; // 429 with the time remaining
+// backup_pool.start_device_backups(&devices, force)?; // 400 listing every device in cooldown
+// ```
+//
+// Change Log:
+// - 1.2.0: Added all-or-nothing cooldown admission for batches
+// - 1.1.0: Added per-device backup cooldown
+// - 1.0.0: Initial implementation
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, warn};
+
+use crate::config::env_or;
+use crate::models::{ApiError, ApiResult, DeviceRejection};
+
+// =============================================================================
+// SECTION 1: CONFIGURATION
+// =============================================================================
+
+/// Concurrent backups allowed when MAX_CONCURRENT_BACKUPS is unset or invalid
+pub const DEFAULT_MAX_CONCURRENT_BACKUPS: usize = 4;
+
+/// Reads the concurrency limit from MAX_CONCURRENT_BACKUPS
+pub fn max_concurrent_backups_from_env() -> usize {
+    env_or("MAX_CONCURRENT_BACKUPS", DEFAULT_MAX_CONCURRENT_BACKUPS).max(1)
+}
+
+/// Seconds between backups of one device when BACKUP_DEVICE_COOLDOWN_SECS is unset or invalid
+pub const DEFAULT_DEVICE_COOLDOWN_SECS: u64 = 30;
+
+/// Reads the per-device cooldown from BACKUP_DEVICE_COOLDOWN_SECS; `0` disables it
+pub fn device_cooldown_from_env() -> Duration {
+    Duration::from_secs(env_or("BACKUP_DEVICE_COOLDOWN_SECS", DEFAULT_DEVICE_COOLDOWN_SECS))
+}
+
+// =============================================================================
+// SECTION 2: POOL IMPLEMENTATION
+// =============================================================================
+
+/// Slot held by a running backup; dropping it frees the slot
+pub type BackupPermit = OwnedSemaphorePermit;
+
+/// Bounded pool of backup slots
+#[derive(Debug)]
+pub struct BackupPool {
+    permits: Arc<Semaphore>,
+    /// Job ids waiting for a slot, oldest first
+    waiting: Arc<Mutex<VecDeque<String>>>,
+    /// Minimum time between backups of the same device
+    device_cooldown: Duration,
+    /// When each device's last backup was admitted
+    last_started: Mutex<HashMap<String, Instant>>,
+}
+
+/// A queued backup's place in line
+#[derive(Debug)]
+pub struct QueueTicket {
+    job_id: String,
+    position: usize,
+    permits: Arc<Semaphore>,
+    waiting: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl BackupPool {
+    /// Creates a pool running at most `max_concurrent` backups at once
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            waiting: Arc::new(Mutex::new(VecDeque::new())),
+            device_cooldown: Duration::ZERO,
+            last_started: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the minimum time between backups of the same device
+    pub fn with_device_cooldown(mut self, cooldown: Duration) -> Self {
+        self.device_cooldown = cooldown;
+        self
+    }
+
+    /// Records a backup of `device` starting now, unless one started within the cooldown
+    ///
+    /// Rejected with 429 and the time left in the cooldown. `force` skips the check but
+    /// still restarts the cooldown.
+    pub fn start_device_backup(&self, device: &str, force: bool) -> ApiResult<()> {
+        let now = Instant::now();
+        let mut last_started = self.last_started.lock().unwrap_or_else(|e| e.into_inner());
+        last_started.retain(|_, started| now.duration_since(*started) < self.device_cooldown);
+
+        if !force {
+            if let Some(retry_in) = self.cooldown_left(&last_started, device, now) {
+                warn!("Backup of {} rejected: cooldown has {}s left", device, retry_in);
+                return Err(ApiError::TooManyRequests(format!(
+                    "Device '{}' was backed up less than {}s ago; retry in {}s or set force",
+                    device,
+                    self.device_cooldown.as_secs(),
+                    retry_in
+                )));
+            }
+        }
+        if !self.device_cooldown.is_zero() {
+            last_started.insert(device.to_string(), now);
+        }
+        Ok(())
+    }
+
+    /// Records backups of all `devices` starting now, or of none of them
+    ///
+    /// When any device is within its cooldown the whole batch is rejected with 400
+    /// listing those devices, and no cooldown is touched.
+    pub fn start_device_backups(&self, devices: &[&str], force: bool) -> ApiResult<()> {
+        let now = Instant::now();
+        let mut last_started = self.last_started.lock().unwrap_or_else(|e| e.into_inner());
+        last_started.retain(|_, started| now.duration_since(*started) < self.device_cooldown);
+
+        if !force {
+            let rejected: Vec<DeviceRejection> = devices
+                .iter()
+                .filter_map(|device| {
+                    self.cooldown_left(&last_started, device, now).map(|retry_in| DeviceRejection {
+                        device: device.to_string(),
+                        reason: format!("Backed up less than {}s ago; retry in {}s or set force", self.device_cooldown.as_secs(), retry_in),
+                    })
+                })
+                .collect();
+            if !rejected.is_empty() {
+                warn!("Backup batch rejected: {} device(s) in cooldown", rejected.len());
+                return Err(ApiError::InvalidDevices(rejected));
+            }
+        }
+        if !self.device_cooldown.is_zero() {
+            for device in devices {
+                last_started.insert(device.to_string(), now);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whole seconds left in `device`'s cooldown, if it is in one
+    fn cooldown_left(&self, last_started: &HashMap<String, Instant>, device: &str, now: Instant) -> Option<u64> {
+        let started = last_started.get(device)?;
+        let remaining = self.device_cooldown - now.duration_since(*started);
+        Some(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0))
+    }
+
+    /// Takes a free slot without waiting
+    pub fn try_acquire(&self) -> Option<BackupPermit> {
+        self.permits.clone().try_acquire_owned().ok()
+    }
+
+    /// Places a job at the back of the queue
+    pub fn enqueue(&self, job_id: &str) -> QueueTicket {
+        let position = {
+            let mut waiting = self.waiting.lock().unwrap_or_else(|e| e.into_inner());
+            waiting.push_back(job_id.to_string());
+            waiting.len()
+        };
+        debug!("Backup {} queued at position {}", job_id, position);
+
+        QueueTicket {
+            job_id: job_id.to_string(),
+            position,
+            permits: Arc::clone(&self.permits),
+            waiting: Arc::clone(&self.waiting),
+        }
+    }
+}
+
+impl QueueTicket {
+    /// 1-based position in the queue when the job was enqueued
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Waits for a slot; the semaphore hands out slots in FIFO order
+    pub async fn wait(self) -> BackupPermit {
+        self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("backup pool semaphore is never closed")
+        // `self` is dropped here, removing the job from the waiting list
+    }
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        let mut waiting = self.waiting.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(index) = waiting.iter().position(|id| id == &self.job_id) {
+            waiting.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_in_cooldown_starts_no_device() {
+        let pool = BackupPool::new(1).with_device_cooldown(Duration::from_secs(60));
+        pool.start_device_backup("r2", false).unwrap();
+
+        match pool.start_device_backups(&["r1", "r2", "r3"], false) {
+            Err(ApiError::InvalidDevices(rejected)) => {
+                assert_eq!(rejected.len(), 1);
+                assert_eq!(rejected[0].device, "r2");
+            }
+            other => panic!("expected InvalidDevices, got {:?}", other),
+        }
+        // Nothing was admitted, so the other devices are still free
+        pool.start_device_backups(&["r1", "r3"], false).unwrap();
+        assert!(pool.start_device_backup("r1", false).is_err());
+    }
+
+    #[test]
+    fn device_in_cooldown_is_rejected_with_429_unless_forced() {
+        let pool = BackupPool::new(1).with_device_cooldown(Duration::from_secs(60));
+        pool.start_device_backup("r1", false).unwrap();
+
+        match pool.start_device_backup("r1", false) {
+            Err(ApiError::TooManyRequests(message)) => assert!(message.contains("retry in 60s"), "{}", message),
+            other => panic!("expected TooManyRequests, got {:?}", other),
+        }
+        pool.start_device_backup("r1", true).unwrap();
+        pool.start_device_backup("r2", false).unwrap();
+
+        let pool = BackupPool::new(1);
+        pool.start_device_backup("r1", false).unwrap();
+        pool.start_device_backup("r1", false).unwrap();
+    }
+
+    #[test]
+    fn cooldown_left_rounds_partial_seconds_up() {
+        let pool = BackupPool::new(1).with_device_cooldown(Duration::from_secs(10));
+        let started = Instant::now();
+        let last_started = HashMap::from([("r1".to_string(), started)]);
+
+        assert_eq!(pool.cooldown_left(&last_started, "r1", started + Duration::from_millis(1500)), Some(9));
+        assert_eq!(pool.cooldown_left(&last_started, "r1", started + Duration::from_secs(2)), Some(8));
+        assert_eq!(pool.cooldown_left(&last_started, "r1", started + Duration::from_millis(9_999)), Some(1));
+        assert_eq!(pool.cooldown_left(&last_started, "r2", started), None);
+    }
+
+    #[tokio::test]
+    async fn queued_backups_start_in_order_as_slots_free() {
+        let pool = BackupPool::new(1);
+        let running = pool.try_acquire().unwrap();
+        assert!(pool.try_acquire().is_none());
+
+        let first = pool.enqueue("job-1");
+        let second = pool.enqueue("job-2");
+        assert_eq!((first.position(), second.position()), (1, 2));
+
+        let first = tokio::spawn(first.wait());
+        tokio::task::yield_now().await;
+        let second = tokio::spawn(second.wait());
+        tokio::task::yield_now().await;
+        assert!(!first.is_finished());
+
+        drop(running);
+        let first_permit = first.await.unwrap();
+        assert!(!second.is_finished());
+        assert!(pool.waiting.lock().unwrap().iter().eq(["job-2"]));
+        drop(first_permit);
+        let _second_permit = second.await.unwrap();
+        assert!(pool.waiting.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,299 @@
+// File Path: src/services/credentials_service.rs
+// Version: 1.1.0
+// Description: Resolves the username and password used to log in to a device, so handlers
+// never pick credentials out of request bodies themselves.
+//
+// Key Features:
+// - Each field resolves independently with a fixed precedence:
+//   1. request override (blank values count as absent)
+//   2. inventory entry: `username`, and `password_env` naming the env var holding the password;
+//      only variables starting with DEVICE_PASSWORD_ can be named, so an inventory edit cannot
+//      read arbitrary server secrets
+//   3. environment defaults DEVICE_USERNAME / DEVICE_PASSWORD
+// - Inventory entries match by host name (case-insensitive) or IP address
+// - `Credentials` never prints its password: Debug and Display show `***` and where each
+//   field came from
+// - A field no source provides is a 400 naming the device and the places to set it
+//
+// Usage Guide:
+// ```
+// let credentials = state.credentials_service
+//     .resolve(&hostname, payload.username.as_deref(), payload.password.as_deref())
+//     .await?;
+// info!("Backing up {} as {}", hostname, credentials); // username=admin (request), password=*** (environment)
+// client.post(url).json(&json!({ "username": credentials.username, "password": credentials.password() }))
+// ```
+//
+// Change Log:
+// - 1.1.0: `password_env` must name a DEVICE_PASSWORD_* variable
+// - 1.0.0: Initial implementation
+
+use serde::Serialize;
+use serde_json::Value;
+use std::{fmt, sync::Arc};
+use tracing::{debug, warn};
+
+use crate::{
+    models::{ApiError, ApiResult},
+    services::YamlService,
+};
+
+/// Shown in place of a password wherever credentials are logged
+pub const MASK: &str = "***";
+
+/// Required prefix of the environment variable an inventory `password_env` names
+pub const PASSWORD_ENV_PREFIX: &str = "DEVICE_PASSWORD_";
+
+// =============================================================================
+// SECTION 1: TYPE DEFINITIONS
+// =============================================================================
+
+/// Where a credential field was resolved from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialSource {
+    Request,
+    Inventory,
+    Environment,
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Request => "request",
+            Self::Inventory => "inventory",
+            Self::Environment => "environment",
+        })
+    }
+}
+
+/// Resolved device login; the password is only reachable through `password()`
+#[derive(Clone)]
+pub struct Credentials {
+    pub username: String,
+    password: String,
+    pub username_source: CredentialSource,
+    pub password_source: CredentialSource,
+}
+
+impl Credentials {
+    /// The plain-text password, for handing to the device-facing call only
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl fmt::Display for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "username={} ({}), password={} ({})",
+            self.username, self.username_source, MASK, self.password_source
+        )
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &MASK)
+            .field("username_source", &self.username_source)
+            .field("password_source", &self.password_source)
+            .finish()
+    }
+}
+
+/// Environment-wide credential defaults
+#[derive(Clone, Default)]
+pub struct CredentialDefaults {
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl CredentialDefaults {
+    /// Reads DEVICE_USERNAME and DEVICE_PASSWORD
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.trim().is_empty());
+        Self {
+            username: var("DEVICE_USERNAME"),
+            password: var("DEVICE_PASSWORD"),
+        }
+    }
+}
+
+impl fmt::Debug for CredentialDefaults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialDefaults")
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| MASK))
+            .finish()
+    }
+}
+
+// =============================================================================
+// SECTION 2: SERVICE IMPLEMENTATION
+// =============================================================================
+
+/// Resolves device credentials from the request, the inventory and the environment
+pub struct CredentialsService {
+    yaml_service: Arc<YamlService>,
+    defaults: CredentialDefaults,
+}
+
+impl CredentialsService {
+    pub fn new(yaml_service: Arc<YamlService>, defaults: CredentialDefaults) -> Self {
+        Self { yaml_service, defaults }
+    }
+
+    /// Resolves credentials for `hostname`, preferring the request's own values
+    pub async fn resolve(
+        &self,
+        hostname: &str,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> ApiResult<Credentials> {
+        let present = |value: Option<&str>| value.filter(|value| !value.trim().is_empty()).map(str::to_string);
+        let username = present(username);
+        let password = present(password);
+
+        // The inventory is only read when the request leaves something out
+        let entry = if username.is_none() || password.is_none() {
+            match self.yaml_service.get_yaml_data("inventory", Some("inventories/inventory.yaml")).await {
+                Ok(inventory) => inventory_entry(&inventory, hostname),
+                Err(e) => {
+                    warn!("Inventory unavailable for credential lookup of {}: {}", hostname, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let credentials = resolve_with(hostname, username, password, entry.as_ref(), &self.defaults)?;
+        debug!("Resolved credentials for {}: {}", hostname, credentials);
+        Ok(credentials)
+    }
+}
+
+/// Applies the precedence to one device's candidate values
+fn resolve_with(
+    hostname: &str,
+    username: Option<String>,
+    password: Option<String>,
+    entry: Option<&Value>,
+    defaults: &CredentialDefaults,
+) -> ApiResult<Credentials> {
+    let inventory_username = entry
+        .and_then(|entry| entry.get("username"))
+        .and_then(Value::as_str)
+        .filter(|value| !value.trim().is_empty())
+        .map(str::to_string);
+    let inventory_password = entry
+        .and_then(|entry| entry.get("password_env"))
+        .and_then(Value::as_str)
+        .filter(|name| {
+            let allowed = name.starts_with(PASSWORD_ENV_PREFIX) && name.len() > PASSWORD_ENV_PREFIX.len();
+            if !allowed {
+                warn!(
+                    "Ignoring password variable {} for {}: only {}* variables can be named",
+                    name, hostname, PASSWORD_ENV_PREFIX
+                );
+            }
+            allowed
+        })
+        .and_then(|name| match std::env::var(name) {
+            Ok(value) if !value.trim().is_empty() => Some(value),
+            _ => {
+                warn!("Inventory entry for {} names password variable {} but it is not set", hostname, name);
+                None
+            }
+        });
+
+    let pick = |request: Option<String>, inventory: Option<String>, environment: Option<String>| {
+        request
+            .map(|value| (value, CredentialSource::Request))
+            .or_else(|| inventory.map(|value| (value, CredentialSource::Inventory)))
+            .or_else(|| environment.map(|value| (value, CredentialSource::Environment)))
+    };
+    let missing = |field: &str, env: &str| {
+        ApiError::BadRequest(format!(
+            "No {} for {}: pass it in the request, set it in the inventory or set {}",
+            field, hostname, env
+        ))
+    };
+
+    let (username, username_source) = pick(username, inventory_username, defaults.username.clone())
+        .ok_or_else(|| missing("username", "DEVICE_USERNAME"))?;
+    let (password, password_source) = pick(password, inventory_password, defaults.password.clone())
+        .ok_or_else(|| missing("password", "DEVICE_PASSWORD"))?;
+
+    Ok(Credentials { username, password, username_source, password_source })
+}
+
+/// Raw inventory entry whose host name or IP address is `hostname`
+fn inventory_entry(inventory: &Value, hostname: &str) -> Option<Value> {
+    inventory
+        .get("locations")?
+        .as_object()?
+        .values()
+        .filter_map(Value::as_object)
+        .flat_map(|roles| roles.values())
+        .filter_map(Value::as_array)
+        .flatten()
+        .find(|entry| {
+            let field = |name: &str| entry.get(name).and_then(Value::as_str).unwrap_or_default();
+            field("host_name").eq_ignore_ascii_case(hostname) || field("ip_address") == hostname
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_beats_inventory_beats_environment() {
+        let inventory = serde_json::json!({
+            "locations": { "LAB": { "routers": [
+                { "host_name": "R1", "ip_address": "10.0.0.1", "username": "netops" }
+            ]}}
+        });
+        let defaults = CredentialDefaults {
+            username: Some("admin".to_string()),
+            password: Some("default-secret".to_string()),
+        };
+        let entry = inventory_entry(&inventory, "r1");
+
+        let credentials = resolve_with("r1", None, None, entry.as_ref(), &defaults).unwrap();
+        assert_eq!(credentials.username, "netops");
+        assert_eq!(credentials.username_source, CredentialSource::Inventory);
+        assert_eq!(credentials.password(), "default-secret");
+        assert_eq!(credentials.password_source, CredentialSource::Environment);
+
+        let credentials = resolve_with("r1", Some("oncall".to_string()), Some("hunter2".to_string()), entry.as_ref(), &defaults).unwrap();
+        assert_eq!(credentials.username_source, CredentialSource::Request);
+        assert_eq!(credentials.password_source, CredentialSource::Request);
+        assert!(!format!("{} {:?}", credentials, credentials).contains("hunter2"));
+
+        assert!(resolve_with("r2", None, None, None, &CredentialDefaults::default()).is_err());
+    }
+
+    #[test]
+    fn password_env_must_carry_the_device_password_prefix() {
+        std::env::set_var("DEVICE_PASSWORD_CREDENTIALS_TEST", "lab-secret");
+        std::env::set_var("CREDENTIALS_TEST_SERVER_SECRET", "server-secret");
+        let defaults = CredentialDefaults {
+            username: Some("admin".to_string()),
+            password: None,
+        };
+
+        let entry = serde_json::json!({ "host_name": "r1", "password_env": "DEVICE_PASSWORD_CREDENTIALS_TEST" });
+        let credentials = resolve_with("r1", None, None, Some(&entry), &defaults).unwrap();
+        assert_eq!(credentials.password(), "lab-secret");
+        assert_eq!(credentials.password_source, CredentialSource::Inventory);
+
+        let entry = serde_json::json!({ "host_name": "r1", "password_env": "CREDENTIALS_TEST_SERVER_SECRET" });
+        assert!(resolve_with("r1", None, None, Some(&entry), &defaults).is_err());
+    }
+}
@@ -0,0 +1,375 @@
+// File Path: src/services/device_list_service.rs
+// Version: 1.2.0
+// Description: Fetches the device list from the Python API behind a circuit breaker, so the
+// device picker keeps working from the last known list during transient Python outages.
+//
+// Key Features:
+// - Opens after DEVICE_LIST_BREAKER_THRESHOLD consecutive failures (default 3)
+// - While open, requests are answered from the last known list, flagged stale, without calling Python
+// - A background task retries every DEVICE_LIST_BREAKER_RETRY_SECS (default 30) and closes the breaker on success;
+//   while its probe is in flight the breaker is half-open and requests are still served from the cache
+// - Each call to the Python API gives up after DEVICE_LIST_TIMEOUT_SECS (default 10), so a hung
+//   Python API counts as a failure instead of stalling the picker
+// - Breaker state and counters are exported in the Prometheus metrics
+//
+// Usage Guide:
+// ```
+// let device_list_service = DeviceListService::new(DeviceListBreakerConfig::from_env(&python_api_url));
+// let listing = device_list_service.list().await?;
+// // listing.stale is true when the list came from the cache while the breaker is open
+// ```
+//
+// Change Log:
+// - 1.2.0: The device list URL is built from PYTHON_API_URL instead of a fixed host
+// - 1.1.0: Python API calls time out; the breaker is half-open while a retry probe runs
+// - 1.0.0: Initial implementation
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::config::env_or;
+use crate::models::{ApiError, ApiResult, DeviceList};
+
+/// Python API path listing devices with backups
+const DEVICE_LIST_PATH: &str = "/api/backups/devices";
+
+// =============================================================================
+// SECTION 1: CONFIGURATION
+// =============================================================================
+
+/// Circuit breaker tuning
+#[derive(Debug, Clone)]
+pub struct DeviceListBreakerConfig {
+    /// Consecutive failures that open the breaker
+    pub failure_threshold: u32,
+    /// Time between background retries while the breaker is open
+    pub retry_interval: Duration,
+    /// Time allowed for one device list call to the Python API
+    pub request_timeout: Duration,
+    /// Python API endpoint listing devices
+    pub url: String,
+}
+
+impl DeviceListBreakerConfig {
+    /// Reads the breaker tuning from the environment, listing devices from `python_api_url`
+    pub fn from_env(python_api_url: &str) -> Self {
+        Self {
+            failure_threshold: env_or("DEVICE_LIST_BREAKER_THRESHOLD", 3).max(1),
+            retry_interval: Duration::from_secs(env_or("DEVICE_LIST_BREAKER_RETRY_SECS", 30).max(1)),
+            request_timeout: Duration::from_secs(env_or("DEVICE_LIST_TIMEOUT_SECS", 10).max(1)),
+            url: format!("{}{}", python_api_url.trim_end_matches('/'), DEVICE_LIST_PATH),
+        }
+    }
+}
+
+// =============================================================================
+// SECTION 2: TYPE DEFINITIONS
+// =============================================================================
+
+/// Whether calls go through to the Python API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls go to the Python API
+    Closed,
+    /// Calls are short-circuited while a background task retries
+    Open,
+    /// A background retry is in flight; calls are still short-circuited
+    HalfOpen,
+}
+
+/// A device list and whether it came from the cache
+#[derive(Debug, Clone)]
+pub struct DeviceListing {
+    pub devices: DeviceList,
+    /// True when served from the last known list because the breaker is open
+    pub stale: bool,
+    /// When the list was fetched from the Python API
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    last_known: Option<(DeviceList, DateTime<Utc>)>,
+}
+
+// =============================================================================
+// SECTION 3: SERVICE IMPLEMENTATION
+// =============================================================================
+
+/// Device list client guarded by a circuit breaker
+pub struct DeviceListService {
+    config: DeviceListBreakerConfig,
+    client: Client,
+    inner: Mutex<BreakerInner>,
+    /// Failed calls to the Python API
+    failures: AtomicU64,
+    /// Requests answered without calling the Python API
+    short_circuits: AtomicU64,
+}
+
+impl DeviceListService {
+    pub fn new(config: DeviceListBreakerConfig) -> Self {
+        let client = Client::builder()
+            .timeout(config.request_timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                warn!("Failed to build device list client with a timeout, using defaults: {}", e);
+                Client::new()
+            });
+        Self {
+            config,
+            client,
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                last_known: None,
+            }),
+            failures: AtomicU64::new(0),
+            short_circuits: AtomicU64::new(0),
+        }
+    }
+
+    /// Lists devices, falling back to the last known list while the breaker is open
+    ///
+    /// Fails with 503 when the breaker is open and no list was ever fetched.
+    pub async fn list(self: &Arc<Self>) -> ApiResult<DeviceListing> {
+        if let Some(cached) = self.short_circuit().await? {
+            return Ok(cached);
+        }
+
+        match self.fetch().await {
+            Ok(devices) => Ok(self.record_success(devices).await),
+            Err(e) => {
+                self.record_failure(&e).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Current breaker state
+    pub async fn state(&self) -> BreakerState {
+        self.inner.lock().await.state
+    }
+
+    /// Breaker state and counters in the Prometheus text format
+    pub async fn render_prometheus(&self) -> String {
+        let inner = self.inner.lock().await;
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP device_list_breaker_open Whether the device list circuit breaker is open.");
+        let _ = writeln!(out, "# TYPE device_list_breaker_open gauge");
+        let _ = writeln!(out, "device_list_breaker_open {}", (inner.state != BreakerState::Closed) as u8);
+        let _ = writeln!(out, "# HELP device_list_breaker_half_open Whether a device list retry probe is in flight.");
+        let _ = writeln!(out, "# TYPE device_list_breaker_half_open gauge");
+        let _ = writeln!(out, "device_list_breaker_half_open {}", (inner.state == BreakerState::HalfOpen) as u8);
+        let _ = writeln!(out, "# HELP device_list_consecutive_failures Consecutive failed device list calls.");
+        let _ = writeln!(out, "# TYPE device_list_consecutive_failures gauge");
+        let _ = writeln!(out, "device_list_consecutive_failures {}", inner.consecutive_failures);
+        let _ = writeln!(out, "# HELP device_list_failures_total Failed device list calls to the Python API.");
+        let _ = writeln!(out, "# TYPE device_list_failures_total counter");
+        let _ = writeln!(out, "device_list_failures_total {}", self.failures.load(Ordering::Relaxed));
+        let _ = writeln!(out, "# HELP device_list_short_circuits_total Device list requests answered while the breaker was open.");
+        let _ = writeln!(out, "# TYPE device_list_short_circuits_total counter");
+        let _ = writeln!(out, "device_list_short_circuits_total {}", self.short_circuits.load(Ordering::Relaxed));
+        out
+    }
+
+    /// The cached listing when the breaker is open; `None` when calls may go through
+    async fn short_circuit(&self) -> ApiResult<Option<DeviceListing>> {
+        let inner = self.inner.lock().await;
+        if inner.state == BreakerState::Closed {
+            return Ok(None);
+        }
+
+        self.short_circuits.fetch_add(1, Ordering::Relaxed);
+        match &inner.last_known {
+            Some((devices, fetched_at)) => Ok(Some(DeviceListing {
+                devices: devices.clone(),
+                stale: true,
+                fetched_at: *fetched_at,
+            })),
+            None => Err(ApiError::ServiceUnavailable(
+                "Python API device list unavailable and no cached list exists".to_string(),
+            )),
+        }
+    }
+
+    async fn record_success(&self, devices: DeviceList) -> DeviceListing {
+        let fetched_at = Utc::now();
+        let mut inner = self.inner.lock().await;
+        if inner.state != BreakerState::Closed {
+            info!("Device list circuit breaker closed: Python API is reachable again");
+        }
+        inner.state = BreakerState::Closed;
+        inner.consecutive_failures = 0;
+        inner.last_known = Some((devices.clone(), fetched_at));
+
+        DeviceListing {
+            devices,
+            stale: false,
+            fetched_at,
+        }
+    }
+
+    async fn record_failure(self: &Arc<Self>, error: &ApiError) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        let mut inner = self.inner.lock().await;
+        inner.consecutive_failures += 1;
+
+        if inner.state == BreakerState::Closed && inner.consecutive_failures >= self.config.failure_threshold {
+            inner.state = BreakerState::Open;
+            warn!(
+                "Device list circuit breaker opened after {} consecutive failures (last: {})",
+                inner.consecutive_failures, error
+            );
+            Arc::clone(self).spawn_retry();
+        }
+    }
+
+    /// Retries the Python API until it answers, then closes the breaker
+    fn spawn_retry(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.config.retry_interval).await;
+                self.inner.lock().await.state = BreakerState::HalfOpen;
+                match self.fetch().await {
+                    Ok(devices) => {
+                        self.record_success(devices).await;
+                        return;
+                    }
+                    Err(e) => {
+                        self.failures.fetch_add(1, Ordering::Relaxed);
+                        let mut inner = self.inner.lock().await;
+                        inner.state = BreakerState::Open;
+                        inner.consecutive_failures += 1;
+                        warn!("Device list retry failed, breaker stays open: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Calls the Python API and parses the device list
+    async fn fetch(&self) -> ApiResult<DeviceList> {
+        info!("Calling Python API to list devices");
+
+        let response = self.client.get(&self.config.url)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Failed to connect to Python API: {}", e);
+                ApiError::InternalError(format!("Python API unavailable: {}", e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            error!("Python API returned error: {} - {}", status, body);
+            return Err(ApiError::InternalError(format!("Python API error: {}", status)));
+        }
+
+        let devices_data: serde_json::Value = response.json().await.map_err(|e| {
+            error!("Failed to parse Python API response: {}", e);
+            ApiError::InternalError("Invalid response from Python API".to_string())
+        })?;
+
+        DeviceList::from_upstream(&devices_data).map_err(|reason| {
+            error!("Unexpected device list from Python API: {}", reason);
+            ApiError::UpstreamError(reason)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+    use std::sync::atomic::AtomicU8;
+    use tokio::sync::Semaphore;
+
+    const FAILING: u8 = 0;
+    const HEALTHY: u8 = 1;
+    const HANGING: u8 = 2;
+
+    /// A stand-in Python API whose answer is switched by `mode`; healthy answers wait for a gate permit
+    #[derive(Clone)]
+    struct Upstream {
+        mode: Arc<AtomicU8>,
+        gate: Arc<Semaphore>,
+    }
+
+    async fn devices(State(upstream): State<Upstream>) -> axum::response::Response {
+        match upstream.mode.load(Ordering::SeqCst) {
+            FAILING => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            HEALTHY => {
+                upstream.gate.acquire().await.unwrap().forget();
+                Json(serde_json::json!({ "devices": { "r1": ["r1_1.conf"] } })).into_response()
+            }
+            _ => std::future::pending().await,
+        }
+    }
+
+    async fn wait_for(service: &DeviceListService, state: BreakerState) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while service.state().await != state {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("breaker never became {:?}", state));
+    }
+
+    #[tokio::test]
+    async fn breaker_opens_probes_half_open_and_closes() {
+        let upstream = Upstream { mode: Arc::new(AtomicU8::new(HEALTHY)), gate: Arc::new(Semaphore::new(1)) };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/devices", listener.local_addr().unwrap());
+        let app = Router::new().route("/devices", get(devices)).with_state(upstream.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let service = Arc::new(DeviceListService::new(DeviceListBreakerConfig {
+            failure_threshold: 2,
+            retry_interval: Duration::from_millis(20),
+            request_timeout: Duration::from_millis(500),
+            url,
+        }));
+
+        assert!(!service.list().await.unwrap().stale);
+
+        // Consecutive failures open the breaker; the last known list is served as stale
+        upstream.mode.store(FAILING, Ordering::SeqCst);
+        assert!(service.list().await.is_err());
+        assert_eq!(service.state().await, BreakerState::Closed);
+        assert!(service.list().await.is_err());
+        assert_eq!(service.state().await, BreakerState::Open);
+        assert!(service.list().await.unwrap().stale);
+
+        // The next probe holds the breaker half-open until the Python API answers
+        upstream.mode.store(HEALTHY, Ordering::SeqCst);
+        wait_for(&service, BreakerState::HalfOpen).await;
+        assert!(service.list().await.unwrap().stale);
+        upstream.gate.add_permits(1);
+        wait_for(&service, BreakerState::Closed).await;
+        upstream.gate.add_permits(1);
+        assert!(!service.list().await.unwrap().stale);
+
+        // A hung Python API fails the call at the client timeout
+        upstream.mode.store(HANGING, Ordering::SeqCst);
+        let hung = tokio::time::timeout(Duration::from_secs(2), service.list()).await;
+        assert!(matches!(hung, Ok(Err(_))));
+    }
+}
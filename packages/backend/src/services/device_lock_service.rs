@@ -0,0 +1,166 @@
+// File Path: src/services/device_lock_service.rs
+// Version: 1.1.0
+// Description: Per-device async locks that serialize conflicting operations on the same device.
+// Operations on different devices run in parallel.
+//
+// Key Features:
+// - One async mutex per device, keyed by hostname
+// - Non-blocking acquisition so callers can reject with 409 instead of waiting
+// - Locks are released automatically when the returned guard is dropped
+// - Each held lock records the operation, job id and acquisition time of its holder
+//
+// Usage Guide:
+// ```
+// let guard = device_lock_service.try_lock(&hostname, "restore", None).await
+//     .ok_or_else(|| ApiError::Conflict(format!("Restore already running for {}", hostname)))?;
+// // ... run the operation, guard is released at the end of scope
+// ```
+//
+// Change Log:
+// - 1.1.0: Locks record their holder; `holders()` lists the currently held locks
+// - 1.0.0: Initial implementation
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tracing::debug;
+
+/// Holders of the currently held locks, keyed by device hostname
+type HolderMap = Arc<std::sync::Mutex<HashMap<String, LockRecord>>>;
+
+// =============================================================================
+// SECTION 1: TYPE DEFINITIONS
+// =============================================================================
+
+/// A held device lock as reported by GET /api/devices/locks
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceLockInfo {
+    pub device: String,
+    /// Operation holding the lock, e.g. `restore` or `upgrade`
+    pub operation: String,
+    /// Job tracked for the operation, if it runs as one
+    pub job_id: Option<String>,
+    pub acquired_at: DateTime<Utc>,
+    pub age_secs: i64,
+}
+
+#[derive(Debug)]
+struct LockRecord {
+    operation: String,
+    job_id: Option<String>,
+    acquired_at: DateTime<Utc>,
+}
+
+/// Guard holding a device lock until dropped
+#[derive(Debug)]
+pub struct DeviceLockGuard {
+    device: String,
+    holders: HolderMap,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for DeviceLockGuard {
+    fn drop(&mut self) {
+        // Runs before the mutex guard is released, so a new holder is never removed
+        self.holders.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.device);
+    }
+}
+
+// =============================================================================
+// SECTION 2: SERVICE IMPLEMENTATION
+// =============================================================================
+// Lock registry keyed by device hostname
+
+/// Registry of per-device locks
+#[derive(Debug, Default)]
+pub struct DeviceLockService {
+    /// One mutex per device hostname
+    locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    holders: HolderMap,
+}
+
+impl DeviceLockService {
+    /// Creates an empty lock registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tries to acquire the lock for a device without waiting
+    ///
+    /// # Arguments
+    /// * `device` - Device hostname
+    /// * `operation` - Operation taking the lock, reported while it is held
+    /// * `job_id` - Job running the operation, if any
+    ///
+    /// # Returns
+    /// A guard holding the lock, or None if the device is already locked
+    pub async fn try_lock(&self, device: &str, operation: &str, job_id: Option<&str>) -> Option<DeviceLockGuard> {
+        let lock = {
+            let mut locks = self.locks.lock().await;
+            locks
+                .entry(device.to_string())
+                .or_insert_with(|| Arc::new(Mutex::new(())))
+                .clone()
+        };
+
+        let Ok(guard) = lock.try_lock_owned() else {
+            debug!("Device lock for {}: busy", device);
+            return None;
+        };
+        debug!("Device lock for {}: acquired for {}", device, operation);
+
+        self.holders.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            device.to_string(),
+            LockRecord {
+                operation: operation.to_string(),
+                job_id: job_id.map(str::to_string),
+                acquired_at: Utc::now(),
+            },
+        );
+        Some(DeviceLockGuard {
+            device: device.to_string(),
+            holders: Arc::clone(&self.holders),
+            _guard: guard,
+        })
+    }
+
+    /// Currently held locks, oldest first
+    pub fn holders(&self) -> Vec<DeviceLockInfo> {
+        let now = Utc::now();
+        let holders = self.holders.lock().unwrap_or_else(|e| e.into_inner());
+        let mut locks: Vec<DeviceLockInfo> = holders
+            .iter()
+            .map(|(device, record)| DeviceLockInfo {
+                device: device.clone(),
+                operation: record.operation.clone(),
+                job_id: record.job_id.clone(),
+                acquired_at: record.acquired_at,
+                age_secs: (now - record.acquired_at).num_seconds().max(0),
+            })
+            .collect();
+        locks.sort_by(|a, b| a.acquired_at.cmp(&b.acquired_at).then_with(|| a.device.cmp(&b.device)));
+        locks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn held_locks_are_listed_until_released() {
+        let service = DeviceLockService::new();
+        let guard = service.try_lock("r1", "upgrade", Some("job-1")).await.unwrap();
+        assert!(service.try_lock("r1", "restore", None).await.is_none());
+
+        let holders = service.holders();
+        assert_eq!(holders.len(), 1);
+        assert_eq!(holders[0].operation, "upgrade");
+        assert_eq!(holders[0].job_id.as_deref(), Some("job-1"));
+
+        drop(guard);
+        assert!(service.holders().is_empty());
+        assert!(service.try_lock("r1", "restore", None).await.is_some());
+    }
+}
@@ -0,0 +1,258 @@
+// File Path: src/services/execution_queue.rs
+// Version: 1.0.1
+// Description: Priority queue bounding how many Python executions run at once. Urgent runs
+// (e.g. emergency backups) take the next free slot ahead of bulk workloads.
+//
+// Key Features:
+// - At most `max_concurrent` executions hold a slot; the rest wait
+// - A freed slot goes to the waiter with the highest effective priority, oldest first on ties
+// - Aging: a waiter's priority rises one level per `aging` interval waited, so low-priority
+//   runs cannot starve behind a steady stream of urgent ones
+// - Slots are released on drop, including when a waiting or running task is aborted
+// - Waiters whose task was aborted are dropped from the queue and not counted as waiting
+//
+// Usage Guide:
+// ```
+// let queue = ExecutionQueue::new(4, Duration::from_secs(30));
+// let _slot = queue.acquire(ExecutionPriority::High).await;
+// // ... run; the slot is handed to the next waiter when `_slot` is dropped
+// ```
+//
+// Change Log:
+// - 1.0.1: Aborted waiters are pruned instead of counted and blocking the free-slot fast path
+// - 1.0.0: Initial implementation
+
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+// =============================================================================
+// SECTION 1: PRIORITY LEVELS
+// =============================================================================
+
+/// Scheduling priority of an execution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionPriority {
+    /// Bulk and background runs
+    Low,
+    #[default]
+    Normal,
+    High,
+    /// Emergency runs, e.g. a backup before an urgent change
+    Urgent,
+}
+
+impl ExecutionPriority {
+    fn rank(self) -> u64 {
+        self as u64
+    }
+}
+
+// =============================================================================
+// SECTION 2: QUEUE IMPLEMENTATION
+// =============================================================================
+
+/// Bounded execution slots handed out by priority
+#[derive(Debug)]
+pub struct ExecutionQueue {
+    max_concurrent: usize,
+    /// Waiting time that raises a waiter's priority by one level
+    aging: Duration,
+    state: Mutex<QueueState>,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    running: usize,
+    waiting: Vec<Waiter>,
+    next_seq: u64,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    seq: u64,
+    priority: ExecutionPriority,
+    enqueued: Instant,
+    slot: oneshot::Sender<ExecutionSlot>,
+}
+
+/// A held execution slot; dropping it passes the slot on
+#[derive(Debug)]
+pub struct ExecutionSlot {
+    queue: Arc<ExecutionQueue>,
+}
+
+impl ExecutionQueue {
+    pub fn new(max_concurrent: usize, aging: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            max_concurrent: max_concurrent.max(1),
+            aging,
+            state: Mutex::new(QueueState::default()),
+        })
+    }
+
+    /// Waits for a slot, ahead of lower-priority waiters
+    pub async fn acquire(self: &Arc<Self>, priority: ExecutionPriority) -> ExecutionSlot {
+        let receiver = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.prune_abandoned();
+            if state.running < self.max_concurrent && state.waiting.is_empty() {
+                state.running += 1;
+                return ExecutionSlot { queue: Arc::clone(self) };
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiting.push(Waiter { seq, priority, enqueued: Instant::now(), slot: sender });
+            debug!("Execution queued with {:?} priority ({} waiting)", priority, state.waiting.len());
+            receiver
+        };
+
+        // The sender is only dropped unsent when the queue itself is dropped
+        receiver.await.expect("execution queue outlives its waiters")
+    }
+
+    /// Number of executions waiting for a slot
+    pub fn waiting(&self) -> usize {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.prune_abandoned();
+        state.waiting.len()
+    }
+
+    /// Hands a freed slot to the best waiter, or returns it to the pool
+    fn release(self: &Arc<Self>) {
+        let next = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.prune_abandoned();
+            match self.next_waiter(&state.waiting) {
+                Some(index) => Some(state.waiting.remove(index)),
+                None => {
+                    state.running -= 1;
+                    None
+                }
+            }
+        };
+
+        if let Some(waiter) = next {
+            // A waiter that gave up returns the slot, which is then dropped and released again
+            let _ = waiter.slot.send(ExecutionSlot { queue: Arc::clone(self) });
+        }
+    }
+
+    /// Index of the waiter with the highest aged priority, oldest first on ties
+    fn next_waiter(&self, waiting: &[Waiter]) -> Option<usize> {
+        let now = Instant::now();
+        waiting
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, waiter)| {
+                let aged = match self.aging.as_millis() {
+                    0 => 0,
+                    aging => (now.duration_since(waiter.enqueued).as_millis() / aging) as u64,
+                };
+                (waiter.priority.rank() + aged, std::cmp::Reverse(waiter.seq))
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+impl QueueState {
+    /// Drops waiters whose acquiring task was aborted or stopped waiting
+    fn prune_abandoned(&mut self) {
+        self.waiting.retain(|waiter| !waiter.slot.is_closed());
+    }
+}
+
+impl Drop for ExecutionSlot {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn higher_priority_waiters_go_first() {
+        let queue = ExecutionQueue::new(1, Duration::from_secs(3600));
+        let running = queue.acquire(ExecutionPriority::Normal).await;
+
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        for priority in [ExecutionPriority::Low, ExecutionPriority::Urgent, ExecutionPriority::Normal] {
+            let queue = Arc::clone(&queue);
+            let order_tx = order_tx.clone();
+            tokio::spawn(async move {
+                let _slot = queue.acquire(priority).await;
+                order_tx.send(priority).unwrap();
+            });
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(queue.waiting(), 3);
+
+        drop(running);
+        let order = [
+            order_rx.recv().await.unwrap(),
+            order_rx.recv().await.unwrap(),
+            order_rx.recv().await.unwrap(),
+        ];
+        assert_eq!(order, [ExecutionPriority::Urgent, ExecutionPriority::Normal, ExecutionPriority::Low]);
+    }
+
+    #[tokio::test]
+    async fn long_waiters_age_past_newer_urgent_ones() {
+        let queue = ExecutionQueue::new(1, Duration::from_millis(10));
+        let running = queue.acquire(ExecutionPriority::Normal).await;
+
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        let spawn_waiter = |priority| {
+            let queue = Arc::clone(&queue);
+            let order_tx = order_tx.clone();
+            tokio::spawn(async move {
+                let _slot = queue.acquire(priority).await;
+                order_tx.send(priority).unwrap();
+            })
+        };
+
+        // Ten aging intervals lift the low-priority waiter well past Urgent
+        spawn_waiter(ExecutionPriority::Low);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        spawn_waiter(ExecutionPriority::Urgent);
+        tokio::task::yield_now().await;
+        assert_eq!(queue.waiting(), 2);
+
+        drop(running);
+        let order = [order_rx.recv().await.unwrap(), order_rx.recv().await.unwrap()];
+        assert_eq!(order, [ExecutionPriority::Low, ExecutionPriority::Urgent]);
+    }
+
+    #[tokio::test]
+    async fn aborted_waiters_are_not_counted_or_served() {
+        let queue = ExecutionQueue::new(1, Duration::from_secs(3600));
+        let running = queue.acquire(ExecutionPriority::Normal).await;
+
+        let waiter = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            async move {
+                let _slot = queue.acquire(ExecutionPriority::High).await;
+            }
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(queue.waiting(), 1);
+
+        waiter.abort();
+        let _ = waiter.await;
+        assert_eq!(queue.waiting(), 0);
+
+        // The freed slot is not parked behind the abandoned waiter
+        drop(running);
+        let slot = tokio::time::timeout(Duration::from_secs(1), queue.acquire(ExecutionPriority::Low)).await;
+        assert!(slot.is_ok());
+    }
+}
@@ -0,0 +1,171 @@
+// File Path: src/services/geoip_service.rs
+// Version: 1.0.1
+// Description: Optional MaxMind GeoIP lookups adding country and ASN context to WebSocket
+// connections for ops dashboards.
+//
+// Key Features:
+// - Country from a GeoLite2/GeoIP2 Country (or City) database, ASN from an ASN database
+// - Compiled in with the `geoip` Cargo feature; without it, or without a configured
+//   database, every lookup returns `None`
+// - Private, loopback and other non-routable addresses are never looked up
+//
+// Usage Guide:
+// Build with `--features geoip` and set GEOIP_COUNTRY_DB and/or GEOIP_ASN_DB to .mmdb paths.
+// ```
+// let geoip = GeoIpService::from_env();
+// let geo = geoip.lookup(remote_addr.ip()); // Option<GeoInfo>
+// ```
+//
+// Change Log:
+// - 1.0.1: Carrier-grade NAT and IPv4-mapped private addresses are not looked up
+// - 1.0.0: Initial implementation
+
+use std::net::IpAddr;
+#[cfg(not(feature = "geoip"))]
+use tracing::warn;
+
+use crate::models::websocket::GeoInfo;
+
+/// Loaded GeoIP databases; empty when disabled
+pub struct GeoIpService {
+    #[cfg(feature = "geoip")]
+    country: Option<maxminddb::Reader<Vec<u8>>>,
+    #[cfg(feature = "geoip")]
+    asn: Option<maxminddb::Reader<Vec<u8>>>,
+}
+
+impl GeoIpService {
+    /// Opens the databases named by GEOIP_COUNTRY_DB and GEOIP_ASN_DB
+    ///
+    /// A database that cannot be opened is logged and skipped.
+    pub fn from_env() -> Self {
+        let path = |name: &str| std::env::var(name).ok().filter(|path| !path.is_empty());
+        let country_path = path("GEOIP_COUNTRY_DB");
+        let asn_path = path("GEOIP_ASN_DB");
+
+        #[cfg(feature = "geoip")]
+        {
+            let open = |path: Option<String>| {
+                let path = path?;
+                match maxminddb::Reader::open_readfile(&path) {
+                    Ok(reader) => {
+                        tracing::info!("Loaded GeoIP database {}", path);
+                        Some(reader)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to open GeoIP database {}: {}", path, e);
+                        None
+                    }
+                }
+            };
+            Self {
+                country: open(country_path),
+                asn: open(asn_path),
+            }
+        }
+
+        #[cfg(not(feature = "geoip"))]
+        {
+            if country_path.is_some() || asn_path.is_some() {
+                warn!("GeoIP databases are configured but the `geoip` feature is not compiled in; lookups are disabled");
+            }
+            Self {}
+        }
+    }
+
+    /// Country and ASN for a public address, when a database knows it
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoInfo> {
+        if !is_public(ip) {
+            return None;
+        }
+        self.lookup_databases(ip)
+    }
+
+    #[cfg(feature = "geoip")]
+    fn lookup_databases(&self, ip: IpAddr) -> Option<GeoInfo> {
+        use maxminddb::geoip2;
+
+        let mut info = GeoInfo::default();
+        if let Some(country) = self.country.as_ref().and_then(|db| db.lookup::<geoip2::Country>(ip).ok()) {
+            if let Some(country) = country.country {
+                info.country_code = country.iso_code.map(str::to_string);
+                info.country = country.names.and_then(|names| names.get("en").map(|name| name.to_string()));
+            }
+        }
+        if let Some(asn) = self.asn.as_ref().and_then(|db| db.lookup::<geoip2::Asn>(ip).ok()) {
+            info.asn = asn.autonomous_system_number;
+            info.as_organization = asn.autonomous_system_organization.map(str::to_string);
+        }
+
+        (info != GeoInfo::default()).then_some(info)
+    }
+
+    #[cfg(not(feature = "geoip"))]
+    fn lookup_databases(&self, _ip: IpAddr) -> Option<GeoInfo> {
+        None
+    }
+}
+
+impl std::fmt::Debug for GeoIpService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("GeoIpService");
+        #[cfg(feature = "geoip")]
+        debug
+            .field("country_db", &self.country.is_some())
+            .field("asn_db", &self.asn.is_some());
+        debug.finish()
+    }
+}
+
+/// Whether an address is globally routable and worth looking up
+///
+/// Private, loopback, link-local, unspecified and carrier-grade NAT (100.64.0.0/10) IPv4
+/// addresses are not; nor are IPv6 loopback, unspecified, unique local (fc00::/7) and
+/// link-local (fe80::/10) addresses. IPv4-mapped IPv6 addresses are judged as IPv4.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            let shared = first == 100 && (second & 0xc0) == 64;
+            !(ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified() || shared)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(mapped));
+            }
+            let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+            let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_globally_routable_addresses_are_public() {
+        for private in [
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "127.0.0.1",
+            "169.254.10.1",
+            "100.64.0.1",
+            "100.127.255.254",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd12:3456::1",
+            "fc00::1",
+            "fe80::1",
+            "::ffff:192.168.1.1",
+        ] {
+            assert!(!is_public(private.parse().unwrap()), "{} should not be public", private);
+        }
+        for public in ["8.8.8.8", "100.63.255.255", "100.128.0.1", "2001:4860:4860::8888", "::ffff:1.1.1.1"] {
+            assert!(is_public(public.parse().unwrap()), "{} should be public", public);
+        }
+    }
+}
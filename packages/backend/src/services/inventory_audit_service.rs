@@ -0,0 +1,264 @@
+// File Path: src/services/inventory_audit_service.rs
+// Version: 1.1.1
+// Description: Append-only audit trail of inventory edits, so changes to a shared inventory
+// can be traced back to who made them and when.
+//
+// Key Features:
+// - One JSON object per line: timestamp, request id, principal, file and structural diff
+// - Appends are serialized, so concurrent writes never interleave lines
+// - Reads return the newest entries first; malformed lines are skipped with a warning
+// - Reads scan the log backwards from its end and stop once they have enough entries
+// - The log is rotated to `<log>.1` once it would grow past INVENTORY_AUDIT_MAX_BYTES
+//   (default 10MB); only the current and the one rotated file are kept
+//
+// Usage Guide:
+// The log is written to INVENTORY_AUDIT_LOG (default `logs/inventory_audit.jsonl`).
+// ```
+// let audit = InventoryAuditService::new(inventory_audit_path_from_env())
+//     .with_max_bytes(inventory_audit_max_bytes_from_env());
+// audit.record(&entry).await?;
+// let recent = audit.recent(50).await?;
+// ```
+//
+// Change Log:
+// - 1.1.1: Rotation is shared with the metrics snapshot file (log_rotation)
+// - 1.1.0: Bounded backwards reads and size-based rotation
+// - 1.0.0: Initial implementation
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{ErrorKind, SeekFrom},
+    path::{Path, PathBuf},
+};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    sync::Mutex,
+};
+use tracing::warn;
+
+use super::{log_rotation, yaml_service::DiffEntry};
+use crate::config::env_or;
+
+/// Audit log location when INVENTORY_AUDIT_LOG is unset
+const DEFAULT_AUDIT_LOG: &str = "logs/inventory_audit.jsonl";
+
+/// Log size that triggers rotation when INVENTORY_AUDIT_MAX_BYTES is unset
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Bytes read per step when scanning the log backwards
+const READ_CHUNK_BYTES: u64 = 64 * 1024;
+
+/// Reads the rotation size from INVENTORY_AUDIT_MAX_BYTES
+pub fn inventory_audit_max_bytes_from_env() -> u64 {
+    env_or("INVENTORY_AUDIT_MAX_BYTES", DEFAULT_MAX_BYTES).max(1)
+}
+
+/// Reads the audit log location from INVENTORY_AUDIT_LOG
+pub fn inventory_audit_path_from_env() -> PathBuf {
+    env_or("INVENTORY_AUDIT_LOG", PathBuf::from(DEFAULT_AUDIT_LOG))
+}
+
+// =============================================================================
+// SECTION 1: TYPE DEFINITIONS
+// =============================================================================
+
+/// One inventory edit as recorded in the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryAuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Caller's X-Request-Id, or one generated for the request
+    pub request_id: String,
+    /// Authenticated principal; `None` for anonymous requests
+    pub principal: Option<String>,
+    /// Inventory file relative to the data directory
+    pub file: String,
+    pub diff: Vec<DiffEntry>,
+}
+
+// =============================================================================
+// SECTION 2: SERVICE IMPLEMENTATION
+// =============================================================================
+
+/// Appends and reads the inventory audit log
+#[derive(Debug)]
+pub struct InventoryAuditService {
+    path: PathBuf,
+    /// Size past which the log is rotated before the next append
+    max_bytes: u64,
+    /// Held while appending so lines from concurrent edits stay whole
+    write_lock: Mutex<()>,
+}
+
+impl InventoryAuditService {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, max_bytes: DEFAULT_MAX_BYTES, write_lock: Mutex::new(()) }
+    }
+
+    /// Sets the log size that triggers rotation
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes.max(1);
+        self
+    }
+
+    /// Appends one entry to the log, creating the file and its directory if needed
+    ///
+    /// When the entry would take the log past `max_bytes`, the log is first moved to
+    /// `<log>.1`, replacing any earlier rotated file.
+    pub async fn record(&self, entry: &InventoryAuditEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).await?;
+        }
+        let size = match fs::metadata(&self.path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        if size > 0 && size + line.len() as u64 > self.max_bytes {
+            log_rotation::rotate(&self.path, 1).await?;
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+
+    /// Up to `limit` entries, newest first; an absent log has no entries
+    ///
+    /// Only as much of the log as is needed is read, continuing into the rotated
+    /// file when the current one has fewer than `limit` entries.
+    pub async fn recent(&self, limit: usize) -> std::io::Result<Vec<InventoryAuditEntry>> {
+        let mut entries = Vec::new();
+        for path in [self.path.clone(), log_rotation::rotated_path(&self.path, 1)] {
+            if entries.len() >= limit {
+                break;
+            }
+            read_newest(&path, limit, &mut entries).await?;
+        }
+        Ok(entries)
+    }
+}
+
+/// Adds entries from the end of one log file until `entries` holds `limit`
+async fn read_newest(path: &Path, limit: usize, entries: &mut Vec<InventoryAuditEntry>) -> std::io::Result<()> {
+    let mut file = match fs::File::open(path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let mut position = file.metadata().await?.len();
+    // Start of the last chunk read, which may be the tail of a line in the chunk before it
+    let mut partial: Vec<u8> = Vec::new();
+
+    while position > 0 && entries.len() < limit {
+        let size = position.min(READ_CHUNK_BYTES);
+        position -= size;
+        let mut chunk = vec![0; size as usize];
+        file.seek(SeekFrom::Start(position)).await?;
+        file.read_exact(&mut chunk).await?;
+        chunk.extend_from_slice(&partial);
+
+        let mut lines: Vec<&[u8]> = chunk.split(|byte| *byte == b'\n').collect();
+        partial = if position > 0 { lines.remove(0).to_vec() } else { Vec::new() };
+
+        for line in lines.into_iter().rev() {
+            if entries.len() >= limit {
+                break;
+            }
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            match serde_json::from_slice(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping malformed line in {}: {}", path.display(), e),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn entries_are_read_back_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = InventoryAuditService::new(dir.path().join("logs/audit.jsonl"));
+        assert!(audit.recent(10).await.unwrap().is_empty());
+
+        for request_id in ["first", "second"] {
+            audit
+                .record(&InventoryAuditEntry {
+                    timestamp: Utc::now(),
+                    request_id: request_id.to_string(),
+                    principal: Some("ops".to_string()),
+                    file: "inventories/inventory.yaml".to_string(),
+                    diff: Vec::new(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let recent = audit.recent(1).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].request_id, "second");
+        assert_eq!(audit.recent(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn full_logs_rotate_and_reads_span_both_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = InventoryAuditService::new(dir.path().join("audit.jsonl")).with_max_bytes(600);
+
+        // Each entry is about 135 bytes, so a log holds four before it rotates
+        for n in 0..10 {
+            audit
+                .record(&InventoryAuditEntry {
+                    timestamp: Utc::now(),
+                    request_id: format!("request-{}", n),
+                    principal: None,
+                    file: "inventories/inventory.yaml".to_string(),
+                    diff: Vec::new(),
+                })
+                .await
+                .unwrap();
+        }
+        assert!(std::fs::metadata(dir.path().join("audit.jsonl")).unwrap().len() <= 600);
+        assert!(dir.path().join("audit.jsonl.1").exists());
+        assert!(!dir.path().join("audit.jsonl.2").exists());
+
+        let recent = audit.recent(5).await.unwrap();
+        let ids: Vec<&str> = recent.iter().map(|entry| entry.request_id.as_str()).collect();
+        assert_eq!(ids, ["request-9", "request-8", "request-7", "request-6", "request-5"]);
+    }
+
+    #[tokio::test]
+    async fn backwards_reads_join_lines_split_across_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let entry = |n: usize| InventoryAuditEntry {
+            timestamp: Utc::now(),
+            request_id: format!("{}-{}", n, "x".repeat(40_000)),
+            principal: None,
+            file: "inventories/inventory.yaml".to_string(),
+            diff: Vec::new(),
+        };
+        let lines: Vec<String> = (0..4).map(|n| serde_json::to_string(&entry(n)).unwrap()).collect();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+        let mut entries = Vec::new();
+        read_newest(&path, 10, &mut entries).await.unwrap();
+        let ids: Vec<&str> = entries.iter().map(|entry| &entry.request_id[..1]).collect();
+        assert_eq!(ids, ["3", "2", "1", "0"]);
+    }
+}
@@ -0,0 +1,89 @@
+// File Path: src/services/inventory_diff.rs
+// Version: 1.0.0
+// Description: Device-level diff between two versions of an inventory, used to validate
+// only the devices a write adds or changes.
+//
+// Key Features:
+// - Devices are matched by `host_name` within each location and category
+// - Any other change (top-level keys, locations, categories) means no partial diff
+// - Depends only on serde_json, so benches/inventory_validation.rs can include it directly
+//
+// Change Log:
+// - 1.0.0: Moved out of yaml_service
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+fn same_keys(a: &Map<String, Value>, b: &Map<String, Value>) -> bool {
+    a.len() == b.len() && a.keys().all(|key| b.contains_key(key))
+}
+
+/// Builds a partial inventory containing only devices that were added or changed
+/// between `previous` and `updated`, keyed by `host_name`.
+///
+/// Returns `None` when anything other than device entries changed (top-level keys,
+/// locations or categories), or when a device has no `host_name`, so the caller
+/// falls back to full validation.
+pub fn changed_devices(previous: &Value, updated: &Value) -> Option<(Value, usize)> {
+    let previous = previous.as_object()?;
+    let updated = updated.as_object()?;
+
+    if !same_keys(previous, updated) {
+        return None;
+    }
+    if updated
+        .iter()
+        .any(|(key, value)| key != "locations" && previous.get(key) != Some(value))
+    {
+        return None;
+    }
+
+    let previous_locations = previous.get("locations")?.as_object()?;
+    let updated_locations = updated.get("locations")?.as_object()?;
+    if !same_keys(previous_locations, updated_locations) {
+        return None;
+    }
+
+    let mut partial_locations = Map::new();
+    let mut changed_count = 0;
+
+    for (location, categories) in updated_locations {
+        let previous_categories = previous_locations.get(location)?.as_object()?;
+        let categories = categories.as_object()?;
+        if !same_keys(previous_categories, categories) {
+            return None;
+        }
+
+        let mut partial_categories = Map::new();
+        for (category, devices) in categories {
+            let previous_devices = previous_categories.get(category)?.as_array()?;
+            let devices = devices.as_array()?;
+
+            let mut known: HashMap<&str, &Value> = HashMap::new();
+            for device in previous_devices {
+                known.insert(device.get("host_name")?.as_str()?, device);
+            }
+
+            let mut changed = Vec::new();
+            for device in devices {
+                let host_name = device.get("host_name")?.as_str()?;
+                if known.get(host_name).copied() != Some(device) {
+                    changed.push(device.clone());
+                }
+            }
+
+            changed_count += changed.len();
+            partial_categories.insert(category.clone(), Value::Array(changed));
+        }
+        partial_locations.insert(location.clone(), Value::Object(partial_categories));
+    }
+
+    let mut partial: Map<String, Value> = updated
+        .iter()
+        .filter(|(key, _)| key.as_str() != "locations")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    partial.insert("locations".to_string(), Value::Object(partial_locations));
+
+    Some((Value::Object(partial), changed_count))
+}
@@ -0,0 +1,283 @@
+// File Path: src/services/job_service.rs
+// Version: 1.2.0
+// Description: Registry of in-flight device jobs (backup, restore, ...) started by the backend.
+// Tracks each job's background task so jobs can be listed and cancelled.
+//
+// Key Features:
+// - Registers jobs before their background task is spawned
+// - Attaches the task's abort handle once spawned
+// - Removes jobs when their task finishes
+// - Cancels all in-flight jobs, reporting jobs that could not be cancelled
+// - Cancels a device's in-flight job of a given type, for operators who know the device but not the job id
+// - Counts finished jobs per job type for the activity summary
+//
+// Usage Guide:
+// ```
+// job_service.register(&job_id, &device, "backup").await;
+// let handle = tokio::spawn(async move { ...; job_service.complete(&job_id, succeeded).await; });
+// job_service.attach_handle(&job_id, handle.abort_handle()).await;
+// ```
+//
+// Change Log:
+// - 1.2.0: Added cancel_device_job()
+// - 1.1.0: Added per-type outcome counters and summary()
+// - 1.0.0: Initial implementation
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tokio::{sync::RwLock, task::AbortHandle};
+use tracing::{info, warn};
+
+use crate::models::{ApiError, ApiResult, CancelAllResult, CancelFailure};
+
+// =============================================================================
+// SECTION 1: TYPE DEFINITIONS
+// =============================================================================
+
+/// An in-flight job tracked by the registry
+#[derive(Debug)]
+struct TrackedJob {
+    info: JobInfo,
+    /// Abort handle of the job's background task, set once spawned
+    handle: Option<AbortHandle>,
+}
+
+/// Public description of an in-flight job
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub job_id: String,
+    pub device: String,
+    pub job_type: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Activity counts for one job type
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobTypeSummary {
+    pub in_progress: usize,
+    pub completed: u64,
+    pub failed: u64,
+    pub cancelled: u64,
+    /// Age in seconds of the oldest in-flight job, if any
+    pub oldest_in_flight_secs: Option<i64>,
+}
+
+/// Finished-job counters for one job type
+#[derive(Debug, Clone, Copy, Default)]
+struct OutcomeCounts {
+    completed: u64,
+    failed: u64,
+    cancelled: u64,
+}
+
+// =============================================================================
+// SECTION 2: SERVICE IMPLEMENTATION
+// =============================================================================
+
+/// Registry of in-flight jobs
+#[derive(Debug, Default)]
+pub struct JobService {
+    jobs: RwLock<HashMap<String, TrackedJob>>,
+    /// Finished jobs per job type since startup
+    outcomes: RwLock<HashMap<String, OutcomeCounts>>,
+}
+
+impl JobService {
+    /// Creates an empty job registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a job before its background task is spawned
+    pub async fn register(&self, job_id: &str, device: &str, job_type: &str) {
+        let info = JobInfo {
+            job_id: job_id.to_string(),
+            device: device.to_string(),
+            job_type: job_type.to_string(),
+            started_at: Utc::now(),
+        };
+        self.jobs.write().await.insert(job_id.to_string(), TrackedJob { info, handle: None });
+    }
+
+    /// Attaches the abort handle of a job's task (ignored if the job already completed)
+    pub async fn attach_handle(&self, job_id: &str, handle: AbortHandle) {
+        if let Some(job) = self.jobs.write().await.get_mut(job_id) {
+            job.handle = Some(handle);
+        }
+    }
+
+    /// Removes a finished job from the registry and counts its outcome
+    pub async fn complete(&self, job_id: &str, succeeded: bool) {
+        let removed = self.jobs.write().await.remove(job_id);
+        if let Some(job) = removed {
+            self.record_outcome(&job.info.job_type, succeeded).await;
+        }
+    }
+
+    /// Counts the outcome of a job that was not tracked in flight (e.g. a synchronous restore)
+    pub async fn record_outcome(&self, job_type: &str, succeeded: bool) {
+        let mut outcomes = self.outcomes.write().await;
+        let counts = outcomes.entry(job_type.to_string()).or_default();
+        if succeeded {
+            counts.completed += 1;
+        } else {
+            counts.failed += 1;
+        }
+    }
+
+    /// Per job type, in-flight count and age of the oldest in-flight job plus finished counts
+    pub async fn summary(&self) -> BTreeMap<String, JobTypeSummary> {
+        let now = Utc::now();
+        let mut summary: BTreeMap<String, JobTypeSummary> = BTreeMap::new();
+
+        for (job_type, counts) in self.outcomes.read().await.iter() {
+            let entry = summary.entry(job_type.clone()).or_default();
+            entry.completed = counts.completed;
+            entry.failed = counts.failed;
+            entry.cancelled = counts.cancelled;
+        }
+
+        for job in self.jobs.read().await.values() {
+            let entry = summary.entry(job.info.job_type.clone()).or_default();
+            entry.in_progress += 1;
+            let age = now.signed_duration_since(job.info.started_at).num_seconds();
+            entry.oldest_in_flight_secs = Some(entry.oldest_in_flight_secs.map_or(age, |oldest| oldest.max(age)));
+        }
+
+        summary
+    }
+
+    /// Lists all in-flight jobs
+    pub async fn list_jobs(&self) -> Vec<JobInfo> {
+        self.jobs.read().await.values().map(|job| job.info.clone()).collect()
+    }
+
+    /// Cancels the newest in-flight job of `job_type` for `device` (case-insensitive)
+    ///
+    /// # Returns
+    /// The cancelled job; 404 when none is in flight, 409 when its task has not started yet
+    pub async fn cancel_device_job(&self, device: &str, job_type: &str) -> ApiResult<JobInfo> {
+        let mut jobs = self.jobs.write().await;
+        let not_found = || ApiError::NotFound(format!("No {} job in flight for {}", job_type, device));
+
+        let job_id = jobs
+            .values()
+            .filter(|job| job.info.job_type == job_type && job.info.device.eq_ignore_ascii_case(device))
+            .max_by_key(|job| job.info.started_at)
+            .map(|job| job.info.job_id.clone())
+            .ok_or_else(not_found)?;
+
+        match jobs.get(&job_id).and_then(|job| job.handle.as_ref()).map(|handle| handle.is_finished()) {
+            None => Err(ApiError::Conflict(format!("Job {} has not started yet; retry shortly", job_id))),
+            Some(true) => {
+                jobs.remove(&job_id);
+                Err(not_found())
+            }
+            Some(false) => {
+                let job = jobs.remove(&job_id).ok_or_else(not_found)?;
+                if let Some(handle) = &job.handle {
+                    handle.abort();
+                }
+                info!("Job cancelled: {} ({} for {})", job_id, job_type, job.info.device);
+                self.outcomes.write().await.entry(job.info.job_type.clone()).or_default().cancelled += 1;
+                Ok(job.info)
+            }
+        }
+    }
+
+    /// Cancels every in-flight job
+    ///
+    /// # Returns
+    /// Cancelled jobs, plus the ids of jobs that could not be cancelled
+    /// (task not yet attached or already finished)
+    pub async fn cancel_all(&self) -> (Vec<JobInfo>, CancelAllResult) {
+        let mut jobs = self.jobs.write().await;
+        let mut cancelled_jobs = Vec::new();
+        let mut result = CancelAllResult::default();
+
+        let job_ids: Vec<String> = jobs.keys().cloned().collect();
+        for job_id in job_ids {
+            let cancellable = jobs
+                .get(&job_id)
+                .and_then(|job| job.handle.as_ref())
+                .map(|handle| !handle.is_finished());
+
+            match cancellable {
+                Some(true) => {
+                    if let Some(job) = jobs.remove(&job_id) {
+                        if let Some(handle) = &job.handle {
+                            handle.abort();
+                        }
+                        info!("Job cancelled: {}", job_id);
+                        self.outcomes.write().await.entry(job.info.job_type.clone()).or_default().cancelled += 1;
+                        result.cancelled.push(job_id);
+                        cancelled_jobs.push(job.info);
+                    }
+                }
+                Some(false) => {
+                    jobs.remove(&job_id);
+                    warn!("Job already finished, not cancelled: {}", job_id);
+                    result.failed.push(CancelFailure {
+                        id: job_id,
+                        error: "Job already finished".to_string(),
+                    });
+                }
+                None => {
+                    warn!("Job task not yet started, not cancelled: {}", job_id);
+                    result.failed.push(CancelFailure {
+                        id: job_id,
+                        error: "Job task not yet started".to_string(),
+                    });
+                }
+            }
+        }
+
+        (cancelled_jobs, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cancels_a_device_job_by_device_name() {
+        let jobs = JobService::new();
+        jobs.register("job-1", "R1", "backup").await;
+        let task = tokio::spawn(std::future::pending::<()>());
+        jobs.attach_handle("job-1", task.abort_handle()).await;
+
+        assert!(matches!(jobs.cancel_device_job("r1", "restore").await, Err(ApiError::NotFound(_))));
+
+        let cancelled = jobs.cancel_device_job("r1", "backup").await.unwrap();
+        assert_eq!(cancelled.job_id, "job-1");
+        assert!(task.await.unwrap_err().is_cancelled());
+        assert!(jobs.list_jobs().await.is_empty());
+        assert_eq!(jobs.summary().await["backup"].cancelled, 1);
+    }
+
+    #[tokio::test]
+    async fn cancel_all_reports_jobs_it_could_not_cancel() {
+        let jobs = JobService::new();
+        for (job_id, device) in [("running", "r1"), ("finished", "r2"), ("unstarted", "r3")] {
+            jobs.register(job_id, device, "backup").await;
+        }
+        let running = tokio::spawn(std::future::pending::<()>());
+        jobs.attach_handle("running", running.abort_handle()).await;
+        let finished = tokio::spawn(async {});
+        jobs.attach_handle("finished", finished.abort_handle()).await;
+        finished.await.unwrap();
+
+        let (cancelled, result) = jobs.cancel_all().await;
+        assert_eq!(cancelled.iter().map(|job| job.job_id.as_str()).collect::<Vec<_>>(), ["running"]);
+        assert_eq!(result.cancelled, ["running"]);
+        let mut failed: Vec<_> = result.failed.iter().map(|f| (f.id.as_str(), f.error.as_str())).collect();
+        failed.sort();
+        assert_eq!(failed, [("finished", "Job already finished"), ("unstarted", "Job task not yet started")]);
+        assert!(running.await.unwrap_err().is_cancelled());
+
+        // Only the job whose task has not started yet is still tracked
+        assert_eq!(jobs.list_jobs().await.len(), 1);
+    }
+}
@@ -0,0 +1,45 @@
+// File Path: src/services/log_rotation.rs
+// Version: 1.0.0
+// Description: Numbered-suffix rotation for the JSON-lines files the backend appends to
+// (metrics snapshots, inventory audit log).
+//
+// Key Features:
+// - `log` → `log.1` → `log.2` ... keeping at most `max_files` rotated files
+// - With `max_files == 0` the current file is removed instead of kept
+// - Callers decide when to rotate; this only moves the files
+//
+// Usage Guide:
+// ```
+// if size >= max_bytes {
+//     log_rotation::rotate(&path, max_files).await?;
+// }
+// ```
+//
+// Change Log:
+// - 1.0.0: Moved out of metrics_snapshot_service
+
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// `path` with a `.index` suffix, the name of its `index`-th rotated file
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// Shifts `path.N` to `path.N+1` (dropping the oldest) and moves `path` to `path.1`
+pub async fn rotate(path: &Path, max_files: usize) -> std::io::Result<()> {
+    if max_files == 0 {
+        return fs::remove_file(path).await;
+    }
+
+    let _ = fs::remove_file(rotated_path(path, max_files)).await;
+    for index in (1..max_files).rev() {
+        let from = rotated_path(path, index);
+        if fs::metadata(&from).await.is_ok() {
+            fs::rename(&from, rotated_path(path, index + 1)).await?;
+        }
+    }
+    fs::rename(path, rotated_path(path, 1)).await
+}
@@ -0,0 +1,200 @@
+// File Path: src/services/metrics_snapshot_service.rs
+// Version: 1.1.1
+// Description: Periodically appends WebSocket and Python runner metrics to a JSON-lines file
+// so post-mortems have a metrics trail without a time-series database.
+//
+// Key Features:
+// - One JSON object per line: timestamp, WebSocket service metrics, execution counts per status
+// - Size-based rotation (`metrics.jsonl` → `metrics.jsonl.1` → ... up to `max_files`)
+// - A final snapshot is written on graceful shutdown
+//
+// Usage Guide:
+// Enabled by setting METRICS_SNAPSHOT_PATH. Optional tuning:
+// METRICS_SNAPSHOT_INTERVAL_SECS (default 60), METRICS_SNAPSHOT_MAX_BYTES (default 10MB),
+// METRICS_SNAPSHOT_MAX_FILES (rotated files kept, default 5).
+// ```
+// let snapshots = Arc::new(MetricsSnapshotService::new(config, websocket_service, python_runner_service));
+// snapshots.clone().spawn();
+// // ... on shutdown
+// snapshots.write_snapshot().await?;
+// ```
+//
+// Change Log:
+// - 1.1.1: Rotation is shared with the inventory audit log (log_rotation)
+// - 1.1.0: The snapshot loop reports a heartbeat for background task monitoring
+// - 1.0.0: Initial implementation
+
+use chrono::Utc;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::{fs, io::AsyncWriteExt};
+use tracing::{info, warn};
+
+use super::{log_rotation, PythonRunnerService, TaskHealthService, WebSocketService};
+use crate::config::env_or;
+
+// =============================================================================
+// SECTION 1: CONFIGURATION
+// =============================================================================
+
+/// Where and how often metrics snapshots are written
+#[derive(Debug, Clone)]
+pub struct MetricsSnapshotConfig {
+    /// JSON-lines file snapshots are appended to
+    pub path: PathBuf,
+    /// Time between snapshots
+    pub interval: Duration,
+    /// Size at which the file is rotated
+    pub max_bytes: u64,
+    /// Rotated files kept besides the active one
+    pub max_files: usize,
+}
+
+impl MetricsSnapshotConfig {
+    /// Reads the configuration from the environment; `None` when METRICS_SNAPSHOT_PATH is unset
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var("METRICS_SNAPSHOT_PATH").ok().filter(|path| !path.is_empty())?;
+        Some(Self {
+            path: PathBuf::from(path),
+            interval: Duration::from_secs(env_or("METRICS_SNAPSHOT_INTERVAL_SECS", 60).max(1)),
+            max_bytes: env_or("METRICS_SNAPSHOT_MAX_BYTES", 10 * 1024 * 1024),
+            max_files: env_or("METRICS_SNAPSHOT_MAX_FILES", 5),
+        })
+    }
+}
+
+// =============================================================================
+// SECTION 2: SERVICE IMPLEMENTATION
+// =============================================================================
+
+/// Writes metrics snapshots to disk
+pub struct MetricsSnapshotService {
+    config: MetricsSnapshotConfig,
+    websocket_service: Arc<WebSocketService>,
+    python_runner_service: Arc<PythonRunnerService>,
+}
+
+impl MetricsSnapshotService {
+    pub fn new(
+        config: MetricsSnapshotConfig,
+        websocket_service: Arc<WebSocketService>,
+        python_runner_service: Arc<PythonRunnerService>,
+    ) -> Self {
+        Self {
+            config,
+            websocket_service,
+            python_runner_service,
+        }
+    }
+
+    /// Starts the periodic snapshot task
+    pub fn spawn(self: Arc<Self>, task_health: &TaskHealthService) {
+        let heartbeat = task_health.register("metrics_snapshots", self.config.interval);
+        tokio::spawn(async move {
+            info!(
+                "Writing metrics snapshots to {} every {:?}",
+                self.config.path.display(),
+                self.config.interval
+            );
+
+            let mut interval = tokio::time::interval(self.config.interval);
+            // The first tick completes immediately; skip it so the first snapshot has data
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                heartbeat.beat();
+                if let Err(e) = self.write_snapshot().await {
+                    warn!("Failed to write metrics snapshot: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Appends one snapshot line, rotating the file first when it is too large
+    pub async fn write_snapshot(&self) -> std::io::Result<()> {
+        let snapshot = serde_json::json!({
+            "timestamp": Utc::now(),
+            "websocket": self.websocket_service.get_metrics().await,
+            "python": {
+                "executions": self.python_runner_service.execution_counts().await,
+            },
+        });
+        let mut line = serde_json::to_string(&snapshot).map_err(std::io::Error::other)?;
+        line.push('\n');
+
+        self.rotate_if_needed().await?;
+
+        if let Some(parent) = self.config.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).await?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+
+    /// Rotates the file once it reaches `max_bytes`, keeping `max_files` rotated files
+    async fn rotate_if_needed(&self) -> std::io::Result<()> {
+        let size = match fs::metadata(&self.config.path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return Ok(()),
+        };
+        if size < self.config.max_bytes {
+            return Ok(());
+        }
+
+        log_rotation::rotate(&self.config.path, self.config.max_files).await?;
+
+        info!("Rotated metrics snapshot file {}", self.config.path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestApp;
+
+    async fn service(path: PathBuf, max_files: usize) -> MetricsSnapshotService {
+        let app = TestApp::new().await;
+        let config = MetricsSnapshotConfig { path, interval: Duration::from_secs(60), max_bytes: 4, max_files };
+        MetricsSnapshotService::new(config, app.state.websocket_service.clone(), app.state.python_runner_service.clone())
+    }
+
+    #[tokio::test]
+    async fn rotation_shifts_files_and_drops_the_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.jsonl");
+        let read = |suffix: &str| std::fs::read_to_string(dir.path().join(format!("metrics.jsonl{}", suffix))).ok();
+        let snapshots = service(path.clone(), 2).await;
+
+        // Below max_bytes nothing moves
+        std::fs::write(&path, "new").unwrap();
+        snapshots.rotate_if_needed().await.unwrap();
+        assert_eq!(read("").as_deref(), Some("new"));
+
+        std::fs::write(&path, "newest").unwrap();
+        std::fs::write(dir.path().join("metrics.jsonl.1"), "older").unwrap();
+        std::fs::write(dir.path().join("metrics.jsonl.2"), "oldest").unwrap();
+        snapshots.rotate_if_needed().await.unwrap();
+        assert_eq!(read(""), None);
+        assert_eq!(read(".1").as_deref(), Some("newest"));
+        assert_eq!(read(".2").as_deref(), Some("older"));
+        assert_eq!(read(".3"), None);
+    }
+
+    #[tokio::test]
+    async fn rotation_without_kept_files_removes_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.jsonl");
+        let snapshots = service(path.clone(), 0).await;
+
+        std::fs::write(&path, "full log").unwrap();
+        snapshots.rotate_if_needed().await.unwrap();
+        assert!(!path.exists());
+        assert!(!dir.path().join("metrics.jsonl.1").exists());
+    }
+}
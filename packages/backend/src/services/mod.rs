@@ -1,5 +1,5 @@
 // File Path: src/services/mod.rs
-// Version: 1.2.1
+// Version: 1.16.0
 // Description: Services module that organizes all application services.
 // Updated to include Python runner service while maintaining backward compatibility.
 //
@@ -13,6 +13,20 @@
 // New Python runner service is available for script execution.
 //
 // Change Log:
+// - 1.16.0: Added shared log file rotation
+// - 1.15.0: Added inventory device diff
+// - 1.14.0: Added inventory audit log
+// - 1.13.0: Added device credentials resolver
+// - 1.12.0: Added priority execution queue
+// - 1.11.0: Added background task health service
+// - 1.10.0: Added optional GeoIP service
+// - 1.9.0: Added device list service with circuit breaker
+// - 1.8.0: Added metrics snapshot service
+// - 1.7.0: Added backup pool
+// - 1.6.0: Added route metrics service
+// - 1.5.0: Added job service
+// - 1.4.0: Added device lock service
+// - 1.3.0: Added webhook service
 // - 1.2.1: Removed initialize_services function to avoid conflicts
 // - 1.2.0: Added Python runner service exports
 // - 1.0.0: Initial version with YAML and WebSocket services
@@ -40,3 +54,110 @@ pub mod python_runner;
 
 /// Export Python runner service and its types for easy access
 pub use python_runner::{PythonRunnerService, ExecutionStatus};
+
+/// Priority queue bounding concurrent Python executions
+pub mod execution_queue;
+pub use execution_queue::ExecutionPriority;
+
+// =============================================================================
+// SECTION 3: WEBHOOK SERVICE
+// =============================================================================
+// HTTP webhook notifications for integrations that don't hold a WebSocket
+
+/// Webhook delivery service for job events and connection-count thresholds
+pub mod webhook_service;
+pub use webhook_service::WebhookService;
+
+// =============================================================================
+// SECTION 4: DEVICE LOCK SERVICE
+// =============================================================================
+// Serializes conflicting operations on the same device
+
+/// Per-device lock registry keyed by hostname
+pub mod device_lock_service;
+pub use device_lock_service::DeviceLockService;
+
+// =============================================================================
+// SECTION 5: JOB SERVICE
+// =============================================================================
+// Registry of in-flight device jobs
+
+/// In-flight job registry used for listing and cancelling jobs
+pub mod job_service;
+pub use job_service::JobService;
+
+// =============================================================================
+// SECTION 6: ROUTE METRICS SERVICE
+// =============================================================================
+// Per-route request metrics for SLO dashboards
+
+/// Per-route request counters and latency histograms
+pub mod route_metrics_service;
+pub use route_metrics_service::RouteMetricsService;
+
+// =============================================================================
+// SECTION 7: BACKUP POOL
+// =============================================================================
+// Bounds concurrent backups sent to the Python API
+
+/// Semaphore-bounded backup slots with a FIFO wait queue
+pub mod backup_pool;
+pub use backup_pool::BackupPool;
+
+// =============================================================================
+// SECTION 8: METRICS SNAPSHOT SERVICE
+// =============================================================================
+// Periodic metrics trail on disk
+
+/// Appends metrics snapshots to a rotating JSON-lines file
+pub mod metrics_snapshot_service;
+
+// =============================================================================
+// SECTION 9: DEVICE LIST SERVICE
+// =============================================================================
+// Python API device list behind a circuit breaker
+
+/// Device list client that serves the last known list during Python outages
+pub mod device_list_service;
+pub use device_list_service::DeviceListService;
+
+// =============================================================================
+// SECTION 10: GEOIP SERVICE
+// =============================================================================
+// Optional country/ASN context for client addresses (`geoip` feature)
+
+/// MaxMind database lookups; a no-op when the feature or databases are missing
+pub mod geoip_service;
+
+// =============================================================================
+// SECTION 11: TASK HEALTH SERVICE
+// =============================================================================
+// Heartbeats of periodic background tasks
+
+/// Detects background loops that stopped or stalled
+pub mod task_health_service;
+pub use task_health_service::TaskHealthService;
+
+// =============================================================================
+// SECTION 12: CREDENTIALS SERVICE
+// =============================================================================
+// Central resolution and masking of device login credentials
+
+/// Resolves device credentials from request overrides, the inventory and the environment
+pub mod credentials_service;
+pub use credentials_service::CredentialsService;
+
+// =============================================================================
+// SECTION 13: INVENTORY AUDIT SERVICE
+// =============================================================================
+// Who changed the inventory, when, and what changed
+
+/// Append-only JSON-lines log of inventory edits
+pub mod inventory_audit_service;
+pub use inventory_audit_service::InventoryAuditService;
+
+/// Changed devices between two inventory versions, for incremental validation
+pub mod inventory_diff;
+
+/// Numbered-suffix rotation shared by the metrics snapshot and audit logs
+pub mod log_rotation;
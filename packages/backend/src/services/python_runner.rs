@@ -1,5 +1,5 @@
 // File Path: src/services/python_runner.rs
-// Version: 1.0.3
+// Version: 1.15.4
 // Description: Python script execution service that runs scripts in Docker containers.
 // Integrates with existing WebSocket service for real-time updates.
 //
@@ -14,10 +14,61 @@
 // Example usage:
 // ```
 // let python_runner = PythonRunnerService::new(websocket_service, None).await?;
-// let execution_id = python_runner.execute_script("script.py", vec![], HashMap::new(), None).await?;
+// let execution_id = python_runner
+//     .execute_script("script.py", vec![], HashMap::new(), None, None, ExecutionPriority::Normal)
+//     .await?;
 // ```
+// Containers run as PYTHON_RUNNER_CONTAINER_USER (uid[:gid], default 1000:1000). Requests may pick
+// another user only from PYTHON_RUNNER_ALLOWED_USERS (comma-separated). The python_pipeline mount
+// must be readable, and any output directories writable, by every configured uid/gid.
+// Each execution gets a trace id, passed to the script as XAOS_TRACE_ID. Job events posted to
+// /jobs/broadcast with that `trace_id` are kept on the execution (see get_execution_events()).
+// Finished executions are kept for a retention period per outcome, configurable through
+// EXECUTION_RETENTION_{COMPLETED,FAILED,CANCELLED,TIMEDOUT}_HOURS (defaults 24, 72, 1, 72).
+//
+// Each execution records where it ran (`environment`: container id, image digest, host node)
+// once its container starts.
+//
+// Every new execution is announced to `jobs` subscribers with an `execution_started` job event
+// (script, image, argument count and flag names, queue time). Argument values are never
+// broadcast, since a positional argument may be a password.
+// Output sent to the requesting client is batched: lines are coalesced into one `output` job
+// event per PYTHON_OUTPUT_FLUSH_MS (default 100), flushed early once PYTHON_OUTPUT_BURST_LINES
+// (default 50) lines or PYTHON_OUTPUT_MAX_BATCH_BYTES (default 16KB) are pending, and on completion.
+// Request arguments and environment variables are capped by PYTHON_MAX_ARGS (default 64),
+// PYTHON_MAX_ENV_VARS (default 64) and PYTHON_MAX_INPUT_BYTES (default 64KB, all args, keys and values).
+// At most PYTHON_MAX_CONCURRENT (default 4) executions run at once; the rest wait in a priority
+// queue (low, normal, high, urgent). Waiters gain one level per PYTHON_QUEUE_AGING_SECS (default 30)
+// waited, so bulk runs are delayed by urgent ones but never starved.
+// On graceful shutdown, queued executions are cancelled and running ones get
+// PYTHON_SHUTDOWN_GRACE_SECS (default 30) to finish; the rest are cancelled with a
+// "server shutdown" note and a `cancelled` job event, never marked failed.
 //
 // Change Log:
+// - 1.15.4: Queued (Pending) executions can be cancelled; CancelError::NotRunning is gone
+// - 1.15.3: execute_script rejects users outside the allowlist and sets User on the container spec
+// - 1.15.2: Job event statuses use the lowercase wire names of ExecutionStatus
+// - 1.15.1: execution_started carries argument flag names and count instead of masked values
+// - 1.15.0: Added shutdown(): grace period for running executions, then cancellation as server shutdown
+// - 1.14.0: Executions wait for a slot in a priority queue bounded by PYTHON_MAX_CONCURRENT
+// - 1.13.1: list_executions applies its limit after sorting, so it keeps the most recent executions
+// - 1.13.0: Added configurable caps on execution argument and environment variable count and size
+// - 1.12.0: Output lines for the requesting client are batched by a configurable flush interval
+// - 1.11.0: Broadcast an execution_started job event when an execution is queued
+// - 1.10.0: Executions record their container id, image digest and host node
+// - 1.9.0: cleanup_old_executions applies a separate retention per terminal status
+// - 1.8.0: Executions get a trace id (XAOS_TRACE_ID) and keep the job events that echo it
+// - 1.7.1: ExecutionStatus serializes and parses as the lowercase names the routes document
+// - 1.7.0: Containers run as a configurable non-root user; per-request users must be allowlisted
+// - 1.6.1: Added execution_counts() for metrics snapshots
+// - 1.6.0: cancel_execution returns a typed CancelError (NotFound, NotRunning, AlreadyTerminal)
+// - 1.5.0: Output is captured as raw bytes, lossy-decoded and flagged when not valid UTF-8
+// - 1.4.0: Added container log driver config, validated against known Docker drivers
+// - 1.3.0: Executions remember their WebSocket client; on disconnect they are detached or cancelled
+// - 1.2.0: Sends a Pending→Running job event with queued time to the requesting WebSocket client
+// - 1.1.1: Added cancel_all_executions
+// - 1.1.0: Added named environment presets and kept config on the service
+// - 1.0.4: Added updated-since filter to list_executions
 // - 1.0.3: Removed unused fields to eliminate warnings
 // - 1.0.2: Fixed unused variable warnings and method signatures
 // - 1.0.1: Added proper error handling and logging
@@ -26,11 +77,18 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
+use chrono::Utc;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{Duration, Instant};
 use uuid::Uuid;
 use tracing::{info, warn, debug};
 
+use super::execution_queue::{ExecutionPriority, ExecutionQueue};
 use super::websocket_service::WebSocketService;
+use crate::config::env_or;
+use crate::models::{CancelAllResult, CancelFailure};
+use crate::models::websocket::{ConnectionId, JobEventPayload, WsMessage};
 
 // =============================================================================
 // SECTION 1: TYPE DEFINITIONS
@@ -38,7 +96,11 @@ use super::websocket_service::WebSocketService;
 // Defines data structures for execution tracking and status reporting
 
 /// Execution status enum representing different states of script execution
+///
+/// Serialized as lowercase names ("pending", "running", ..., "timedout"), the same
+/// strings accepted by the `status` filter of the executions list.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum ExecutionStatus {
     /// Script is queued for execution
     Pending,
@@ -54,6 +116,53 @@ pub enum ExecutionStatus {
     TimedOut,
 }
 
+impl ExecutionStatus {
+    /// Every status, in lifecycle order
+    pub const ALL: [ExecutionStatus; 6] = [
+        ExecutionStatus::Pending,
+        ExecutionStatus::Running,
+        ExecutionStatus::Completed,
+        ExecutionStatus::Failed,
+        ExecutionStatus::Cancelled,
+        ExecutionStatus::TimedOut,
+    ];
+
+    /// Wire name of the status, matching its serde representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionStatus::Pending => "pending",
+            ExecutionStatus::Running => "running",
+            ExecutionStatus::Completed => "completed",
+            ExecutionStatus::Failed => "failed",
+            ExecutionStatus::Cancelled => "cancelled",
+            ExecutionStatus::TimedOut => "timedout",
+        }
+    }
+}
+
+impl std::str::FromStr for ExecutionStatus {
+    type Err = String;
+
+    /// Parses a wire name, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowered = s.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == lowered)
+            .ok_or_else(|| format!("Unknown execution status '{}'", s))
+    }
+}
+
+/// Why an execution could not be cancelled
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CancelError {
+    #[error("Execution not found")]
+    NotFound,
+    /// Already completed, failed, cancelled or timed out
+    #[error("Execution already finished with status {0:?}")]
+    AlreadyTerminal(ExecutionStatus),
+}
+
 /// Detailed execution information for tracking and reporting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Execution {
@@ -63,8 +172,15 @@ pub struct Execution {
     pub script_path: String,
     /// Current status of the execution
     pub status: ExecutionStatus,
-    /// Standard output from the script execution
+    /// Standard output from the script execution, lossy-decoded as UTF-8
     pub output: Option<String>,
+    /// Whether the captured output was not valid UTF-8; the raw bytes are
+    /// available from the execution output endpoint
+    #[serde(default)]
+    pub output_binary: bool,
+    /// Raw output bytes, kept only when `output_binary` is set
+    #[serde(skip)]
+    pub raw_output: Option<Vec<u8>>,
     /// Error output if execution failed
     pub error: Option<String>,
     /// Exit code from the script process
@@ -73,6 +189,62 @@ pub struct Execution {
     pub start_time: Option<std::time::SystemTime>,
     /// Timestamp when execution ended
     pub end_time: Option<std::time::SystemTime>,
+    /// WebSocket connection that requested the execution, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub websocket_client_id: Option<String>,
+    /// Whether the requesting client disconnected while the execution kept running;
+    /// output stays buffered on the record
+    #[serde(default)]
+    pub detached: bool,
+    /// User (`uid[:gid]`) the execution container runs as
+    #[serde(default)]
+    pub container_user: String,
+    /// Correlation id passed to the script and echoed in the job events it produces
+    #[serde(default)]
+    pub trace_id: String,
+    /// Job events carrying this execution's trace id, oldest first
+    #[serde(skip)]
+    pub events: Vec<JobEventPayload>,
+    /// Where the execution ran, captured when its container starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<ExecutionEnvironment>,
+    /// Queue priority the execution was submitted with
+    #[serde(default)]
+    pub priority: ExecutionPriority,
+}
+
+/// Where and with what image an execution ran, for audit and reproducibility
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecutionEnvironment {
+    /// Docker container id
+    pub container_id: Option<String>,
+    /// Image reference the container was created from
+    pub image: Option<String>,
+    /// Content digest of that image (`sha256:...`)
+    pub image_digest: Option<String>,
+    /// Node the container ran on
+    pub host: Option<String>,
+}
+
+/// Environment variable carrying the execution's trace id into the script
+pub const TRACE_ID_ENV: &str = "XAOS_TRACE_ID";
+
+/// Job events kept per execution; the oldest are dropped beyond this
+const MAX_TRACE_EVENTS: usize = 500;
+
+/// Flag names shown in the execution_started event; the rest are counted only
+const MAX_SUMMARIZED_ARGS: usize = 20;
+
+/// Names of the flag arguments (`--host`, `--password` from `--password=x`), without values
+///
+/// Positional arguments are left out entirely: the runner cannot tell which of them
+/// are secrets, so no argument value is ever broadcast.
+fn arg_flags(args: &[String]) -> Vec<&str> {
+    args.iter()
+        .filter(|arg| arg.starts_with('-'))
+        .map(|arg| arg.split_once('=').map_or(arg.as_str(), |(name, _)| name))
+        .take(MAX_SUMMARIZED_ARGS)
+        .collect()
 }
 
 // =============================================================================
@@ -89,6 +261,252 @@ pub struct PythonRunnerConfig {
     pub python_pipeline_path: String,
     /// Interval for cleaning up old execution records (in hours)
     pub cleanup_interval_hours: u32,
+    /// Named environment variable sets (e.g. "prod", "lab") that requests can reference
+    pub env_presets: HashMap<String, HashMap<String, String>>,
+    /// Cancel a client's unfinished executions when its WebSocket disconnects,
+    /// instead of detaching them
+    pub cancel_on_disconnect: bool,
+    /// Check captured output for invalid UTF-8 and keep the raw bytes when found
+    pub check_output_encoding: bool,
+    /// Docker log driver for execution containers; `None` uses the daemon default.
+    /// Output is still captured on the `Execution` record regardless of driver.
+    pub log_config: Option<ContainerLogConfig>,
+    /// User (`uid[:gid]`) execution containers run as unless a request overrides it
+    pub container_user: String,
+    /// Image execution containers are created from
+    pub image: String,
+    /// Batching of output lines streamed to the requesting client
+    pub output_flush: OutputFlushConfig,
+    /// Caps on the arguments and environment variables of a request
+    pub input_limits: ExecutionInputLimits,
+    /// Extra users (`uid[:gid]`) a request may ask to run as
+    pub allowed_container_users: Vec<String>,
+    /// How long finished executions are kept, by outcome
+    pub retention: ExecutionRetention,
+    /// Executions running at once; further ones wait in the priority queue
+    pub max_concurrent: usize,
+    /// Waiting time that raises a queued execution's priority by one level
+    pub queue_aging: Duration,
+    /// How long running executions may keep running after a shutdown signal
+    pub shutdown_grace: Duration,
+}
+
+/// Retention of finished executions per terminal status, in hours
+///
+/// Terminal executions are aged from their end time. Executions still pending or
+/// running after the longest retention are assumed stuck and dropped too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionRetention {
+    pub completed_hours: u32,
+    pub failed_hours: u32,
+    pub cancelled_hours: u32,
+    pub timed_out_hours: u32,
+}
+
+impl ExecutionRetention {
+    /// Retention for an execution in `status`
+    pub fn hours_for(&self, status: &ExecutionStatus) -> u32 {
+        match status {
+            ExecutionStatus::Completed => self.completed_hours,
+            ExecutionStatus::Failed => self.failed_hours,
+            ExecutionStatus::Cancelled => self.cancelled_hours,
+            ExecutionStatus::TimedOut => self.timed_out_hours,
+            ExecutionStatus::Pending | ExecutionStatus::Running => self.longest_hours(),
+        }
+    }
+
+    fn longest_hours(&self) -> u32 {
+        self.completed_hours
+            .max(self.failed_hours)
+            .max(self.cancelled_hours)
+            .max(self.timed_out_hours)
+    }
+}
+
+impl Default for ExecutionRetention {
+    fn default() -> Self {
+        Self {
+            completed_hours: env_or("EXECUTION_RETENTION_COMPLETED_HOURS", 24),
+            failed_hours: env_or("EXECUTION_RETENTION_FAILED_HOURS", 72),
+            cancelled_hours: env_or("EXECUTION_RETENTION_CANCELLED_HOURS", 1),
+            timed_out_hours: env_or("EXECUTION_RETENTION_TIMEDOUT_HOURS", 72),
+        }
+    }
+}
+
+/// How output lines are coalesced before being sent over the WebSocket
+#[derive(Debug, Clone)]
+pub struct OutputFlushConfig {
+    /// Longest a line waits before its batch is sent
+    pub interval: Duration,
+    /// Pending bytes that trigger an immediate flush
+    pub max_batch_bytes: usize,
+    /// Pending lines that trigger an immediate flush
+    pub burst_lines: usize,
+}
+
+impl Default for OutputFlushConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(env_or("PYTHON_OUTPUT_FLUSH_MS", 100)),
+            max_batch_bytes: env_or("PYTHON_OUTPUT_MAX_BATCH_BYTES", 16 * 1024).max(1),
+            burst_lines: env_or("PYTHON_OUTPUT_BURST_LINES", 50).max(1),
+        }
+    }
+}
+
+/// Caps on what a single execution request may pass to the script
+#[derive(Debug, Clone)]
+pub struct ExecutionInputLimits {
+    pub max_args: usize,
+    pub max_env_vars: usize,
+    /// Combined length of all arguments, variable names and values, in bytes
+    pub max_total_bytes: usize,
+}
+
+impl Default for ExecutionInputLimits {
+    fn default() -> Self {
+        Self {
+            max_args: env_or("PYTHON_MAX_ARGS", 64),
+            max_env_vars: env_or("PYTHON_MAX_ENV_VARS", 64),
+            max_total_bytes: env_or("PYTHON_MAX_INPUT_BYTES", 64 * 1024),
+        }
+    }
+}
+
+impl ExecutionInputLimits {
+    /// Checks request arguments and environment variables against the caps
+    pub fn check(&self, args: &[String], env_vars: &HashMap<String, String>) -> Result<(), String> {
+        if args.len() > self.max_args {
+            return Err(format!("Too many arguments: {} (max {})", args.len(), self.max_args));
+        }
+        if env_vars.len() > self.max_env_vars {
+            return Err(format!(
+                "Too many environment variables: {} (max {})",
+                env_vars.len(),
+                self.max_env_vars
+            ));
+        }
+
+        let total_bytes = args.iter().map(String::len).sum::<usize>()
+            + env_vars.iter().map(|(key, value)| key.len() + value.len()).sum::<usize>();
+        if total_bytes > self.max_total_bytes {
+            return Err(format!(
+                "Arguments and environment variables too large: {} bytes (max {})",
+                total_bytes, self.max_total_bytes
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Output lines waiting to be flushed
+#[derive(Debug, Default)]
+struct OutputBatch {
+    lines: Vec<String>,
+    bytes: usize,
+}
+
+impl OutputBatch {
+    fn push(&mut self, line: String) {
+        self.bytes += line.len();
+        self.lines.push(line);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Whether the batch must be sent without waiting for the interval
+    fn is_full(&self, config: &OutputFlushConfig) -> bool {
+        self.bytes >= config.max_batch_bytes || self.lines.len() >= config.burst_lines
+    }
+
+    fn take(&mut self) -> Vec<String> {
+        self.bytes = 0;
+        std::mem::take(&mut self.lines)
+    }
+}
+
+/// Output lines buffered between an execution and its output streamer
+const OUTPUT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Container user when PYTHON_RUNNER_CONTAINER_USER is unset
+pub const DEFAULT_CONTAINER_USER: &str = "1000:1000";
+
+/// Checks a container user is numeric `uid` or `uid:gid`
+pub fn validate_container_user(user: &str) -> Result<(), String> {
+    let mut parts = user.splitn(2, ':');
+    let numeric = |part: Option<&str>| part.is_some_and(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+    let uid = parts.next();
+    let valid = numeric(uid) && parts.next().is_none_or(|gid| numeric(Some(gid)));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid container user '{}' (expected uid or uid:gid)", user))
+    }
+}
+
+/// Whether a container user runs as root (uid 0)
+fn is_root_user(user: &str) -> bool {
+    user.split(':').next().and_then(|uid| uid.parse::<u32>().ok()) == Some(0)
+}
+
+/// Docker log drivers accepted in `ContainerLogConfig::driver`
+pub const SUPPORTED_LOG_DRIVERS: [&str; 11] = [
+    "json-file", "local", "syslog", "journald", "gelf", "fluentd",
+    "awslogs", "splunk", "gcplogs", "logentries", "none",
+];
+
+/// Container log driver and options, shaped like Docker's `HostConfig.LogConfig`
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerLogConfig {
+    /// Log driver name, one of `SUPPORTED_LOG_DRIVERS`
+    #[serde(rename = "Type")]
+    pub driver: String,
+    /// Driver-specific options (e.g. `syslog-address`, `tag`)
+    #[serde(rename = "Config")]
+    pub options: HashMap<String, String>,
+}
+
+impl ContainerLogConfig {
+    /// Checks the driver name against `SUPPORTED_LOG_DRIVERS`
+    pub fn validate(&self) -> Result<(), String> {
+        if SUPPORTED_LOG_DRIVERS.contains(&self.driver.as_str()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Unsupported container log driver '{}' (expected one of: {})",
+                self.driver,
+                SUPPORTED_LOG_DRIVERS.join(", ")
+            ))
+        }
+    }
+}
+
+/// Create body of an execution container, shaped like Docker's `ContainerConfig`
+///
+/// `User` is always set, so a script never falls back to the image's default user.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerSpec {
+    pub image: String,
+    /// `uid[:gid]` resolved by `resolve_container_user`
+    pub user: String,
+    /// `python <script> <args...>`
+    pub cmd: Vec<String>,
+    /// `KEY=value` pairs, sorted by key
+    pub env: Vec<String>,
+    pub host_config: ContainerHostConfig,
+}
+
+/// The part of Docker's `HostConfig` set for execution containers
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerHostConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_config: Option<ContainerLogConfig>,
 }
 
 impl Default for PythonRunnerConfig {
@@ -97,6 +515,28 @@ impl Default for PythonRunnerConfig {
             docker_socket_path: "/var/run/docker.sock".to_string(),
             python_pipeline_path: "/home/nikos/github/ngeran/vlabs/python_pipeline".to_string(),
             cleanup_interval_hours: 24,
+            env_presets: HashMap::new(),
+            cancel_on_disconnect: false,
+            check_output_encoding: true,
+            log_config: None,
+            container_user: env_or("PYTHON_RUNNER_CONTAINER_USER", DEFAULT_CONTAINER_USER.to_string()),
+            allowed_container_users: std::env::var("PYTHON_RUNNER_ALLOWED_USERS")
+                .map(|users| {
+                    users
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|user| !user.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            retention: ExecutionRetention::default(),
+            image: env_or("PYTHON_RUNNER_IMAGE", "python-runner".to_string()),
+            output_flush: OutputFlushConfig::default(),
+            input_limits: ExecutionInputLimits::default(),
+            max_concurrent: env_or("PYTHON_MAX_CONCURRENT", 4).max(1),
+            queue_aging: Duration::from_secs(env_or("PYTHON_QUEUE_AGING_SECS", 30)),
+            shutdown_grace: Duration::from_secs(env_or("PYTHON_SHUTDOWN_GRACE_SECS", 30)),
         }
     }
 }
@@ -111,18 +551,20 @@ impl Default for PythonRunnerConfig {
 pub struct PythonRunnerService {
     /// Thread-safe storage for execution records
     executions: Arc<Mutex<HashMap<String, Execution>>>,
-    // FIXED: Removed unused config field
-    // config: PythonRunnerConfig,
-    // FIXED: Removed unused websocket_service field
-    // websocket_service: Arc<WebSocketService>,
+    /// Service configuration
+    config: PythonRunnerConfig,
+    /// WebSocket service used to notify the requesting client of status transitions
+    websocket_service: Arc<WebSocketService>,
+    /// Slots bounding concurrent executions, handed out by priority
+    queue: Arc<ExecutionQueue>,
 }
 
 impl PythonRunnerService {
     /// Creates a new PythonRunnerService instance
     ///
     /// # Arguments
-    /// * `websocket_service` - WebSocket service for real-time updates (currently unused)
-    /// * `config` - Optional configuration (uses defaults if None, currently unused)
+    /// * `websocket_service` - WebSocket service for real-time updates
+    /// * `config` - Optional configuration (uses defaults if None)
     ///
     /// # Returns
     /// Result with initialized service or error
@@ -132,42 +574,125 @@ impl PythonRunnerService {
     /// let python_runner = PythonRunnerService::new(websocket_service, None).await?;
     /// ```
     pub async fn new(
-        _websocket_service: Arc<WebSocketService>, // FIXED: Prefix with underscore to indicate unused
-        _config: Option<PythonRunnerConfig>,        // FIXED: Prefix with underscore to indicate unused
+        websocket_service: Arc<WebSocketService>,
+        config: Option<PythonRunnerConfig>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         info!("Initializing Python Runner service");
+
+        let config = config.unwrap_or_default();
+        if let Some(log_config) = &config.log_config {
+            log_config.validate()?;
+            info!("Execution containers will log via the '{}' driver", log_config.driver);
+        }
+        for user in std::iter::once(&config.container_user).chain(&config.allowed_container_users) {
+            validate_container_user(user)?;
+        }
+        if is_root_user(&config.container_user) {
+            warn!("Execution containers are configured to run as root ({})", config.container_user);
+        }
+        info!("Execution containers run as user {}", config.container_user);
         
+        info!(
+            "Up to {} executions run at once; queued ones age one priority level per {:?}",
+            config.max_concurrent, config.queue_aging
+        );
         let service = Self {
             executions: Arc::new(Mutex::new(HashMap::new())),
-            // FIXED: Removed unused field assignments
-            // config: config.unwrap_or_default(),
-            // websocket_service,
+            queue: ExecutionQueue::new(config.max_concurrent, config.queue_aging),
+            config,
+            websocket_service,
         };
 
+        service.spawn_disconnect_listener();
+
         info!("Python Runner service initialized successfully");
         Ok(service)
     }
 
+    /// Checks a request's arguments and inline environment variables against
+    /// `PythonRunnerConfig::input_limits`, before anything is launched
+    pub fn check_input_limits(&self, args: &[String], env_vars: &HashMap<String, String>) -> Result<(), String> {
+        self.config.input_limits.check(args, env_vars)
+    }
+
+    /// Resolves the environment for an execution from an optional named preset
+    ///
+    /// # Arguments
+    /// * `preset` - Optional preset name defined in `PythonRunnerConfig::env_presets`
+    /// * `env_vars` - Inline environment variables, which override preset values
+    ///
+    /// # Returns
+    /// Merged environment variables or error if the preset is unknown
+    pub fn resolve_env_vars(
+        &self,
+        preset: Option<&str>,
+        env_vars: HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+        let mut resolved = match preset {
+            Some(name) => self.config.env_presets.get(name).cloned().ok_or_else(|| {
+                warn!("Unknown environment preset: {}", name);
+                format!("Unknown environment preset '{}'", name)
+            })?,
+            None => HashMap::new(),
+        };
+
+        resolved.extend(env_vars);
+        Ok(resolved)
+    }
+
+    /// Resolves the user an execution container runs as
+    ///
+    /// # Arguments
+    /// * `requested` - Optional `uid[:gid]` from the request; must be the default user or in
+    ///   `PythonRunnerConfig::allowed_container_users`
+    ///
+    /// # Returns
+    /// The container user or error if the requested user is not allowed
+    pub fn resolve_container_user(&self, requested: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+        let user = match requested {
+            None => return Ok(self.config.container_user.clone()),
+            Some(user) => user,
+        };
+
+        validate_container_user(user)?;
+        if user == self.config.container_user || self.config.allowed_container_users.iter().any(|allowed| allowed == user) {
+            Ok(user.to_string())
+        } else {
+            warn!("Rejected container user override: {}", user);
+            Err(format!("Container user '{}' is not in the allowed list", user).into())
+        }
+    }
+
     /// Executes a Python script in a Docker container
     ///
     /// # Arguments
     /// * `script_path` - Path to Python script relative to python_pipeline directory
-    /// * `_args` - Command line arguments for the script (currently unused)
-    /// * `_env_vars` - Environment variables for the execution (currently unused)
-    /// * `_websocket_client_id` - Optional WebSocket client ID for real-time updates (currently unused)
+    /// * `args` - Command line arguments for the script, summarized (masked) in the started event
+    /// * `env_vars` - Environment variables for the execution; `XAOS_TRACE_ID` is added
+    /// * `websocket_client_id` - Optional WebSocket connection ID notified when the execution starts running
+    /// * `container_user` - User resolved by `resolve_container_user`; `None` uses the configured default
+    /// * `priority` - Position in the execution queue relative to other waiting executions
     ///
     /// # Returns
     /// Unique execution ID that can be used to track the execution
     pub async fn execute_script(
         &self,
         script_path: &str,
-        _args: Vec<String>, // Prefix with underscore to suppress warning
-        _env_vars: HashMap<String, String>, // Prefix with underscore
-        _websocket_client_id: Option<String>, // Prefix with underscore
+        args: Vec<String>,
+        mut env_vars: HashMap<String, String>,
+        websocket_client_id: Option<String>,
+        container_user: Option<String>,
+        priority: ExecutionPriority,
     ) -> Result<String, Box<dyn std::error::Error>> {
         info!("Starting Python script execution: {}", script_path);
+        // Checked here too, so no caller can start a container as a user outside the allowlist
+        let container_user = self.resolve_container_user(container_user.as_deref())?;
         
         let execution_id = Uuid::new_v4().to_string();
+        let trace_id = Uuid::new_v4().to_string();
+        env_vars.insert(TRACE_ID_ENV.to_string(), trace_id.clone());
+        debug!("Environment for {}: {:?}", execution_id, env_vars.keys().collect::<Vec<_>>());
+        let container = self.container_spec(script_path, &args, &env_vars, &container_user);
         
         // Create execution record
         let execution = Execution {
@@ -175,26 +700,38 @@ impl PythonRunnerService {
             script_path: script_path.to_string(),
             status: ExecutionStatus::Pending,
             output: None,
+            output_binary: false,
+            raw_output: None,
             error: None,
             exit_code: None,
             start_time: Some(std::time::SystemTime::now()),
             end_time: None,
+            websocket_client_id: websocket_client_id.clone(),
+            detached: false,
+            container_user,
+            trace_id,
+            events: Vec::new(),
+            environment: None,
+            priority,
         };
 
         // Store execution
-        let mut executions = self.executions.lock().await;
-        executions.insert(execution_id.clone(), execution);
+        self.executions.lock().await.insert(execution_id.clone(), execution);
+        self.notify_started(&execution_id, script_path, &args, &env_vars).await;
 
         // Clone execution_id for the async task to avoid move issues
         let execution_id_clone = execution_id.clone();
         let service_clone = self.clone();
         let script_path_clone = script_path.to_string();
 
-        // Spawn async task to run the script
+        // Spawn async task to run the script once a slot is free
         tokio::spawn(async move {
+            let _slot = service_clone.queue.acquire(priority).await;
             service_clone.simulate_script_execution(
                 &execution_id_clone,
                 &script_path_clone,
+                &container,
+                websocket_client_id,
             ).await;
         });
 
@@ -207,29 +744,301 @@ impl PythonRunnerService {
     /// # Arguments
     /// * `execution_id` - ID of the execution to simulate
     /// * `script_path` - Path to the script being executed
+    /// * `container` - Create body the execution container would be started with
+    /// * `websocket_client_id` - Optional WebSocket connection ID to notify of the Pending→Running transition
     async fn simulate_script_execution(
         &self,
         execution_id: &str,
         script_path: &str,
+        container: &ContainerSpec,
+        websocket_client_id: Option<String>,
     ) {
         debug!("Simulating script execution: {}", script_path);
-        
+        debug!(
+            "Container for {}: image {}, user {}, log config {:?}",
+            execution_id, container.image, container.user, container.host_config.log_config
+        );
+
+        // Pending → Running; start_time is set when the execution is queued
+        let queued_ms = {
+            let mut executions = self.executions.lock().await;
+            match executions.get_mut(execution_id) {
+                // Cancelled (e.g. on client disconnect) before it left the queue
+                Some(execution) if execution.status != ExecutionStatus::Pending => return,
+                Some(execution) => {
+                    execution.status = ExecutionStatus::Running;
+                    execution.environment = Some(self.capture_environment(execution_id));
+                    execution.start_time
+                        .and_then(|queued_at| queued_at.elapsed().ok())
+                        .map(|queued| queued.as_millis() as u64)
+                        .unwrap_or(0)
+                }
+                None => return,
+            }
+        };
+
+        let output = match &websocket_client_id {
+            Some(client_id) => {
+                self.notify_running(execution_id, script_path, client_id, queued_ms).await;
+                self.spawn_output_stream(execution_id, client_id)
+            }
+            None => None,
+        };
+
+        // Simulate execution time
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
         let mut executions = self.executions.lock().await;
         if let Some(execution) = executions.get_mut(execution_id) {
-            execution.status = ExecutionStatus::Running;
-            
-            // Simulate execution time
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-            
+            // Leave executions cancelled while running untouched
+            if execution.status != ExecutionStatus::Running {
+                return;
+            }
+
             execution.status = ExecutionStatus::Completed;
-            execution.output = Some(format!("Simulated output for {}", script_path));
+            let captured = format!("Simulated output for {}", script_path).into_bytes();
+            let lines: Vec<String> = String::from_utf8_lossy(&captured).lines().map(str::to_string).collect();
+            self.store_output(execution, captured);
             execution.exit_code = Some(0);
             execution.end_time = Some(std::time::SystemTime::now());
-            
+            drop(executions);
+
+            // Closing the channel after the last line flushes what is left
+            if let Some(output) = output {
+                for line in lines {
+                    if output.send(line).await.is_err() {
+                        break;
+                    }
+                }
+            }
+
             info!("Script execution completed: {}", execution_id);
         }
     }
 
+    /// Builds the create body of an execution's container
+    fn container_spec(
+        &self,
+        script_path: &str,
+        args: &[String],
+        env_vars: &HashMap<String, String>,
+        container_user: &str,
+    ) -> ContainerSpec {
+        let mut env: Vec<String> = env_vars.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        env.sort();
+
+        ContainerSpec {
+            image: self.config.image.clone(),
+            user: container_user.to_string(),
+            cmd: ["python", script_path].into_iter().map(str::to_string).chain(args.iter().cloned()).collect(),
+            env,
+            host_config: ContainerHostConfig { log_config: self.config.log_config.clone() },
+        }
+    }
+
+    /// Starts the task that batches output lines to the requesting client
+    ///
+    /// Lines sent on the returned channel are coalesced into `output` job events
+    /// per `OutputFlushConfig`; dropping the sender flushes the remainder.
+    fn spawn_output_stream(&self, execution_id: &str, client_id: &str) -> Option<mpsc::Sender<String>> {
+        let connection_id = Uuid::parse_str(client_id).ok()?;
+        let (sender, mut receiver) = mpsc::channel::<String>(OUTPUT_CHANNEL_CAPACITY);
+        let service = self.clone();
+        let execution_id = execution_id.to_string();
+
+        tokio::spawn(async move {
+            let config = service.config.output_flush.clone();
+            let mut batch = OutputBatch::default();
+            let mut seq = 0u64;
+            let mut deadline: Option<Instant> = None;
+
+            loop {
+                let line = match deadline {
+                    Some(at) => tokio::select! {
+                        line = receiver.recv() => line,
+                        _ = tokio::time::sleep_until(at) => {
+                            service.send_output_batch(connection_id, &execution_id, &mut seq, batch.take()).await;
+                            deadline = None;
+                            continue;
+                        }
+                    },
+                    None => receiver.recv().await,
+                };
+
+                let Some(line) = line else {
+                    if !batch.is_empty() {
+                        service.send_output_batch(connection_id, &execution_id, &mut seq, batch.take()).await;
+                    }
+                    break;
+                };
+
+                if batch.is_empty() {
+                    deadline = Some(Instant::now() + config.interval);
+                }
+                batch.push(line);
+                if batch.is_full(&config) {
+                    service.send_output_batch(connection_id, &execution_id, &mut seq, batch.take()).await;
+                    deadline = None;
+                }
+            }
+        });
+
+        Some(sender)
+    }
+
+    /// Sends one batch of output lines as an `output` job event
+    async fn send_output_batch(&self, connection_id: ConnectionId, execution_id: &str, seq: &mut u64, lines: Vec<String>) {
+        let trace_id = self.executions.lock().await.get(execution_id).map(|execution| execution.trace_id.clone());
+        *seq += 1;
+
+        let msg = WsMessage::JobEvent {
+            payload: JobEventPayload {
+                job_id: execution_id.to_string(),
+                device: String::new(),
+                job_type: "python_execution".to_string(),
+                event_type: "output".to_string(),
+                status: ExecutionStatus::Running.as_str().to_string(),
+                timestamp: Utc::now(),
+                data: serde_json::json!({
+                    "seq": *seq,
+                    "lines": lines,
+                }),
+                error: None,
+                trace_id,
+            },
+        };
+        if let Err(e) = self.websocket_service.send_to_connection(connection_id, msg).await {
+            debug!("Failed to send output batch for {}: {}", execution_id, e);
+        }
+    }
+
+    /// Captures where an execution runs from its container's inspect data
+    ///
+    /// Until the Docker client is wired in there is no container to inspect, so
+    /// only the host node and configured image are known; container id and digest stay unset.
+    fn capture_environment(&self, execution_id: &str) -> ExecutionEnvironment {
+        let host = std::env::var("HOSTNAME")
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|host| host.trim().to_string())
+            .filter(|host| !host.is_empty());
+        debug!("Execution {} runs on host {:?}", execution_id, host);
+
+        ExecutionEnvironment {
+            host,
+            image: Some(self.config.image.clone()),
+            ..ExecutionEnvironment::default()
+        }
+    }
+
+    /// Broadcasts an `execution_started` job event for a newly queued execution
+    ///
+    /// Carries the script, image and container user, the argument count and flag
+    /// names, the names (not values) of the environment variables and the time the
+    /// execution was queued.
+    async fn notify_started(
+        &self,
+        execution_id: &str,
+        script_path: &str,
+        args: &[String],
+        env_vars: &HashMap<String, String>,
+    ) {
+        let (trace_id, container_user, queued_at) = match self.executions.lock().await.get(execution_id) {
+            Some(execution) => (
+                execution.trace_id.clone(),
+                execution.container_user.clone(),
+                execution.start_time.map(chrono::DateTime::<Utc>::from),
+            ),
+            None => return,
+        };
+
+        let mut env_names: Vec<&String> = env_vars.keys().collect();
+        env_names.sort();
+
+        let payload = JobEventPayload {
+            job_id: execution_id.to_string(),
+            device: String::new(),
+            job_type: "python_execution".to_string(),
+            event_type: "execution_started".to_string(),
+            status: ExecutionStatus::Pending.as_str().to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({
+                "script_path": script_path,
+                "image": self.config.image,
+                "container_user": container_user,
+                "arg_flags": arg_flags(args),
+                "arg_count": args.len(),
+                "env": env_names,
+                "queued_at": queued_at,
+            }),
+            error: None,
+            trace_id: Some(trace_id),
+        };
+        self.record_trace_event(&payload).await;
+
+        if let Err(e) = self.websocket_service.broadcast_job_event(payload).await {
+            warn!("Failed to broadcast execution_started for {}: {}", execution_id, e);
+        }
+    }
+
+    /// Stores captured output bytes on an execution record
+    ///
+    /// `output` is always lossy-decoded so the record stays serializable. With
+    /// `check_output_encoding`, invalid UTF-8 sets `output_binary` and keeps the raw bytes.
+    fn store_output(&self, execution: &mut Execution, bytes: Vec<u8>) {
+        let binary = self.config.check_output_encoding && std::str::from_utf8(&bytes).is_err();
+        execution.output = Some(String::from_utf8_lossy(&bytes).into_owned());
+        execution.output_binary = binary;
+        if binary {
+            warn!("Execution {} produced non-UTF-8 output ({} bytes)", execution.id, bytes.len());
+            execution.raw_output = Some(bytes);
+        }
+    }
+
+    /// Sends a `running` job event to the client that requested the execution
+    ///
+    /// # Arguments
+    /// * `execution_id` - ID of the execution that started running
+    /// * `script_path` - Path to the script being executed
+    /// * `client_id` - WebSocket connection ID supplied with the execution request
+    /// * `queued_ms` - Time the execution spent pending, in milliseconds
+    async fn notify_running(&self, execution_id: &str, script_path: &str, client_id: &str, queued_ms: u64) {
+        let connection_id = match Uuid::parse_str(client_id) {
+            Ok(connection_id) => connection_id,
+            Err(_) => {
+                warn!("Invalid WebSocket client id '{}' for execution {}", client_id, execution_id);
+                return;
+            }
+        };
+
+        let trace_id = match self.executions.lock().await.get(execution_id) {
+            Some(execution) => execution.trace_id.clone(),
+            None => return,
+        };
+
+        let payload = JobEventPayload {
+            job_id: execution_id.to_string(),
+            device: String::new(),
+            job_type: "python_execution".to_string(),
+            event_type: "running".to_string(),
+            status: ExecutionStatus::Running.as_str().to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({
+                "script_path": script_path,
+                "previous_status": ExecutionStatus::Pending.as_str(),
+                "queued_ms": queued_ms,
+            }),
+            error: None,
+            trace_id: Some(trace_id),
+        };
+        self.record_trace_event(&payload).await;
+        let msg = WsMessage::JobEvent { payload };
+
+        if let Err(e) = self.websocket_service.send_to_connection(connection_id, msg).await {
+            warn!("Failed to notify client {} of execution {} running: {}", client_id, execution_id, e);
+        }
+    }
+
     /// Retrieves the status of a specific execution
     ///
     /// # Arguments
@@ -266,10 +1075,23 @@ impl PythonRunnerService {
         }
     }
 
+    /// Retrieves the raw output bytes of an execution
+    ///
+    /// # Returns
+    /// The raw bytes for binary output, the UTF-8 output otherwise, or None if
+    /// no output was captured; error if the execution is not found
+    pub async fn get_raw_output(&self, execution_id: &str) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        let executions = self.executions.lock().await;
+        let execution = executions.get(execution_id).ok_or("Execution not found")?;
+        Ok(execution.raw_output.clone()
+            .or_else(|| execution.output.as_ref().map(|output| output.clone().into_bytes())))
+    }
+
     /// Lists executions with optional filtering
     ///
     /// # Arguments
     /// * `status_filter` - Optional status to filter by
+    /// * `since` - Optional cutoff; only executions started or ended after it are kept
     /// * `limit` - Optional maximum number of results
     ///
     /// # Returns
@@ -277,6 +1099,7 @@ impl PythonRunnerService {
     pub async fn list_executions(
         &self,
         status_filter: Option<ExecutionStatus>,
+        since: Option<std::time::SystemTime>,
         limit: Option<usize>,
     ) -> Vec<Execution> {
         let executions = self.executions.lock().await;
@@ -287,9 +1110,11 @@ impl PythonRunnerService {
             results.retain(|e| e.status == filter);
         }
 
-        // Apply limit
-        if let Some(limit) = limit {
-            results.truncate(limit);
+        // Apply updated-since filter
+        if let Some(since) = since {
+            results.retain(|e| {
+                e.start_time.is_some_and(|t| t > since) || e.end_time.is_some_and(|t| t > since)
+            });
         }
 
         // Sort by start time (most recent first)
@@ -299,10 +1124,67 @@ impl PythonRunnerService {
             b_time.cmp(&a_time)
         });
 
+        // Apply limit to the most recent executions
+        if let Some(limit) = limit {
+            results.truncate(limit);
+        }
+
         results
     }
 
-    /// Cancels a running execution
+    /// Attaches a job event to the execution whose trace id it carries
+    ///
+    /// # Returns
+    /// `false` when the event has no trace id or no execution has that trace id
+    pub async fn record_trace_event(&self, event: &JobEventPayload) -> bool {
+        let Some(trace_id) = event.trace_id.as_deref() else {
+            return false;
+        };
+
+        let mut executions = self.executions.lock().await;
+        match executions.values_mut().find(|execution| execution.trace_id == trace_id) {
+            Some(execution) => {
+                if execution.events.len() >= MAX_TRACE_EVENTS {
+                    execution.events.remove(0);
+                }
+                execution.events.push(event.clone());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Job events recorded for an execution, oldest first
+    ///
+    /// # Returns
+    /// The events or error if the execution is not found
+    pub async fn get_execution_events(&self, execution_id: &str) -> Result<Vec<JobEventPayload>, Box<dyn std::error::Error>> {
+        let executions = self.executions.lock().await;
+        match executions.get(execution_id) {
+            Some(execution) => Ok(execution.events.clone()),
+            None => {
+                warn!("Execution not found: {}", execution_id);
+                Err("Execution not found".into())
+            }
+        }
+    }
+
+    /// Number of tracked executions per status (e.g. `"running": 2`)
+    pub async fn execution_counts(&self) -> std::collections::BTreeMap<String, usize> {
+        let executions = self.executions.lock().await;
+        let mut counts = std::collections::BTreeMap::new();
+        for execution in executions.values() {
+            *counts.entry(execution.status.as_str().to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Number of executions waiting for a free execution slot
+    pub fn queued_executions(&self) -> usize {
+        self.queue.waiting()
+    }
+
+    /// Cancels a queued or running execution
     ///
     /// # Arguments
     /// * `execution_id` - ID of the execution to cancel
@@ -313,38 +1195,606 @@ impl PythonRunnerService {
     /// # Note
     /// Currently marks execution as cancelled. Future implementation
     /// will terminate the actual Docker container.
-    pub async fn cancel_execution(&self, execution_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn cancel_execution(&self, execution_id: &str) -> Result<(), CancelError> {
         let mut executions = self.executions.lock().await;
-        if let Some(execution) = executions.get_mut(execution_id) {
-            if execution.status == ExecutionStatus::Running {
+        let execution = executions.get_mut(execution_id).ok_or(CancelError::NotFound)?;
+
+        // A queued execution never starts once cancelled; see simulate_script_execution
+        match &execution.status {
+            ExecutionStatus::Pending | ExecutionStatus::Running => {
                 execution.status = ExecutionStatus::Cancelled;
                 execution.end_time = Some(std::time::SystemTime::now());
                 execution.error = Some("Execution cancelled by user".to_string());
                 info!("Execution cancelled: {}", execution_id);
-            } else {
-                return Err("Execution is not running".into());
+                Ok(())
             }
-        } else {
-            return Err("Execution not found".into());
+            terminal => Err(CancelError::AlreadyTerminal(terminal.clone())),
         }
-        
-        Ok(())
     }
 
-    /// Cleans up old execution records
+    /// Cancels every running execution
+    ///
+    /// # Returns
+    /// Cancelled execution ids, plus ids that could not be cancelled
+    /// (e.g. finished between listing and cancelling)
+    pub async fn cancel_all_executions(&self) -> CancelAllResult {
+        let running: Vec<String> = self
+            .list_executions(Some(ExecutionStatus::Running), None, None)
+            .await
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+
+        let mut result = CancelAllResult::default();
+        for execution_id in running {
+            match self.cancel_execution(&execution_id).await {
+                Ok(()) => result.cancelled.push(execution_id),
+                Err(e) => result.failed.push(CancelFailure {
+                    id: execution_id,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        info!(
+            "Cancel-all completed: {} cancelled, {} failed",
+            result.cancelled.len(),
+            result.failed.len()
+        );
+        result
+    }
+
+    /// Listens for WebSocket disconnects and handles the client's unfinished executions
+    fn spawn_disconnect_listener(&self) {
+        let service = self.clone();
+        let mut disconnects = self.websocket_service.subscribe_disconnects();
+
+        tokio::spawn(async move {
+            loop {
+                match disconnects.recv().await {
+                    Ok(connection_id) => service.handle_client_disconnect(connection_id).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Missed {} WebSocket disconnect notifications", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Detaches or cancels the unfinished executions of a disconnected client
     ///
     /// # Arguments
-    /// * `hours_old` - Age in hours after which executions should be cleaned up
-    pub async fn cleanup_old_executions(&self, hours_old: u32) {
-        info!("Cleaning up executions older than {} hours", hours_old);
-        
+    /// * `connection_id` - WebSocket connection that was cleaned up
+    ///
+    /// # Behavior
+    /// - With `cancel_on_disconnect`, pending and running executions are cancelled
+    /// - Otherwise they keep running detached, with output buffered on the record
+    /// - A `cancelled` or `detached` job event is broadcast for each execution
+    async fn handle_client_disconnect(&self, connection_id: ConnectionId) {
+        let client_id = connection_id.to_string();
+        let cancel = self.config.cancel_on_disconnect;
+
+        let affected: Vec<(String, String, String)> = {
+            let mut executions = self.executions.lock().await;
+            executions
+                .values_mut()
+                .filter(|e| e.websocket_client_id.as_deref() == Some(client_id.as_str()))
+                .filter(|e| matches!(e.status, ExecutionStatus::Pending | ExecutionStatus::Running))
+                .map(|execution| {
+                    if cancel {
+                        execution.status = ExecutionStatus::Cancelled;
+                        execution.end_time = Some(std::time::SystemTime::now());
+                        execution.error = Some("Execution cancelled: client disconnected".to_string());
+                    } else {
+                        execution.detached = true;
+                    }
+                    (execution.id.clone(), execution.script_path.clone(), execution.trace_id.clone())
+                })
+                .collect()
+        };
+
+        if affected.is_empty() {
+            return;
+        }
+
+        let (event_type, status) = if cancel {
+            ("cancelled", ExecutionStatus::Cancelled)
+        } else {
+            ("detached", ExecutionStatus::Running)
+        };
+        info!("Client {} disconnected: {} {} execution(s)", client_id, event_type, affected.len());
+
+        for (execution_id, script_path, trace_id) in affected {
+            self.broadcast_lifecycle_event(&execution_id, trace_id, event_type, status.clone(), serde_json::json!({
+                "script_path": script_path,
+                "websocket_client_id": client_id,
+                "reason": "client_disconnected",
+            }))
+            .await;
+        }
+    }
+
+    /// Stops executions for a graceful shutdown
+    ///
+    /// # Behavior
+    /// - Pending executions are cancelled at once; they would not start in time
+    /// - Running executions get `shutdown_grace` to finish on their own
+    /// - Executions still running after that are cancelled as "server shutdown", which also
+    ///   stops their container, rather than being marked failed
+    /// - A `cancelled` job event with reason `server_shutdown` is broadcast for each
+    pub async fn shutdown(&self) {
+        self.cancel_for_shutdown(ExecutionStatus::Pending).await;
+
+        let grace = self.config.shutdown_grace;
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            let running = self.list_executions(Some(ExecutionStatus::Running), None, None).await.len();
+            if running == 0 {
+                info!("All executions finished before shutdown");
+                return;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                warn!("{} execution(s) still running after the {:?} shutdown grace period", running, grace);
+                break;
+            }
+            info!("Waiting for {} running execution(s) before shutdown", running);
+            tokio::time::sleep(Duration::from_millis(250).min(grace)).await;
+        }
+
+        self.cancel_for_shutdown(ExecutionStatus::Running).await;
+    }
+
+    /// Cancels every execution in `status` with a "server shutdown" note
+    async fn cancel_for_shutdown(&self, status: ExecutionStatus) {
+        let cancelled: Vec<(String, String, String)> = {
+            let mut executions = self.executions.lock().await;
+            executions
+                .values_mut()
+                .filter(|e| e.status == status)
+                .map(|execution| {
+                    // The execution task sees the status change and stops the container
+                    execution.status = ExecutionStatus::Cancelled;
+                    execution.end_time = Some(std::time::SystemTime::now());
+                    execution.error = Some("Execution cancelled: server shutdown".to_string());
+                    (execution.id.clone(), execution.script_path.clone(), execution.trace_id.clone())
+                })
+                .collect()
+        };
+
+        if cancelled.is_empty() {
+            return;
+        }
+        info!("Shutdown: cancelled {} {} execution(s)", cancelled.len(), status.as_str());
+
+        for (execution_id, script_path, trace_id) in cancelled {
+            self.broadcast_lifecycle_event(&execution_id, trace_id, "cancelled", ExecutionStatus::Cancelled, serde_json::json!({
+                "script_path": script_path,
+                "reason": "server_shutdown",
+                "was": status.as_str(),
+            }))
+            .await;
+        }
+    }
+
+    /// Records and broadcasts a status change job event for an execution
+    async fn broadcast_lifecycle_event(
+        &self,
+        execution_id: &str,
+        trace_id: String,
+        event_type: &str,
+        status: ExecutionStatus,
+        data: serde_json::Value,
+    ) {
+        let event = JobEventPayload {
+            job_id: execution_id.to_string(),
+            device: String::new(),
+            job_type: "python_execution".to_string(),
+            event_type: event_type.to_string(),
+            status: status.as_str().to_string(),
+            timestamp: Utc::now(),
+            data,
+            error: None,
+            trace_id: Some(trace_id),
+        };
+        self.record_trace_event(&event).await;
+        if let Err(e) = self.websocket_service.broadcast_job_event(event).await {
+            warn!("Failed to broadcast {} event for execution {}: {}", event_type, execution_id, e);
+        }
+    }
+
+    /// Cleans up execution records older than the retention for their status
+    ///
+    /// See `ExecutionRetention` for the per-status durations.
+    pub async fn cleanup_old_executions(&self) {
+        let retention = self.config.retention;
+        info!("Cleaning up executions past retention: {:?}", retention);
+
         let mut executions = self.executions.lock().await;
-        let cutoff = std::time::SystemTime::now() - std::time::Duration::from_secs(hours_old as u64 * 3600);
-        
+        let before = executions.len();
+        let now = std::time::SystemTime::now();
+
         executions.retain(|_, execution| {
-            execution.start_time.map(|t| t > cutoff).unwrap_or(false)
+            let max_age = std::time::Duration::from_secs(retention.hours_for(&execution.status) as u64 * 3600);
+            execution
+                .end_time
+                .or(execution.start_time)
+                .map(|t| now.duration_since(t).unwrap_or_default())
+                .is_some_and(|age| age < max_age)
         });
-        
-        info!("Cleanup completed: {} executions remaining", executions.len());
+
+        info!(
+            "Cleanup completed: {} removed, {} executions remaining",
+            before - executions.len(),
+            executions.len()
+        );
+    }
+
+    /// Records a finished execution with the given output, standing in for a real run
+    #[cfg(test)]
+    pub(crate) async fn insert_finished_execution(&self, script_path: &str, status: ExecutionStatus, output: &str) -> String {
+        let execution_id = Uuid::new_v4().to_string();
+        let now = std::time::SystemTime::now();
+        let exit_code = Some(if status == ExecutionStatus::Completed { 0 } else { 1 });
+        let execution = Execution {
+            id: execution_id.clone(),
+            script_path: script_path.to_string(),
+            status,
+            output: Some(output.to_string()),
+            output_binary: false,
+            raw_output: None,
+            error: None,
+            exit_code,
+            start_time: Some(now),
+            end_time: Some(now),
+            websocket_client_id: None,
+            detached: false,
+            container_user: self.config.container_user.clone(),
+            trace_id: Uuid::new_v4().to_string(),
+            events: Vec::new(),
+            environment: None,
+            priority: ExecutionPriority::Normal,
+        };
+        self.executions.lock().await.insert(execution_id.clone(), execution);
+        execution_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution_status_round_trips_through_documented_names() {
+        for status in ExecutionStatus::ALL {
+            let json = serde_json::to_value(&status).unwrap();
+            assert_eq!(json, serde_json::Value::String(status.as_str().to_string()));
+
+            let from_json: ExecutionStatus = serde_json::from_value(json).unwrap();
+            assert_eq!(from_json, status);
+
+            let parsed: ExecutionStatus = status.as_str().parse().unwrap();
+            assert_eq!(parsed, status);
+        }
+
+        assert_eq!("TimedOut".parse::<ExecutionStatus>(), Ok(ExecutionStatus::TimedOut));
+        assert!("timed_out".parse::<ExecutionStatus>().is_err());
+    }
+
+    #[tokio::test]
+    async fn cleanup_applies_the_retention_of_each_status() {
+        let websocket_service = Arc::new(WebSocketService::new(
+            None,
+            Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+        ));
+        let retention = ExecutionRetention { completed_hours: 24, failed_hours: 72, cancelled_hours: 1, timed_out_hours: 72 };
+        let config = PythonRunnerConfig { retention, ..PythonRunnerConfig::default() };
+        let service = PythonRunnerService::new(websocket_service, Some(config)).await.unwrap();
+
+        let mut ids = Vec::new();
+        for status in [ExecutionStatus::Completed, ExecutionStatus::Failed, ExecutionStatus::Cancelled] {
+            ids.push(service.insert_finished_execution("scripts/run.py", status, "").await);
+        }
+        // Two hours old: past the cancelled retention only
+        let two_hours_ago = std::time::SystemTime::now() - Duration::from_secs(2 * 3600);
+        for execution in service.executions.lock().await.values_mut() {
+            execution.end_time = Some(two_hours_ago);
+        }
+
+        service.cleanup_old_executions().await;
+        let mut kept = Vec::new();
+        for id in &ids {
+            kept.push(service.get_execution(id).await.is_ok());
+        }
+        assert_eq!(kept, [true, true, false]);
+    }
+
+    #[tokio::test]
+    async fn shutdown_cancels_executions_past_the_grace_period() {
+        let websocket_service = Arc::new(WebSocketService::new(
+            None,
+            Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+        ));
+        let config = PythonRunnerConfig {
+            max_concurrent: 1,
+            shutdown_grace: Duration::ZERO,
+            ..PythonRunnerConfig::default()
+        };
+        let service = PythonRunnerService::new(websocket_service, Some(config)).await.unwrap();
+
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let id = service
+                .execute_script("scripts/run.py", Vec::new(), HashMap::new(), None, None, ExecutionPriority::Normal)
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(service.get_execution_status(&ids[0]).await.unwrap(), ExecutionStatus::Running);
+        assert_eq!(service.get_execution_status(&ids[1]).await.unwrap(), ExecutionStatus::Pending);
+
+        service.shutdown().await;
+        for id in &ids {
+            let execution = service.get_execution(id).await.unwrap();
+            assert_eq!(execution.status, ExecutionStatus::Cancelled);
+            assert_eq!(execution.error.as_deref(), Some("Execution cancelled: server shutdown"));
+        }
+    }
+
+    #[tokio::test]
+    async fn queued_executions_can_be_cancelled() {
+        let websocket_service = Arc::new(WebSocketService::new(
+            None,
+            Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+        ));
+        let config = PythonRunnerConfig { max_concurrent: 1, ..PythonRunnerConfig::default() };
+        let service = PythonRunnerService::new(websocket_service, Some(config)).await.unwrap();
+
+        let run = || service.execute_script("scripts/run.py", Vec::new(), HashMap::new(), None, None, ExecutionPriority::Normal);
+        let running = run().await.unwrap();
+        let queued = run().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(service.get_execution_status(&queued).await.unwrap(), ExecutionStatus::Pending);
+
+        service.cancel_execution(&queued).await.unwrap();
+        assert_eq!(service.get_execution_status(&queued).await.unwrap(), ExecutionStatus::Cancelled);
+        assert!(matches!(
+            service.cancel_execution(&queued).await,
+            Err(CancelError::AlreadyTerminal(ExecutionStatus::Cancelled))
+        ));
+
+        // Once the running execution frees its slot, the cancelled one stays cancelled
+        tokio::time::sleep(Duration::from_millis(2100)).await;
+        assert_eq!(service.get_execution_status(&running).await.unwrap(), ExecutionStatus::Completed);
+        assert_eq!(service.get_execution_status(&queued).await.unwrap(), ExecutionStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn containers_run_as_the_resolved_user_only() {
+        let websocket_service = Arc::new(WebSocketService::new(
+            None,
+            Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+        ));
+        let config = PythonRunnerConfig {
+            container_user: "1000:1000".to_string(),
+            allowed_container_users: vec!["2000".to_string()],
+            ..PythonRunnerConfig::default()
+        };
+        let service = PythonRunnerService::new(websocket_service, Some(config)).await.unwrap();
+
+        let spec = service.container_spec("scripts/run.py", &["--host".to_string()], &HashMap::new(), "2000");
+        let spec = serde_json::to_value(&spec).unwrap();
+        assert_eq!(spec["User"], "2000");
+        assert_eq!(spec["Cmd"], serde_json::json!(["python", "scripts/run.py", "--host"]));
+
+        let run_as = |user: Option<&str>| {
+            let user = user.map(str::to_string);
+            service.execute_script("scripts/run.py", Vec::new(), HashMap::new(), None, user, ExecutionPriority::Normal)
+        };
+        let id = run_as(None).await.unwrap();
+        assert_eq!(service.get_execution(&id).await.unwrap().container_user, "1000:1000");
+        let id = run_as(Some("2000")).await.unwrap();
+        assert_eq!(service.get_execution(&id).await.unwrap().container_user, "2000");
+        assert!(run_as(Some("0")).await.is_err());
+        assert!(run_as(Some("root")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn log_config_is_validated_and_passed_to_the_container() {
+        let websocket_service = Arc::new(WebSocketService::new(
+            None,
+            Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+        ));
+        let log_config = |driver: &str| ContainerLogConfig {
+            driver: driver.to_string(),
+            options: HashMap::from([("tag".to_string(), "xaos".to_string())]),
+        };
+
+        let config = PythonRunnerConfig { log_config: Some(log_config("syslogd")), ..PythonRunnerConfig::default() };
+        let error = PythonRunnerService::new(websocket_service.clone(), Some(config)).await.err().unwrap();
+        assert!(error.to_string().contains("'syslogd'"));
+
+        let config = PythonRunnerConfig { log_config: Some(log_config("syslog")), ..PythonRunnerConfig::default() };
+        let service = PythonRunnerService::new(websocket_service, Some(config)).await.unwrap();
+        let spec = serde_json::to_value(service.container_spec("scripts/run.py", &[], &HashMap::new(), "1000")).unwrap();
+        assert_eq!(spec["HostConfig"]["LogConfig"], serde_json::json!({ "Type": "syslog", "Config": { "tag": "xaos" } }));
+    }
+
+    #[test]
+    fn output_batch_is_full_at_line_or_byte_limit() {
+        let config = OutputFlushConfig {
+            interval: Duration::from_millis(100),
+            max_batch_bytes: 10,
+            burst_lines: 3,
+        };
+
+        let mut batch = OutputBatch::default();
+        batch.push("a".to_string());
+        batch.push("b".to_string());
+        assert!(!batch.is_full(&config));
+        batch.push("c".to_string());
+        assert!(batch.is_full(&config));
+        assert_eq!(batch.take(), vec!["a", "b", "c"]);
+        assert!(batch.is_empty());
+
+        batch.push("0123456789".to_string());
+        assert!(batch.is_full(&config));
+    }
+
+    #[test]
+    fn input_limits_reject_oversized_requests() {
+        let limits = ExecutionInputLimits { max_args: 2, max_env_vars: 1, max_total_bytes: 10 };
+        let env = HashMap::from([("A".to_string(), "1".to_string())]);
+
+        assert!(limits.check(&["a".to_string()], &env).is_ok());
+        assert!(limits.check(&vec!["a".to_string(); 3], &env).unwrap_err().starts_with("Too many arguments"));
+        let two_vars = HashMap::from([("A".to_string(), "1".to_string()), ("B".to_string(), "2".to_string())]);
+        assert!(limits.check(&[], &two_vars).unwrap_err().starts_with("Too many environment variables"));
+        assert!(limits.check(&["0123456789".to_string()], &env).unwrap_err().contains("too large"));
+    }
+
+    #[tokio::test]
+    async fn inline_env_vars_override_the_preset() {
+        let websocket_service = Arc::new(WebSocketService::new(
+            None,
+            Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+        ));
+        let preset = HashMap::from([("ENV".to_string(), "lab".to_string()), ("DEBUG".to_string(), "true".to_string())]);
+        let config = PythonRunnerConfig {
+            env_presets: HashMap::from([("lab".to_string(), preset)]),
+            ..PythonRunnerConfig::default()
+        };
+        let service = PythonRunnerService::new(websocket_service, Some(config)).await.unwrap();
+
+        let inline = HashMap::from([("DEBUG".to_string(), "false".to_string())]);
+        let resolved = service.resolve_env_vars(Some("lab"), inline).unwrap();
+        assert_eq!(resolved["ENV"], "lab");
+        assert_eq!(resolved["DEBUG"], "false");
+        assert!(service.resolve_env_vars(Some("prod"), HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn arg_flags_never_include_values() {
+        let args: Vec<String> = ["--host", "r1", "--password", "hunter2", "--api-token=abc", "positional-secret"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(arg_flags(&args), vec!["--host", "--password", "--api-token"]);
+    }
+
+    #[tokio::test]
+    async fn lifecycle_events_carry_wire_status_names() {
+        let app = crate::test_support::TestApp::new().await;
+        let runner = &app.state.python_runner_service;
+        let execution_id = runner.insert_finished_execution("scripts/run.py", ExecutionStatus::Running, "").await;
+
+        runner.cancel_for_shutdown(ExecutionStatus::Running).await;
+        let events = runner.get_execution_events(&execution_id).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].event_type.as_str(), events[0].status.as_str()), ("cancelled", "cancelled"));
+    }
+
+    #[tokio::test]
+    async fn requesting_client_hears_when_the_execution_starts_running() {
+        let app = crate::test_support::TestApp::new().await;
+        let runner = &app.state.python_runner_service;
+        let (client, _, mut outbound) = app.state.websocket_service.connect_test_client().await;
+
+        let execution_id = runner
+            .execute_script("scripts/run.py", Vec::new(), HashMap::new(), Some(client.to_string()), None, ExecutionPriority::Normal)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut running = Vec::new();
+        while let Ok(axum::extract::ws::Message::Text(text)) = outbound.try_recv() {
+            if let Ok(WsMessage::JobEvent { payload }) = serde_json::from_str(&text) {
+                running.push(payload);
+            }
+        }
+        assert_eq!(running.len(), 1);
+        assert_eq!((running[0].job_id.as_str(), running[0].event_type.as_str()), (execution_id.as_str(), "running"));
+        assert_eq!(running[0].data["previous_status"], "pending");
+        let events = runner.get_execution_events(&execution_id).await.unwrap();
+        assert!(events.iter().any(|event| event.event_type == "running"));
+    }
+
+    #[tokio::test]
+    async fn environment_is_recorded_once_the_execution_runs() {
+        let app = crate::test_support::TestApp::new().await;
+        let runner = &app.state.python_runner_service;
+
+        let execution_id = runner
+            .execute_script("scripts/run.py", Vec::new(), HashMap::new(), None, None, ExecutionPriority::Normal)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let environment = runner.get_execution(&execution_id).await.unwrap().environment.unwrap();
+        assert_eq!(environment.image.as_deref(), Some(runner.config.image.as_str()));
+        assert!(environment.container_id.is_none() && environment.image_digest.is_none());
+    }
+
+    #[tokio::test]
+    async fn disconnects_detach_or_cancel_the_clients_executions() {
+        use crate::models::websocket::CloseReason;
+
+        for cancel_on_disconnect in [false, true] {
+            let websocket_service = Arc::new(WebSocketService::new(
+                None,
+                Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+            ));
+            let config = PythonRunnerConfig { cancel_on_disconnect, ..PythonRunnerConfig::default() };
+            let service = PythonRunnerService::new(websocket_service.clone(), Some(config)).await.unwrap();
+            let (client, _, _outbound) = websocket_service.connect_test_client().await;
+
+            let run = |client_id: Option<String>| {
+                service.execute_script("scripts/run.py", Vec::new(), HashMap::new(), client_id, None, ExecutionPriority::Normal)
+            };
+            let owned = run(Some(client.to_string())).await.unwrap();
+            let other = run(None).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+
+            websocket_service.cleanup_connection(client, CloseReason::ClientClose).await;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+
+            let owned = service.get_execution(&owned).await.unwrap();
+            if cancel_on_disconnect {
+                assert_eq!(owned.status, ExecutionStatus::Cancelled);
+                assert_eq!(owned.error.as_deref(), Some("Execution cancelled: client disconnected"));
+            } else {
+                assert_eq!((owned.status, owned.detached), (ExecutionStatus::Running, true));
+            }
+            let other = service.get_execution(&other).await.unwrap();
+            assert_eq!((other.status, other.detached), (ExecutionStatus::Running, false));
+        }
+    }
+
+    #[tokio::test]
+    async fn non_utf8_output_is_flagged_and_kept_raw() {
+        for check_output_encoding in [true, false] {
+            let websocket_service = Arc::new(WebSocketService::new(
+                None,
+                Arc::new(crate::services::webhook_service::WebhookService::new(None)),
+            ));
+            let config = PythonRunnerConfig { check_output_encoding, ..PythonRunnerConfig::default() };
+            let service = PythonRunnerService::new(websocket_service, Some(config)).await.unwrap();
+            let id = service.insert_finished_execution("scripts/dump.py", ExecutionStatus::Completed, "").await;
+
+            let bytes = vec![b'o', b'k', 0xff];
+            if let Some(execution) = service.executions.lock().await.get_mut(&id) {
+                service.store_output(execution, bytes.clone());
+            }
+            let execution = service.get_execution(&id).await.unwrap();
+            assert_eq!(execution.output.as_deref(), Some("ok\u{fffd}"));
+            assert_eq!(execution.output_binary, check_output_encoding);
+            let raw = service.get_raw_output(&id).await.unwrap().unwrap();
+            if check_output_encoding {
+                assert_eq!(raw, bytes);
+            } else {
+                assert_eq!(raw, "ok\u{fffd}".as_bytes());
+            }
+        }
     }
 }
@@ -0,0 +1,132 @@
+// File Path: src/services/route_metrics_service.rs
+// Version: 1.0.0
+// Description: Per-route request counters and latency histograms used for basic SLO tracking.
+//
+// Key Features:
+// - Request count, 5xx error count and latency histogram per route and method
+// - Routes are keyed by their matched pattern (`/api/python/status/:id`) so cardinality stays bounded
+// - Renders the registry in the Prometheus text exposition format
+//
+// Usage Guide:
+// ```
+// route_metrics_service.record("GET", "/api/python/status/:id", 200, elapsed).await;
+// let body = route_metrics_service.render_prometheus().await;
+// ```
+//
+// Change Log:
+// - 1.0.0: Initial implementation
+
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+use tokio::sync::RwLock;
+
+// =============================================================================
+// SECTION 1: REGISTRY TYPES
+// =============================================================================
+
+/// Upper bounds in seconds of the latency histogram buckets
+pub const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Label used for requests that did not match any route
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Counters and latency histogram for a single route and method
+#[derive(Debug, Clone, Default)]
+pub struct RouteStats {
+    /// Total requests handled
+    pub requests: u64,
+    /// Requests that returned a 5xx status
+    pub errors: u64,
+    /// Cumulative count per bucket in `LATENCY_BUCKETS`
+    pub buckets: [u64; LATENCY_BUCKETS.len()],
+    /// Sum of all observed latencies in seconds
+    pub latency_sum: f64,
+}
+
+impl RouteStats {
+    fn observe(&mut self, status: u16, latency: Duration) {
+        let seconds = latency.as_secs_f64();
+        self.requests += 1;
+        if status >= 500 {
+            self.errors += 1;
+        }
+        self.latency_sum += seconds;
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+    }
+}
+
+// =============================================================================
+// SECTION 2: SERVICE IMPLEMENTATION
+// =============================================================================
+
+/// Shared registry of per-route request metrics
+#[derive(Debug, Default)]
+pub struct RouteMetricsService {
+    /// Stats keyed by (route pattern, method)
+    routes: RwLock<BTreeMap<(String, String), RouteStats>>,
+}
+
+impl RouteMetricsService {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a completed request
+    ///
+    /// # Arguments
+    /// * `method` - HTTP method
+    /// * `route` - Matched route pattern, or `UNMATCHED_ROUTE`
+    /// * `status` - Response status code
+    /// * `latency` - Time taken to produce the response
+    pub async fn record(&self, method: &str, route: &str, status: u16, latency: Duration) {
+        self.routes
+            .write()
+            .await
+            .entry((route.to_string(), method.to_string()))
+            .or_default()
+            .observe(status, latency);
+    }
+
+    /// Renders all route metrics in the Prometheus text exposition format
+    pub async fn render_prometheus(&self) -> String {
+        let routes = self.routes.read().await;
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP http_requests_total Total HTTP requests per route.");
+        let _ = writeln!(out, "# TYPE http_requests_total counter");
+        for ((route, method), stats) in routes.iter() {
+            let _ = writeln!(out, "http_requests_total{{route=\"{}\",method=\"{}\"}} {}", route, method, stats.requests);
+        }
+
+        let _ = writeln!(out, "# HELP http_request_errors_total HTTP requests per route that returned a 5xx status.");
+        let _ = writeln!(out, "# TYPE http_request_errors_total counter");
+        for ((route, method), stats) in routes.iter() {
+            let _ = writeln!(out, "http_request_errors_total{{route=\"{}\",method=\"{}\"}} {}", route, method, stats.errors);
+        }
+
+        let _ = writeln!(out, "# HELP http_request_duration_seconds HTTP request latency per route.");
+        let _ = writeln!(out, "# TYPE http_request_duration_seconds histogram");
+        for ((route, method), stats) in routes.iter() {
+            for (count, bound) in stats.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "http_request_duration_seconds_bucket{{route=\"{}\",method=\"{}\",le=\"{}\"}} {}",
+                    route, method, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "http_request_duration_seconds_bucket{{route=\"{}\",method=\"{}\",le=\"+Inf\"}} {}",
+                route, method, stats.requests
+            );
+            let _ = writeln!(out, "http_request_duration_seconds_sum{{route=\"{}\",method=\"{}\"}} {}", route, method, stats.latency_sum);
+            let _ = writeln!(out, "http_request_duration_seconds_count{{route=\"{}\",method=\"{}\"}} {}", route, method, stats.requests);
+        }
+
+        out
+    }
+}
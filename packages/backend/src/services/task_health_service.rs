@@ -0,0 +1,201 @@
+// File Path: src/services/task_health_service.rs
+// Version: 1.0.0
+// Description: Heartbeats for periodic background tasks, so a loop that died or hung shows
+// up as stalled instead of failing silently.
+//
+// Key Features:
+// - Each task registers with its expected interval and beats on every loop iteration
+// - A task is stalled when it has not beaten within STALL_FACTOR × its interval
+// - Dropping the heartbeat (task returned, panicked or was aborted) marks the task stopped
+// - Reports process uptime alongside the task list
+//
+// Usage Guide:
+// ```
+// let heartbeat = task_health.register("execution_cleanup", Duration::from_secs(3600));
+// tokio::spawn(async move {
+//     loop {
+//         interval.tick().await;
+//         heartbeat.beat();
+//         // ... work
+//     }
+// });
+// ```
+//
+// Change Log:
+// - 1.0.0: Initial implementation
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{error, warn};
+
+/// Missed intervals after which a task counts as stalled
+const STALL_FACTOR: u32 = 2;
+
+// =============================================================================
+// SECTION 1: TYPE DEFINITIONS
+// =============================================================================
+
+/// Health of one background task as reported by GET /api/admin/tasks
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub expected_interval_secs: u64,
+    /// Last loop iteration; `None` until the first one
+    pub last_tick: Option<DateTime<Utc>>,
+    pub ticks: u64,
+    /// False once the task's loop has exited
+    pub running: bool,
+    /// Running and ticked (or started) within `STALL_FACTOR` × the expected interval
+    pub healthy: bool,
+}
+
+#[derive(Debug)]
+struct TaskRecord {
+    interval: Duration,
+    registered: Instant,
+    last_tick: Option<(Instant, DateTime<Utc>)>,
+    ticks: u64,
+    running: bool,
+}
+
+// =============================================================================
+// SECTION 2: SERVICE IMPLEMENTATION
+// =============================================================================
+
+/// Registry of background task heartbeats
+#[derive(Debug)]
+pub struct TaskHealthService {
+    started_at: DateTime<Utc>,
+    started: Instant,
+    tasks: Arc<Mutex<BTreeMap<String, TaskRecord>>>,
+}
+
+/// Handle a background task beats on; dropping it marks the task stopped
+#[derive(Debug)]
+pub struct TaskHeartbeat {
+    name: String,
+    tasks: Arc<Mutex<BTreeMap<String, TaskRecord>>>,
+}
+
+impl TaskHealthService {
+    pub fn new() -> Self {
+        Self {
+            started_at: Utc::now(),
+            started: Instant::now(),
+            tasks: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Registers a task expected to beat every `interval`
+    pub fn register(&self, name: &str, interval: Duration) -> TaskHeartbeat {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            name.to_string(),
+            TaskRecord {
+                interval,
+                registered: Instant::now(),
+                last_tick: None,
+                ticks: 0,
+                running: true,
+            },
+        );
+        TaskHeartbeat {
+            name: name.to_string(),
+            tasks: Arc::clone(&self.tasks),
+        }
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Every registered task with its healthy/stalled state
+    pub fn statuses(&self) -> Vec<TaskStatus> {
+        let now = Instant::now();
+        let tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks
+            .iter()
+            .map(|(name, task)| {
+                let since = task.last_tick.map_or(task.registered, |(at, _)| at);
+                let healthy = task.running && now.duration_since(since) <= task.interval * STALL_FACTOR;
+                TaskStatus {
+                    name: name.clone(),
+                    expected_interval_secs: task.interval.as_secs(),
+                    last_tick: task.last_tick.map(|(_, at)| at),
+                    ticks: task.ticks,
+                    running: task.running,
+                    healthy,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for TaskHealthService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskHeartbeat {
+    /// Records one loop iteration
+    pub fn beat(&self) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(task) = tasks.get_mut(&self.name) {
+            task.last_tick = Some((Instant::now(), Utc::now()));
+            task.ticks += 1;
+        }
+    }
+}
+
+impl Drop for TaskHeartbeat {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            error!("Background task '{}' panicked", self.name);
+        } else {
+            warn!("Background task '{}' stopped", self.name);
+        }
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(task) = tasks.get_mut(&self.name) {
+            task.running = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(service: &TaskHealthService, name: &str) -> TaskStatus {
+        service.statuses().into_iter().find(|status| status.name == name).unwrap()
+    }
+
+    #[test]
+    fn missed_heartbeats_stall_a_task_until_its_next_beat() {
+        let service = TaskHealthService::new();
+        let heartbeat = service.register("cleanup", Duration::from_millis(20));
+        assert!(status(&service, "cleanup").healthy);
+
+        // More than STALL_FACTOR intervals without a beat
+        std::thread::sleep(Duration::from_millis(60));
+        let stalled = status(&service, "cleanup");
+        assert!(stalled.running && !stalled.healthy);
+
+        heartbeat.beat();
+        let recovered = status(&service, "cleanup");
+        assert!(recovered.healthy);
+        assert_eq!(recovered.ticks, 1);
+
+        drop(heartbeat);
+        let stopped = status(&service, "cleanup");
+        assert!(!stopped.running && !stopped.healthy);
+    }
+}
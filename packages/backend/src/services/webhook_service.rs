@@ -0,0 +1,335 @@
+// File Path: src/services/webhook_service.rs
+// Version: 1.1.0
+// Description: HTTP webhook delivery service for job events and connection-count thresholds.
+// Lets external systems react to backups and other jobs without holding a WebSocket.
+//
+// Key Features:
+// - POSTs job completion/failure events to configured webhook URLs
+// - Optional notifications when the active connection count crosses thresholds
+// - Retries failed deliveries with exponential backoff
+// - Signs every payload with HMAC-SHA256 when a secret is configured
+//
+// Usage Guide:
+// Configure through environment variables (or pass a WebhookConfig):
+// - WEBHOOK_URLS: comma-separated URLs receiving job events
+// - WEBHOOK_CONNECTION_URLS: comma-separated URLs receiving connection-count events
+// - WEBHOOK_CONNECTION_THRESHOLDS: comma-separated connection counts, e.g. "100,500"
+// - WEBHOOK_SECRET: shared secret used for the X-Webhook-Signature header
+// - WEBHOOK_MAX_RETRIES: retries after a failed delivery (default 3)
+//
+// Receivers verify a delivery by computing HMAC-SHA256 of `<X-Webhook-Timestamp>.<raw body>`
+// with the shared secret and comparing it to `X-Webhook-Signature: sha256=<hex>`. Signing the
+// timestamp lets receivers reject replayed deliveries.
+//
+// Change Log:
+// - 1.1.0: The signature covers the timestamp; settings are read through env_or; WEBHOOK_MAX_RETRIES
+// - 1.0.0: Initial implementation
+
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use chrono::Utc;
+
+use crate::{config::env_or, models::websocket::JobEventPayload};
+
+// =============================================================================
+// SECTION 1: CONFIGURATION
+// =============================================================================
+// Webhook targets, signing secret and retry policy
+
+/// Configuration for webhook delivery
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// URLs that receive job completion and failure events
+    pub job_event_urls: Vec<String>,
+    /// URLs that receive connection-count threshold events
+    pub connection_urls: Vec<String>,
+    /// Connection counts that trigger a notification when crossed in either direction
+    pub connection_thresholds: Vec<usize>,
+    /// Shared secret for the HMAC-SHA256 signature header
+    pub secret: Option<String>,
+    /// Number of retries after the first failed attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each subsequent attempt
+    pub retry_backoff: Duration,
+    /// Timeout for a single delivery attempt
+    pub request_timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            job_event_urls: env_list("WEBHOOK_URLS"),
+            connection_urls: env_list("WEBHOOK_CONNECTION_URLS"),
+            connection_thresholds: env_list("WEBHOOK_CONNECTION_THRESHOLDS")
+                .iter()
+                .filter_map(|t| t.parse().ok())
+                .collect(),
+            secret: Some(env_or("WEBHOOK_SECRET", String::new())).filter(|s| !s.is_empty()),
+            max_retries: env_or("WEBHOOK_MAX_RETRIES", 3),
+            retry_backoff: Duration::from_secs(1),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Reads a comma-separated environment variable into a list of trimmed values
+fn env_list(name: &str) -> Vec<String> {
+    env_or(name, String::new())
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+// =============================================================================
+// SECTION 2: PAYLOADS
+// =============================================================================
+// Bodies POSTed to webhook receivers
+
+/// Body of a webhook delivery
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A job reached a terminal status (completed or failed)
+    JobEvent { job: JobEventPayload },
+    /// The active connection count crossed a configured threshold
+    ConnectionThreshold {
+        threshold: usize,
+        count: usize,
+        direction: String,
+    },
+}
+
+impl WebhookEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::JobEvent { .. } => "job_event",
+            Self::ConnectionThreshold { .. } => "connection_threshold",
+        }
+    }
+}
+
+// =============================================================================
+// SECTION 3: SERVICE IMPLEMENTATION
+// =============================================================================
+// Event filtering, signing and delivery with retries
+
+/// Service delivering webhook notifications in background tasks
+#[derive(Debug, Clone)]
+pub struct WebhookService {
+    client: Client,
+    config: WebhookConfig,
+}
+
+impl WebhookService {
+    /// Creates a new webhook service (uses environment defaults if config is None)
+    pub fn new(config: Option<WebhookConfig>) -> Self {
+        let config = config.unwrap_or_default();
+
+        info!(
+            job_event_urls = config.job_event_urls.len(),
+            connection_urls = config.connection_urls.len(),
+            signed = config.secret.is_some(),
+            "Initializing webhook service"
+        );
+
+        Self {
+            client: Client::new(),
+            config,
+        }
+    }
+
+    /// Notifies job event webhooks when a job completes or fails
+    pub fn notify_job_event(&self, job_event: &JobEventPayload) {
+        if self.config.job_event_urls.is_empty() {
+            return;
+        }
+
+        if !matches!(job_event.status.as_str(), "completed" | "failed") {
+            return;
+        }
+
+        let event = WebhookEvent::JobEvent { job: job_event.clone() };
+        self.dispatch(&self.config.job_event_urls, event);
+    }
+
+    /// Notifies connection webhooks for every threshold crossed between two counts
+    pub fn notify_connection_count(&self, previous: usize, current: usize) {
+        if self.config.connection_urls.is_empty() || previous == current {
+            return;
+        }
+
+        for &threshold in &self.config.connection_thresholds {
+            let direction = if previous < threshold && current >= threshold {
+                "above"
+            } else if previous >= threshold && current < threshold {
+                "below"
+            } else {
+                continue;
+            };
+
+            let event = WebhookEvent::ConnectionThreshold {
+                threshold,
+                count: current,
+                direction: direction.to_string(),
+            };
+            self.dispatch(&self.config.connection_urls, event);
+        }
+    }
+
+    /// Spawns one delivery task per URL
+    fn dispatch(&self, urls: &[String], event: WebhookEvent) {
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize webhook event: {}", e);
+                return;
+            }
+        };
+
+        for url in urls {
+            let service = self.clone();
+            let url = url.clone();
+            let body = body.clone();
+            let event_name = event.name();
+            tokio::spawn(async move {
+                service.deliver(&url, event_name, body).await;
+            });
+        }
+    }
+
+    /// Delivers a single payload, retrying with exponential backoff
+    async fn deliver(&self, url: &str, event_name: &str, body: Vec<u8>) {
+        let mut backoff = self.config.retry_backoff;
+
+        for attempt in 0..=self.config.max_retries {
+            let timestamp = Utc::now().to_rfc3339();
+            let signature = self.sign(&timestamp, &body);
+            let mut request = self.client
+                .post(url)
+                .timeout(self.config.request_timeout)
+                .header("Content-Type", "application/json")
+                .header("X-Webhook-Event", event_name)
+                .header("X-Webhook-Timestamp", timestamp.as_str())
+                .body(body.clone());
+
+            if let Some(signature) = &signature {
+                request = request.header("X-Webhook-Signature", signature.as_str());
+            }
+
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    debug!("Webhook {} delivered to {} (attempt {})", event_name, url, attempt + 1);
+                    return;
+                }
+                Ok(response) => {
+                    warn!("Webhook {} to {} returned HTTP {} (attempt {})", event_name, url, response.status(), attempt + 1);
+                }
+                Err(e) => {
+                    warn!("Webhook {} to {} failed: {} (attempt {})", event_name, url, e, attempt + 1);
+                }
+            }
+
+            if attempt < self.config.max_retries {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+
+        warn!("Giving up on webhook {} to {} after {} attempts", event_name, url, self.config.max_retries + 1);
+    }
+
+    /// Computes the `sha256=<hex>` signature header value for a timestamp and body
+    fn sign(&self, timestamp: &str, body: &[u8]) -> Option<String> {
+        let secret = self.config.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+        mac.update(timestamp.as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        Some(format!("sha256={}", hex::encode(mac.finalize().into_bytes())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::post, Json, Router};
+    use std::sync::{Arc, Mutex};
+
+    fn config() -> WebhookConfig {
+        WebhookConfig {
+            job_event_urls: Vec::new(),
+            connection_urls: Vec::new(),
+            connection_thresholds: Vec::new(),
+            secret: None,
+            max_retries: 0,
+            retry_backoff: Duration::from_millis(10),
+            request_timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let service = WebhookService::new(Some(WebhookConfig { secret: Some("topsecret".to_string()), ..config() }));
+        let body = br#"{"event":"ping"}"#;
+        assert_eq!(
+            service.sign("2025-01-01T00:00:00+00:00", body).as_deref(),
+            Some("sha256=11df0fc9273ceba2c0b9e4651b26491b38dbfc7250e896d1e37528cc27fa5a94")
+        );
+        assert_ne!(service.sign("2025-01-01T00:00:01+00:00", body), service.sign("2025-01-01T00:00:00+00:00", body));
+
+        let unsigned = WebhookService::new(Some(config()));
+        assert_eq!(unsigned.sign("2025-01-01T00:00:00+00:00", body), None);
+    }
+
+    #[tokio::test]
+    async fn connection_events_fire_only_when_a_threshold_is_crossed() {
+        let received: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+        let receiver = Router::new()
+            .route(
+                "/hook",
+                post(|State(received): State<Arc<Mutex<Vec<serde_json::Value>>>>, Json(event): Json<serde_json::Value>| async move {
+                    received.lock().unwrap().push(event);
+                }),
+            )
+            .with_state(Arc::clone(&received));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, receiver).await });
+
+        let service = WebhookService::new(Some(WebhookConfig {
+            connection_urls: vec![url],
+            connection_thresholds: vec![2],
+            ..config()
+        }));
+        for (previous, current) in [(0, 1), (1, 2), (2, 3), (3, 2), (2, 1), (1, 0)] {
+            service.notify_connection_count(previous, current);
+        }
+
+        let events = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let events = received.lock().unwrap().clone();
+                if events.len() >= 2 {
+                    break events;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(received.lock().unwrap().len(), 2);
+
+        let mut crossings: Vec<(String, u64)> = events
+            .iter()
+            .map(|event| (event["direction"].as_str().unwrap().to_string(), event["count"].as_u64().unwrap()))
+            .collect();
+        crossings.sort();
+        assert_eq!(crossings, [("above".to_string(), 2), ("below".to_string(), 1)]);
+        assert!(events.iter().all(|event| event["event"] == "connection_threshold" && event["threshold"] == 2));
+    }
+}
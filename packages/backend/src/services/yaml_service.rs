@@ -1,5 +1,5 @@
 // File Path: backend/src/services/yaml_service.rs
-// Version: 3.1.2
+// Version: 3.21.2
 // Description: YAML validation and schema management service. Handles loading JSON schemas, validating YAML data against them, and providing access to validated data for API consumption.
 // Key Features:
 // - Loads JSON schemas from a specified directory and compiles them for validation.
@@ -13,7 +13,57 @@
 // 3. Initialize the service with both schema and data directory paths.
 // 4. Use get_yaml_data() or validate_yaml_data() with a schema_name to load and validate data.
 // 5. Handle ApiResult to manage errors like file not found or validation failures.
+// 6. Use write_yaml_data() to persist edits; inventory-shaped documents are validated per-device.
+//    preview_yaml_data() validates and returns the diff without writing.
+//    write_yaml_patch() edits one top-level entry in place, keeping the rest of the file's text.
+//    Concurrent cache misses for the same file share a single parse and validation.
+// 7. Use reload_schemas() to recompile schemas; oversized or excess schema files are skipped and reported.
+//    The new set is built aside and swapped in whole; in-flight validations keep the set they started with.
+//    Schemas that fail to compile are listed by list_failed_schemas() and make dependent requests fail with 503.
+// 8. Use form_schema() to get a simplified field descriptor for driving editor forms.
+// 9. Use validate_all() to check every data file against its schema (e.g. before a deploy).
+//    A data file belongs to a schema when its file stem matches the schema name, anywhere
+//    under the data directory (navigation.yaml, inventories/inventory.yaml).
+//    validate_files() does the same for a chosen list of files against one schema.
+//    Both read and validate at most YAML_SCAN_CONCURRENCY (default 8) files at once.
+// 10. Use raw_schema() to get a schema's source exactly as loaded, with a content ETag.
+// 11. Validation failures are ApiError::SchemaValidation, one ValidationIssue per error; for
+//     inventory documents each issue carries the location, category, index and host name of the device.
+// 12. Use get_typed_data() to load a document that must have a schema, deserialized into a model;
+//     a document that passes the schema but does not fit the model is reported as a ValidationIssue.
+// 13. Set SCHEMA_REMOTE_URL to fetch schemas from a central service on startup and reload. The base URL
+//     serves `index.json` (a JSON array of schema file names) and each listed file. Fetched schemas are
+//     cached under SCHEMA_REMOTE_CACHE_DIR (default `<schema_dir>/remote`) and override local files of
+//     the same name; when the service is unreachable the last cached copy, then the local files, are used.
+// 14. Use validate_value() to check an in-memory value (e.g. a report run's result) against a loaded schema;
+//     require_schema() checks up front that a schema is loaded and compiled.
+// 15. Use data_files() to list the YAML files directly inside a data subdirectory (e.g. `sidebars`).
 // Change Log:
+// - 3.21.2 (2026-10-16): Single-flight load locks are dropped once no caller holds or waits on them.
+// - 3.21.1 (2026-10-16): The device-level diff moved to inventory_diff, shared with the validation benchmark.
+// - 3.21.0 (2026-10-16): Added write_yaml_patch(): merge-patches one top-level entry in place, keeping comments and key order.
+// - 3.20.2 (2026-10-16): Incremental validation issues carry the device's full-document path, not its partial index.
+// - 3.20.1 (2026-10-16): DiffEntry and DiffOp deserialize, so recorded diffs can be read back.
+// - 3.20.0 (2026-10-16): Added data_files() listing the YAML files of a data subdirectory.
+// - 3.19.0 (2026-10-16): Added validate_value() and require_schema() for values that are not data files.
+// - 3.18.0 (2026-10-16): Optional remote schema source with a local cache and fallback to local files.
+// - 3.17.0 (2026-10-16): Scan concurrency is configurable through YAML_SCAN_CONCURRENCY and shared with directory listings.
+// - 3.16.0 (2026-10-16): Added get_typed_data() for schema-required, typed documents.
+// - 3.15.0 (2026-10-16): Validation failures return structured issues; inventory issues name the failing device.
+// - 3.14.0 (2026-10-16): Schemas live in an immutable SchemaSet swapped by pointer; reloads no longer wait on or stall validations.
+// - 3.13.0 (2026-10-16): Added validate_files(): concurrent per-file validation of selected files against one schema.
+// - 3.12.0 (2026-10-16): Loaded schema sources are kept and served through raw_schema().
+// - 3.11.0 (2026-10-16): Added data_modified() exposing a data file's modification time.
+// - 3.10.0 (2026-10-16): Added validate_all(): validates every schema's data files concurrently and reports per file.
+// - 3.9.0 (2026-10-16): Added form_schema(): cached form descriptors derived from a schema's fields.
+// - 3.8.0 (2026-10-16): Schemas that fail to compile are tracked; requests needing them get a 503 with the compile error.
+// - 3.7.0 (2026-10-16): Concurrent loads of the same uncached file are collapsed into one parse.
+// - 3.6.0 (2026-10-16): Writes report a structural diff against the existing file; added preview_yaml_data().
+// - 3.5.0 (2026-10-16): resolve_yaml_path() rejects paths that escape the data directory.
+// - 3.4.0 (2026-10-16): write_yaml_data() writes through a temporary file and renames it into place.
+// - 3.3.1 (2026-10-16): reload_schemas() reports how many cached documents were invalidated.
+// - 3.3.0 (2026-10-16): Added schema count/size limits, YamlServiceConfig and reload_schemas().
+// - 3.2.0 (2026-10-16): Added validated document cache and write_yaml_data() with incremental per-device validation.
 // - 3.1.2 (2025-09-14): Fixed borrow error and updated constructor to accept data directory.
 // - 3.1.1 (2025-09-14): Fixed schema name extraction to handle .schema.json files properly.
 // - 3.1.0 (2025-09-13): Reintroduced jsonschema for proper validation, removed basic_validation placeholder.
@@ -27,20 +77,248 @@
 // This section includes necessary imports and defines the YamlService struct,
 // which holds schema and data directories along with compiled JSON schemas.
 
-use crate::models::{ApiError, ApiResult};
-use serde_json::Value;
+use super::inventory_diff::changed_devices;
+use crate::config::env_or;
+use crate::models::{ApiError, ApiResult, ValidationIssue};
+use futures_util::{stream, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
-    path::{Path, PathBuf},
+    collections::{BTreeMap, HashMap},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
-use tokio::fs;
-use tracing::{info, warn};
+use tokio::{
+    fs,
+    sync::{Mutex, RwLock},
+};
+use tracing::{debug, info, warn};
 use jsonschema::{Draft, JSONSchema};
 
+/// Limits applied while loading schemas
+#[derive(Debug, Clone)]
+pub struct YamlServiceConfig {
+    /// Maximum number of schemas compiled from the schema directory
+    pub max_schema_count: usize,
+    /// Maximum size in bytes of a single schema file
+    pub max_schema_size: u64,
+    /// Files read and validated at once by validate_all(), validate_files() and
+    /// directory listings; bounds open file descriptors on large data trees.
+    /// Set with YAML_SCAN_CONCURRENCY.
+    pub scan_concurrency: usize,
+    /// Base URL serving `index.json` and the schema files it lists; `None` uses local
+    /// schemas only. Set with SCHEMA_REMOTE_URL.
+    pub remote_schema_url: Option<String>,
+    /// Directory fetched schemas are cached in; defaults to `<schema_dir>/remote`.
+    /// Set with SCHEMA_REMOTE_CACHE_DIR.
+    pub remote_schema_cache_dir: Option<PathBuf>,
+    /// Timeout for each remote schema request. Set with SCHEMA_REMOTE_TIMEOUT_SECS.
+    pub remote_schema_timeout: Duration,
+}
+
+impl Default for YamlServiceConfig {
+    fn default() -> Self {
+        Self {
+            max_schema_count: 256,
+            max_schema_size: 1024 * 1024, // 1MB
+            scan_concurrency: env_or("YAML_SCAN_CONCURRENCY", 8).max(1),
+            remote_schema_url: std::env::var("SCHEMA_REMOTE_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            remote_schema_cache_dir: std::env::var("SCHEMA_REMOTE_CACHE_DIR")
+                .ok()
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+            remote_schema_timeout: Duration::from_secs(env_or("SCHEMA_REMOTE_TIMEOUT_SECS", 10)),
+        }
+    }
+}
+
 pub struct YamlService {
     schema_dir: PathBuf,
     data_dir: PathBuf,
-    schemas: HashMap<String, JSONSchema>,
+    config: YamlServiceConfig,
+    /// Current schema set; readers clone the `Arc` and never hold the lock while validating
+    schemas: RwLock<Arc<SchemaSet>>,
+    /// Last validated document per resolved YAML path
+    documents: RwLock<HashMap<PathBuf, CachedDocument>>,
+    /// Per-path lock held while a document is parsed, so concurrent misses wait for one load
+    loads: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+    /// Form descriptors derived from schemas, dropped on reload
+    form_schemas: RwLock<HashMap<String, FormSchema>>,
+}
+
+/// Everything produced by one schema load, replaced as a unit on reload
+#[derive(Default)]
+struct SchemaSet {
+    compiled: HashMap<String, JSONSchema>,
+    /// Source of each compiled schema, as read
+    raw: HashMap<String, RawSchema>,
+    /// Schemas whose file exists but did not compile, keyed by schema name
+    failed: HashMap<String, FailedSchema>,
+}
+
+/// A schema document as it was read from disk
+#[derive(Debug, Clone)]
+pub struct RawSchema {
+    pub source: Arc<str>,
+    /// Strong ETag (quoted SHA-256 of the source)
+    pub etag: String,
+}
+
+impl RawSchema {
+    fn new(source: String) -> Self {
+        let etag = format!("\"{}\"", hex::encode(Sha256::digest(source.as_bytes())));
+        Self {
+            source: source.into(),
+            etag,
+        }
+    }
+}
+
+/// A validated document together with the file modification time it was read at
+struct CachedDocument {
+    modified: Option<SystemTime>,
+    data: Value,
+}
+
+/// A schema file that was not loaded, with the reason
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedSchema {
+    pub file: String,
+    pub reason: String,
+}
+
+/// A schema that was found but could not be parsed or compiled
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedSchema {
+    pub name: String,
+    pub file: String,
+    pub error: String,
+}
+
+/// Validation outcome for one data file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileValidation {
+    /// Path relative to the data directory
+    pub file: String,
+    pub schema: String,
+    pub valid: bool,
+    pub errors: Vec<String>,
+}
+
+/// Result of validating every data file against its schema
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationReport {
+    /// True when every file is valid and no schema failed to compile
+    pub passed: bool,
+    pub files: Vec<FileValidation>,
+    /// Schemas that failed to compile; their data files cannot be checked
+    pub failed_schemas: Vec<FailedSchema>,
+}
+
+/// Simplified description of a schema's fields for rendering an editor form
+#[derive(Debug, Clone, Serialize)]
+pub struct FormSchema {
+    pub schema: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    /// For array documents, the type of each item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+    /// Fields of the document, or of each item for arrays of objects
+    pub fields: Vec<FormField>,
+}
+
+/// A single editable field
+#[derive(Debug, Clone, Serialize)]
+pub struct FormField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Allowed values, when the schema restricts the field to an enum
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<Value>>,
+    /// For array fields, the type of each item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+    /// Nested fields of objects, or of each item for arrays of objects
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FormField>,
+}
+
+/// Summary of a schema load or reload
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SchemaLoadReport {
+    pub loaded: Vec<String>,
+    pub skipped: Vec<SkippedSchema>,
+    /// Number of cached YAML documents dropped by the reload
+    pub invalidated_documents: usize,
+    /// Outcome of the remote fetch, when a remote source is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<RemoteSchemaReport>,
+}
+
+/// Result of fetching schemas from the remote source
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteSchemaReport {
+    pub url: String,
+    /// Schema files fetched; 0 when the fetch failed
+    pub fetched: usize,
+    /// Why the fetch failed; cached or local schemas were used instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How a document was validated before being written
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ValidationMode {
+    /// The whole document was validated against its schema
+    Full,
+    /// Only devices that differ from the cached document were validated
+    Incremental { devices_validated: usize },
+    /// No schema is registered for the document
+    Skipped,
+}
+
+/// Result of a successful write_yaml_data() or preview_yaml_data() call
+#[derive(Debug, Clone, Serialize)]
+pub struct WriteOutcome {
+    pub path: String,
+    pub validation: ValidationMode,
+    /// Whether the document was written (false for previews)
+    pub written: bool,
+    /// Changes relative to the file on disk
+    pub diff: Vec<DiffEntry>,
+}
+
+/// A single structural change between two documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffEntry {
+    /// JSON pointer to the changed value (e.g. `/locations/dc1/routers/0`)
+    pub path: String,
+    pub op: DiffOp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// Kind of change recorded in a DiffEntry
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Added,
+    Removed,
+    Changed,
 }
 
 // ====================================================
@@ -50,7 +328,11 @@ pub struct YamlService {
 // from the specified directory.
 
 impl YamlService {
-    pub async fn new(schema_dir: &str, data_dir: &str) -> ApiResult<Self> {
+    pub async fn new(
+        schema_dir: &str,
+        data_dir: &str,
+        config: Option<YamlServiceConfig>,
+    ) -> ApiResult<Self> {
         let schema_path = PathBuf::from(schema_dir);
         let data_path = PathBuf::from(data_dir);
         
@@ -68,53 +350,204 @@ impl YamlService {
             )));
         }
 
-        let mut service = Self {
+        let service = Self {
             schema_dir: schema_path,
             data_dir: data_path,
-            schemas: HashMap::new(),
+            config: config.unwrap_or_default(),
+            schemas: RwLock::new(Arc::default()),
+            documents: RwLock::new(HashMap::new()),
+            loads: Mutex::new(HashMap::new()),
+            form_schemas: RwLock::new(HashMap::new()),
         };
 
-        service.load_schemas().await?;
+        service.reload_schemas().await?;
         Ok(service)
     }
 
-    async fn load_schemas(&mut self) -> ApiResult<()> {
+    /// Recompiles all schemas from the schema directory and swaps them in.
+    ///
+    /// The new set is compiled without holding any lock, then replaces the old one in a
+    /// single pointer swap: a concurrent request sees either the old or the new set, never
+    /// a mix. Cached documents are dropped since they were validated against the old schemas.
+    pub async fn reload_schemas(&self) -> ApiResult<SchemaLoadReport> {
+        let (schemas, mut report) = self.load_schemas().await?;
+
+        *self.schemas.write().await = Arc::new(schemas);
+        self.form_schemas.write().await.clear();
+
+        let mut documents = self.documents.write().await;
+        report.invalidated_documents = documents.len();
+        documents.clear();
+        drop(documents);
+
+        info!(
+            "Schemas loaded: {} loaded, {} skipped, {} cached documents invalidated",
+            report.loaded.len(),
+            report.skipped.len(),
+            report.invalidated_documents
+        );
+        Ok(report)
+    }
+
+    /// The schema set in effect now
+    ///
+    /// Callers keep using the returned set even if a reload swaps in a new one meanwhile.
+    async fn schema_set(&self) -> Arc<SchemaSet> {
+        Arc::clone(&*self.schemas.read().await)
+    }
+
+    async fn load_schemas(&self) -> ApiResult<(SchemaSet, SchemaLoadReport)> {
         info!("Loading schemas from: {}", self.schema_dir.display());
-        
-        let mut entries = fs::read_dir(&self.schema_dir)
-            .await
-            .map_err(ApiError::IoError)?;
+        let mut report = SchemaLoadReport::default();
 
-        while let Some(entry) = entries.next_entry().await.map_err(ApiError::IoError)? {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                    // Extract base name by removing ".schema" suffix if present
-                    let schema_name = if stem.ends_with(".schema") {
-                        stem.trim_end_matches(".schema").to_string()
-                    } else {
-                        stem.to_string()
-                    };
-                    
-                    match self.load_schema(&path).await {
-                        Ok(schema) => {
-                            // Clone schema_name to avoid borrow after move
-                            let schema_name_clone = schema_name.clone();
-                            self.schemas.insert(schema_name, schema);
-                            info!("Loaded schema: {} from {}", schema_name_clone, path.display());
-                        }
-                        Err(e) => {
-                            warn!("Failed to load schema {}: {}", schema_name, e);
-                        }
-                    }
+        // Keyed by file name so remote schemas replace local files of the same name;
+        // sorted so the schemas kept under the count limit are deterministic
+        let mut schema_files = BTreeMap::new();
+        list_schema_files(&self.schema_dir, &mut schema_files).await?;
+
+        if let Some(url) = &self.config.remote_schema_url {
+            let cache_dir = self.remote_cache_dir();
+            let fetched = self.fetch_remote_schemas(url, &cache_dir).await;
+            if let Err(e) = &fetched {
+                warn!("Failed to fetch remote schemas from {}: {}; using cached or local schemas", url, e);
+            }
+            report.remote = Some(RemoteSchemaReport {
+                url: url.clone(),
+                fetched: *fetched.as_ref().unwrap_or(&0),
+                error: fetched.err(),
+            });
+            if cache_dir.exists() {
+                list_schema_files(&cache_dir, &mut schema_files).await?;
+            }
+        }
+
+        let mut schemas = SchemaSet::default();
+
+        for (path, size) in schema_files.into_values() {
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            // Extract base name by removing ".schema" suffix if present
+            let schema_name = if stem.ends_with(".schema") {
+                stem.trim_end_matches(".schema").to_string()
+            } else {
+                stem.to_string()
+            };
+
+            let skip_reason = if schemas.compiled.len() >= self.config.max_schema_count {
+                Some(format!("Schema count limit of {} reached", self.config.max_schema_count))
+            } else if size > self.config.max_schema_size {
+                Some(format!(
+                    "File size {} bytes exceeds limit of {} bytes",
+                    size, self.config.max_schema_size
+                ))
+            } else {
+                None
+            };
+
+            if let Some(reason) = skip_reason {
+                warn!("Skipping schema {}: {}", path.display(), reason);
+                report.skipped.push(SkippedSchema {
+                    file: path.display().to_string(),
+                    reason,
+                });
+                continue;
+            }
+            
+            match self.load_schema(&path).await {
+                Ok((compiled, raw)) => {
+                    info!("Loaded schema: {} from {}", schema_name, path.display());
+                    report.loaded.push(schema_name.clone());
+                    schemas.raw.insert(schema_name.clone(), raw);
+                    schemas.compiled.insert(schema_name, compiled);
+                }
+                Err(e) => {
+                    warn!("Failed to load schema {}: {}", schema_name, e);
+                    report.skipped.push(SkippedSchema {
+                        file: path.display().to_string(),
+                        reason: e.to_string(),
+                    });
+                    schemas.failed.insert(schema_name.clone(), FailedSchema {
+                        name: schema_name,
+                        file: path.display().to_string(),
+                        error: e.to_string(),
+                    });
                 }
             }
         }
 
-        Ok(())
+        Ok((schemas, report))
+    }
+
+    fn remote_cache_dir(&self) -> PathBuf {
+        self.config
+            .remote_schema_cache_dir
+            .clone()
+            .unwrap_or_else(|| self.schema_dir.join("remote"))
+    }
+
+    /// Downloads every schema listed in `<url>/index.json` into the cache directory
+    ///
+    /// All files are fetched before the cache is touched, so a failed fetch leaves the
+    /// previous cache intact. Cached files no longer listed are removed.
+    async fn fetch_remote_schemas(&self, url: &str, cache_dir: &Path) -> Result<usize, String> {
+        let client = reqwest::Client::builder()
+            .timeout(self.config.remote_schema_timeout)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let base = url.trim_end_matches('/');
+
+        let get = |url: String| {
+            let client = client.clone();
+            async move {
+                let response = client.get(&url).send().await.map_err(|e| format!("{}: {}", url, e))?;
+                if !response.status().is_success() {
+                    return Err(format!("{}: HTTP {}", url, response.status()));
+                }
+                response.bytes().await.map_err(|e| format!("{}: {}", url, e))
+            }
+        };
+
+        let index: Vec<String> = serde_json::from_slice(&get(format!("{}/index.json", base)).await?)
+            .map_err(|e| format!("Invalid index.json: {}", e))?;
+
+        let mut files = Vec::with_capacity(index.len());
+        for name in index {
+            let valid_name = name.ends_with(".json")
+                && !name.starts_with('.')
+                && !name.contains(['/', '\\']);
+            if !valid_name {
+                return Err(format!("Invalid schema file name in index.json: {:?}", name));
+            }
+            let body = get(format!("{}/{}", base, name)).await?;
+            if body.len() as u64 > self.config.max_schema_size {
+                return Err(format!(
+                    "{} is {} bytes, over the limit of {} bytes",
+                    name, body.len(), self.config.max_schema_size
+                ));
+            }
+            files.push((name, body));
+        }
+
+        fs::create_dir_all(cache_dir).await.map_err(|e| e.to_string())?;
+        let mut stale = BTreeMap::new();
+        list_schema_files(cache_dir, &mut stale).await.map_err(|e| e.to_string())?;
+        for (name, body) in &files {
+            stale.remove(name);
+            let tmp_path = cache_dir.join(format!(".{}.tmp", name));
+            fs::write(&tmp_path, body).await.map_err(|e| e.to_string())?;
+            fs::rename(&tmp_path, cache_dir.join(name)).await.map_err(|e| e.to_string())?;
+        }
+        for (path, _) in stale.into_values() {
+            let _ = fs::remove_file(path).await;
+        }
+
+        info!("Fetched {} schema(s) from {}", files.len(), base);
+        Ok(files.len())
     }
 
-    async fn load_schema(&self, schema_path: &Path) -> ApiResult<JSONSchema> {
+    async fn load_schema(&self, schema_path: &Path) -> ApiResult<(JSONSchema, RawSchema)> {
         let content = fs::read_to_string(schema_path)
             .await
             .map_err(ApiError::IoError)?;
@@ -127,7 +560,7 @@ impl YamlService {
             .compile(&schema_value)
             .map_err(|e| ApiError::ValidationError(format!("Schema compilation failed: {}", e)))?;
 
-        Ok(schema)
+        Ok((schema, RawSchema::new(content)))
     }
 }
 
@@ -143,6 +576,9 @@ impl YamlService {
         schema_name: &str,
         file_path: Option<&str>,
     ) -> ApiResult<Value> {
+        let schemas = self.schema_set().await;
+        ensure_schema_usable(&schemas, schema_name)?;
+
         let yaml_path = self.resolve_yaml_path(schema_name, file_path)?;
         
         if !yaml_path.exists() {
@@ -152,7 +588,48 @@ impl YamlService {
             )));
         }
 
-        let content = fs::read_to_string(&yaml_path)
+        // Serve the cached copy while the file on disk is unchanged
+        let modified = fs::metadata(&yaml_path).await?.modified().ok();
+        if let Some(data) = self.cached_document(&yaml_path, modified).await {
+            return Ok(data);
+        }
+
+        // Single-flight: only one caller parses a given file; the rest wait and
+        // then pick up the result from the cache
+        let load_lock = self
+            .loads
+            .lock()
+            .await
+            .entry(yaml_path.clone())
+            .or_default()
+            .clone();
+        let result = {
+            let _load_guard = load_lock.lock().await;
+            self.load_document(&schemas, schema_name, &yaml_path, modified).await
+        };
+
+        // Drop the path's lock once no other caller holds or waits on it
+        let mut loads = self.loads.lock().await;
+        if Arc::strong_count(&load_lock) == 2 {
+            loads.remove(&yaml_path);
+        }
+
+        result
+    }
+
+    /// Parses and validates a document unless a concurrent load already cached it
+    async fn load_document(
+        &self,
+        schemas: &Arc<SchemaSet>,
+        schema_name: &str,
+        yaml_path: &Path,
+        modified: Option<SystemTime>,
+    ) -> ApiResult<Value> {
+        if let Some(data) = self.cached_document(yaml_path, modified).await {
+            return Ok(data);
+        }
+
+        let content = fs::read_to_string(yaml_path)
             .await
             .map_err(ApiError::IoError)?;
 
@@ -160,46 +637,647 @@ impl YamlService {
             .map_err(|e| ApiError::YamlParseError(e.to_string()))?;
 
         // Validate against schema
-        if let Some(schema) = self.schemas.get(schema_name) {
-            schema
-                .validate(&yaml_data)
-                .map_err(|errors| {
-                    let error_messages: Vec<String> = errors
-                        .map(|e| e.to_string())
-                        .collect();
-                    ApiError::ValidationError(format!("Schema validation failed: {:?}", error_messages))
-                })?;
+        if let Some(schema) = schemas.compiled.get(schema_name) {
+            validate_document(schema, &yaml_data)?;
+        }
+
+        // Only cache if no reload replaced the schemas meanwhile; the reload clears the
+        // cache after swapping, so checking under the documents lock is enough
+        let mut documents = self.documents.write().await;
+        if Arc::ptr_eq(schemas, &*self.schemas.read().await) {
+            documents.insert(yaml_path.to_path_buf(), CachedDocument { modified, data: yaml_data.clone() });
         }
 
         Ok(yaml_data)
     }
 
+    /// Loads a document whose schema must exist and deserializes it into `T`
+    ///
+    /// Unlike get_yaml_data(), a missing schema is an error rather than skipping validation.
+    pub async fn get_typed_data<T: DeserializeOwned>(
+        &self,
+        schema_name: &str,
+        file_path: Option<&str>,
+    ) -> ApiResult<T> {
+        let schemas = self.schema_set().await;
+        if !schemas.compiled.contains_key(schema_name) {
+            return Err(schema_missing_error(&schemas, schema_name));
+        }
+
+        let data = self.get_yaml_data(schema_name, file_path).await?;
+        serde_json::from_value(data).map_err(|e| {
+            ApiError::SchemaValidation(vec![ValidationIssue {
+                path: String::new(),
+                message: e.to_string(),
+                location: None,
+                category: None,
+                device_index: None,
+                device_hostname: None,
+                field: None,
+            }])
+        })
+    }
+
+    /// Returns the cached document if it was read at the given modification time
+    async fn cached_document(&self, yaml_path: &Path, modified: Option<SystemTime>) -> Option<Value> {
+        let documents = self.documents.read().await;
+        let cached = documents.get(yaml_path)?;
+        if modified.is_some() && cached.modified == modified {
+            debug!("Serving cached YAML data for {}", yaml_path.display());
+            Some(cached.data.clone())
+        } else {
+            None
+        }
+    }
+
     pub async fn validate_yaml_data(
         &self,
         schema_name: &str,
         file_path: Option<&str>,
     ) -> ApiResult<Value> {
-        let schema = self.schemas.get(schema_name).ok_or_else(|| {
-            ApiError::NotFound(format!("Schema '{}' not found", schema_name))
-        })?;
+        let schemas = self.schema_set().await;
+        let Some(schema) = schemas.compiled.get(schema_name) else {
+            return Err(schema_missing_error(&schemas, schema_name));
+        };
 
         let yaml_data = self.get_yaml_data(schema_name, file_path).await?;
         
         // Perform validation (already done in get_yaml_data, but re-validate for clarity)
-        schema
-            .validate(&yaml_data)
-            .map_err(|errors| {
-                let error_messages: Vec<String> = errors
-                    .map(|e| e.to_string())
-                    .collect();
-                ApiError::ValidationError(format!("Schema validation failed: {:?}", error_messages))
-            })?;
+        validate_document(schema, &yaml_data)?;
         
         Ok(serde_json::json!({
             "valid": true,
             "data": yaml_data
         }))
     }
+
+    /// Validates and writes a document back to its YAML file.
+    ///
+    /// When a validated copy of the file is cached and the new document only
+    /// differs inside the inventory device lists (`locations.<location>.<category>`),
+    /// only the added or modified devices are validated. Any other change falls
+    /// back to validating the whole document. The outcome includes a structural
+    /// diff against the file as it was on disk.
+    pub async fn write_yaml_data(
+        &self,
+        schema_name: &str,
+        file_path: Option<&str>,
+        data: Value,
+    ) -> ApiResult<WriteOutcome> {
+        let content = serde_yaml::to_string(&data)
+            .map_err(|e| ApiError::SerializationError(e.to_string()))?;
+        self.write_document(schema_name, file_path, data, content).await
+    }
+
+    /// Writes a document whose only change is a JSON merge patch applied to one
+    /// top-level entry, editing that entry in place.
+    ///
+    /// `data` is the whole document with the patch already applied. Lines outside the
+    /// patched fields are kept as they are, so comments, key order and quoting survive.
+    /// When the file's layout cannot be edited in place (flow style, anchors, a missing
+    /// entry), the document is serialized whole as write_yaml_data() does.
+    pub async fn write_yaml_patch(
+        &self,
+        schema_name: &str,
+        file_path: Option<&str>,
+        key: &str,
+        patch: &Map<String, Value>,
+        data: Value,
+    ) -> ApiResult<WriteOutcome> {
+        let yaml_path = self.resolve_yaml_path(schema_name, file_path)?;
+        let edited = fs::read_to_string(&yaml_path)
+            .await
+            .ok()
+            .and_then(|source| patch_entry_source(&source, key, patch, &data));
+
+        let content = match edited {
+            Some(content) => content,
+            None => {
+                warn!("Could not patch '{}' in place in {}, rewriting the file", key, yaml_path.display());
+                serde_yaml::to_string(&data).map_err(|e| ApiError::SerializationError(e.to_string()))?
+            }
+        };
+        self.write_document(schema_name, file_path, data, content).await
+    }
+
+    /// Validates `data` and atomically replaces its file with `content`, its YAML text
+    async fn write_document(
+        &self,
+        schema_name: &str,
+        file_path: Option<&str>,
+        data: Value,
+        content: String,
+    ) -> ApiResult<WriteOutcome> {
+        let yaml_path = self.resolve_yaml_path(schema_name, file_path)?;
+        let validation = self.validate_for_write(schema_name, &yaml_path, &data).await?;
+        let diff = diff_values(&read_existing(&yaml_path).await, &data);
+
+        if let Some(parent) = yaml_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Write to a sibling temp file and rename so readers never see a partial file
+        let tmp_path = yaml_path.with_extension("yaml.tmp");
+        fs::write(&tmp_path, content).await?;
+        if let Err(e) = fs::rename(&tmp_path, &yaml_path).await {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(ApiError::IoError(e));
+        }
+
+        let modified = fs::metadata(&yaml_path).await?.modified().ok();
+        self.documents.write().await.insert(
+            yaml_path.clone(),
+            CachedDocument { modified, data },
+        );
+
+        info!("Wrote YAML data to {} ({:?}, {} changes)", yaml_path.display(), validation, diff.len());
+
+        Ok(WriteOutcome {
+            path: yaml_path.display().to_string(),
+            validation,
+            written: true,
+            diff,
+        })
+    }
+
+    /// Validates a document and diffs it against its YAML file without writing.
+    pub async fn preview_yaml_data(
+        &self,
+        schema_name: &str,
+        file_path: Option<&str>,
+        data: Value,
+    ) -> ApiResult<WriteOutcome> {
+        let yaml_path = self.resolve_yaml_path(schema_name, file_path)?;
+        let validation = self.validate_for_write(schema_name, &yaml_path, &data).await?;
+        let diff = diff_values(&read_existing(&yaml_path).await, &data);
+
+        Ok(WriteOutcome {
+            path: yaml_path.display().to_string(),
+            validation,
+            written: false,
+            diff,
+        })
+    }
+
+    /// Validates a document about to be written, incrementally when possible
+    async fn validate_for_write(
+        &self,
+        schema_name: &str,
+        yaml_path: &Path,
+        data: &Value,
+    ) -> ApiResult<ValidationMode> {
+        let schemas = self.schema_set().await;
+        ensure_schema_usable(&schemas, schema_name)?;
+
+        let Some(schema) = schemas.compiled.get(schema_name) else {
+            return Ok(ValidationMode::Skipped);
+        };
+
+        let partial = self
+            .documents
+            .read()
+            .await
+            .get(yaml_path)
+            .and_then(|cached| changed_devices(&cached.data, data));
+
+        match partial {
+            Some((partial, devices_validated)) => {
+                validate_partial(schema, &partial, data)?;
+                Ok(ValidationMode::Incremental { devices_validated })
+            }
+            None => {
+                validate_document(schema, data)?;
+                Ok(ValidationMode::Full)
+            }
+        }
+    }
+}
+
+// ====================================================
+// SECTION: Validation Helpers
+// ====================================================
+// This section contains schema validation helpers, including the device-level
+// issue locations for incremental inventory validation (see `inventory_diff`).
+
+fn validate_document(schema: &JSONSchema, data: &Value) -> ApiResult<()> {
+    validate_partial(schema, data, data)
+}
+
+/// Validates `data`, reporting device positions as they are in `full`
+///
+/// Incremental validation checks a partial inventory whose device lists only hold the
+/// changed devices; indices are looked up by host name in the full document.
+fn validate_partial(schema: &JSONSchema, data: &Value, full: &Value) -> ApiResult<()> {
+    schema.validate(data).map_err(|errors| {
+        ApiError::SchemaValidation(errors.map(|error| validation_issue(&error, data, full)).collect())
+    })
+}
+
+/// Locates a validation error, naming the inventory device it falls in, if any
+fn validation_issue(error: &jsonschema::ValidationError, data: &Value, full: &Value) -> ValidationIssue {
+    let mut issue = ValidationIssue {
+        path: error.instance_path.to_string(),
+        message: error.to_string(),
+        location: None,
+        category: None,
+        device_index: None,
+        device_hostname: None,
+        field: None,
+    };
+
+    let segments = error.instance_path.clone().into_vec();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let ["locations", location, category, index, ref rest @ ..] = segments[..] else {
+        return issue;
+    };
+    let Ok(index) = index.parse::<usize>() else {
+        return issue;
+    };
+
+    let host_name = data
+        .pointer(&format!("/locations/{}/{}/{}", escape_pointer(location), escape_pointer(category), index))
+        .and_then(|device| device.get("host_name"))
+        .and_then(Value::as_str);
+    let full_index = if std::ptr::eq(data, full) {
+        Some(index)
+    } else {
+        host_name.and_then(|host_name| {
+            full.pointer(&format!("/locations/{}/{}", escape_pointer(location), escape_pointer(category)))
+                .and_then(Value::as_array)?
+                .iter()
+                .position(|device| device.get("host_name").and_then(Value::as_str) == Some(host_name))
+        })
+    };
+
+    // A partial document's indices only count changed devices; point at the full document
+    if let Some(full_index) = full_index.filter(|full_index| *full_index != index) {
+        let rest: String = rest.iter().map(|segment| format!("/{}", escape_pointer(segment))).collect();
+        issue.path = format!(
+            "/locations/{}/{}/{}{}",
+            escape_pointer(location),
+            escape_pointer(category),
+            full_index,
+            rest
+        );
+    }
+    issue.location = Some(location.to_string());
+    issue.category = Some(category.to_string());
+    issue.device_index = full_index;
+    issue.device_hostname = host_name.map(str::to_string);
+    issue.field = match (&error.kind, rest.first()) {
+        (_, Some(field)) => Some(field.to_string()),
+        (jsonschema::error::ValidationErrorKind::Required { property }, None) => {
+            property.as_str().map(str::to_string)
+        }
+        _ => None,
+    };
+    issue
+}
+
+// ====================================================
+// SECTION: Source Editing Helpers
+// ====================================================
+// In-place edits of block-style YAML text. Each edit replaces only the lines of the
+// fields it changes; the result is re-parsed and discarded unless it matches the
+// intended document exactly.
+
+/// Applies `patch` to the top-level entry `key` of `source`
+///
+/// `data` is the whole document after the patch; patched fields are rendered from it.
+/// Returns `None` when the edit is not possible or would not produce `data`.
+fn patch_entry_source(source: &str, key: &str, patch: &Map<String, Value>, data: &Value) -> Option<String> {
+    let mut lines: Vec<String> = source.split_inclusive('\n').map(str::to_string).collect();
+    if lines.last().is_some_and(|line| !line.ends_with('\n')) {
+        lines.last_mut()?.push('\n');
+    }
+
+    let start = (0..lines.len()).find(|&i| {
+        yaml_key(&lines[i]).is_some_and(|(indent, name, rest)| indent == 0 && name == key && opens_block(rest))
+    })?;
+    let end = block_end(&lines, start, 0);
+    let indent = mapping_indent(&lines, start + 1, end)?;
+    patch_mapping(&mut lines, start + 1, end, indent, patch, data.get(key)?.as_object()?)?;
+
+    let edited = lines.concat();
+    let parsed: Value = serde_yaml::from_str(&edited).ok()?;
+    (&parsed == data).then_some(edited)
+}
+
+/// Applies `patch` to the mapping whose fields sit at `indent` on lines `start..end`
+///
+/// Returns the new end of the mapping's lines.
+fn patch_mapping(
+    lines: &mut Vec<String>,
+    start: usize,
+    mut end: usize,
+    indent: usize,
+    patch: &Map<String, Value>,
+    merged: &Map<String, Value>,
+) -> Option<usize> {
+    for (name, change) in patch {
+        let field = (start..end).find(|&i| {
+            yaml_key(&lines[i]).is_some_and(|(field_indent, field_name, _)| field_indent == indent && field_name == *name)
+        });
+
+        let Some(field) = field else {
+            // New fields go after the mapping's last line
+            if let Some(value) = merged.get(name) {
+                let rendered = render_field(name, value, indent)?;
+                end += rendered.len();
+                lines.splice(end - rendered.len()..end - rendered.len(), rendered);
+            }
+            continue;
+        };
+        let field_end = block_end(lines, field, indent);
+
+        let replacement = match merged.get(name) {
+            None => Vec::new(),
+            Some(Value::Object(merged_child)) if change.is_object() => {
+                let opens = yaml_key(&lines[field]).is_some_and(|(_, _, rest)| opens_block(rest));
+                match mapping_indent(lines, field + 1, field_end).filter(|_| opens) {
+                    Some(child_indent) => {
+                        let new_end = patch_mapping(lines, field + 1, field_end, child_indent, change.as_object()?, merged_child)?;
+                        end = end + new_end - field_end;
+                        continue;
+                    }
+                    None => render_field(name, &Value::Object(merged_child.clone()), indent)?,
+                }
+            }
+            Some(value) => render_field(name, value, indent)?,
+        };
+        end = end + replacement.len() - (field_end - field);
+        lines.splice(field..field_end, replacement);
+    }
+    Some(end)
+}
+
+/// Renders one `name: value` field as lines indented by `indent`
+fn render_field(name: &str, value: &Value, indent: usize) -> Option<Vec<String>> {
+    let mut field = Map::new();
+    field.insert(name.to_string(), value.clone());
+    let rendered = serde_yaml::to_string(&field).ok()?;
+    let pad = " ".repeat(indent);
+    Some(
+        rendered
+            .split_inclusive('\n')
+            .map(|line| if line.trim().is_empty() { line.to_string() } else { format!("{}{}", pad, line) })
+            .collect(),
+    )
+}
+
+/// End of the lines belonging to the field at `field`, excluding trailing blank lines
+///
+/// A field owns the lines indented deeper than it, and `- ` items at its own indent.
+fn block_end(lines: &[String], field: usize, indent: usize) -> usize {
+    let mut end = field + 1;
+    let mut last_content = field + 1;
+    while end < lines.len() {
+        let line = &lines[end];
+        let trimmed = line.trim_start();
+        if !trimmed.trim().is_empty() {
+            let line_indent = line.len() - trimmed.len();
+            let item = trimmed.starts_with("- ") || trimmed.trim_end() == "-";
+            if line_indent < indent || (line_indent == indent && !item) {
+                break;
+            }
+            last_content = end + 1;
+        }
+        end += 1;
+    }
+    last_content
+}
+
+/// Indent of the mapping fields on lines `start..end`; `None` unless they form a block mapping
+fn mapping_indent(lines: &[String], start: usize, end: usize) -> Option<usize> {
+    let first = lines[start..end].iter().find(|line| {
+        let trimmed = line.trim();
+        !trimmed.is_empty() && !trimmed.starts_with('#')
+    })?;
+    yaml_key(first).map(|(indent, _, _)| indent)
+}
+
+/// Whether the text after a key's colon leaves the value to the following lines
+fn opens_block(rest: &str) -> bool {
+    let rest = rest.trim();
+    rest.is_empty() || rest.starts_with('#')
+}
+
+/// Splits a `key: value` line into its indent, unquoted key and the text after the colon
+fn yaml_key(line: &str) -> Option<(usize, String, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    let indent = line.len() - trimmed.len();
+    let (name, rest) = match trimmed.chars().next()? {
+        '#' | '-' | '\n' | '\r' | '{' | '[' | '&' | '*' | '!' | '?' | '|' | '>' => return None,
+        '"' => {
+            let close = trimmed[1..].find('"')? + 1;
+            if trimmed[1..close].contains('\\') {
+                return None;
+            }
+            (trimmed[1..close].to_string(), trimmed[close + 1..].strip_prefix(':')?)
+        }
+        '\'' => {
+            let close = trimmed[1..].find('\'')? + 1;
+            (trimmed[1..close].to_string(), trimmed[close + 1..].strip_prefix(':')?)
+        }
+        _ => {
+            let colon = trimmed
+                .find(": ")
+                .or_else(|| trimmed.trim_end().strip_suffix(':').map(str::len))?;
+            (trimmed[..colon].trim_end().to_string(), &trimmed[colon + 1..])
+        }
+    };
+    // Anchors, aliases and tags on the value are left to a full rewrite
+    if rest.trim_start().starts_with(['&', '*', '!']) {
+        return None;
+    }
+    Some((indent, name, rest))
+}
+
+// ====================================================
+// SECTION: Diff Helpers
+// ====================================================
+// Structural diff between the document on disk and the one being written.
+
+/// Reads the current contents of a YAML file, or `Null` if it is missing or unparsable
+async fn read_existing(yaml_path: &Path) -> Value {
+    match fs::read_to_string(yaml_path).await {
+        Ok(content) => serde_yaml::from_str(&content).unwrap_or_else(|e| {
+            warn!("Existing YAML at {} is unparsable, diffing against empty: {}", yaml_path.display(), e);
+            Value::Null
+        }),
+        Err(_) => Value::Null,
+    }
+}
+
+/// Computes the changes that turn `old` into `new`
+///
+/// Objects are compared key by key and arrays index by index; any other
+/// differing value is reported as a single change at its path.
+fn diff_values(old: &Value, new: &Value) -> Vec<DiffEntry> {
+    let mut diff = Vec::new();
+    diff_at(String::new(), old, new, &mut diff);
+    diff
+}
+
+fn diff_at(path: String, old: &Value, new: &Value, diff: &mut Vec<DiffEntry>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = format!("{}/{}", path, escape_pointer(key));
+                match new_map.get(key) {
+                    Some(new_value) => diff_at(child, old_value, new_value, diff),
+                    None => diff.push(DiffEntry { path: child, op: DiffOp::Removed, old: Some(old_value.clone()), new: None }),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    let child = format!("{}/{}", path, escape_pointer(key));
+                    diff.push(DiffEntry { path: child, op: DiffOp::Added, old: None, new: Some(new_value.clone()) });
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for index in 0..old_items.len().max(new_items.len()) {
+                let child = format!("{}/{}", path, index);
+                match (old_items.get(index), new_items.get(index)) {
+                    (Some(old_item), Some(new_item)) => diff_at(child, old_item, new_item, diff),
+                    (Some(old_item), None) => diff.push(DiffEntry { path: child, op: DiffOp::Removed, old: Some(old_item.clone()), new: None }),
+                    (None, Some(new_item)) => diff.push(DiffEntry { path: child, op: DiffOp::Added, old: None, new: Some(new_item.clone()) }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Null, new) if path.is_empty() => {
+            // No existing document: the whole new document is an addition
+            diff.push(DiffEntry { path, op: DiffOp::Added, old: None, new: Some(new.clone()) });
+        }
+        (old, new) if old != new => {
+            diff.push(DiffEntry { path, op: DiffOp::Changed, old: Some(old.clone()), new: Some(new.clone()) });
+        }
+        _ => {}
+    }
+}
+
+/// Escapes a key for use as a JSON pointer segment (RFC 6901)
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+// ====================================================
+// SECTION: Bulk Validation
+// ====================================================
+// Validates every data file against its schema, bypassing the document cache.
+
+impl YamlService {
+    /// Validates all data files of every loaded schema, at most
+    /// `scan_concurrency` at a time. Files are read fresh from disk.
+    pub async fn validate_all(&self) -> ApiResult<ValidationReport> {
+        let schemas = self.schema_set().await;
+        let targets: Vec<(String, PathBuf)> = self
+            .yaml_files()
+            .await?
+            .into_iter()
+            .filter_map(|path| {
+                let stem = path.file_stem()?.to_str()?;
+                schemas
+                    .compiled
+                    .keys()
+                    .find(|name| name.as_str() == stem)
+                    .map(|name| (name.clone(), path.clone()))
+            })
+            .collect();
+
+        let concurrency = self.scan_concurrency();
+        let mut files: Vec<FileValidation> = stream::iter(targets)
+            .map(|(schema, path)| self.validate_file(&schemas, schema, path))
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        files.sort_by(|a, b| a.file.cmp(&b.file));
+
+        let failed_schemas = failed_schemas(&schemas);
+        let passed = failed_schemas.is_empty() && files.iter().all(|file| file.valid);
+        info!(
+            "Validated {} data file(s): {}",
+            files.len(),
+            if passed { "passed" } else { "failed" }
+        );
+
+        Ok(ValidationReport { passed, files, failed_schemas })
+    }
+
+    /// Validates the given files (relative to the data directory) against one schema
+    ///
+    /// Runs concurrently like validate_all(); results are in the order given.
+    pub async fn validate_files(&self, schema_name: &str, files: &[String]) -> ApiResult<Vec<FileValidation>> {
+        let schemas = self.schema_set().await;
+        if !schemas.compiled.contains_key(schema_name) {
+            return Err(schema_missing_error(&schemas, schema_name));
+        }
+        let paths = files
+            .iter()
+            .map(|file| resolve_within(&self.data_dir, file))
+            .collect::<ApiResult<Vec<PathBuf>>>()?;
+
+        let concurrency = self.scan_concurrency();
+        Ok(stream::iter(paths)
+            .map(|path| self.validate_file(&schemas, schema_name.to_string(), path))
+            .buffered(concurrency)
+            .collect()
+            .await)
+    }
+
+    /// Files processed at once by bulk scans
+    pub fn scan_concurrency(&self) -> usize {
+        self.config.scan_concurrency.max(1)
+    }
+
+    /// Reads, parses and validates a single file, collecting every error
+    async fn validate_file(&self, schemas: &SchemaSet, schema: String, path: PathBuf) -> FileValidation {
+        let file = path
+            .strip_prefix(&self.data_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .into_owned();
+
+        let document = match fs::read_to_string(&path).await {
+            Ok(content) => serde_yaml::from_str::<Value>(&content).map_err(|e| format!("YAML parse error: {}", e)),
+            Err(e) => Err(format!("Failed to read file: {}", e)),
+        };
+
+        let errors = match document {
+            Ok(document) => match schemas.compiled.get(&schema) {
+                Some(compiled) => match compiled.validate(&document) {
+                    Ok(()) => Vec::new(),
+                    Err(errors) => errors.map(|e| format!("{}: {}", e.instance_path, e)).collect(),
+                },
+                None => vec![format!("Schema '{}' is no longer loaded", schema)],
+            },
+            Err(error) => vec![error],
+        };
+
+        FileValidation { file, schema, valid: errors.is_empty(), errors }
+    }
+
+    /// All `.yaml`/`.yml` files under the data directory
+    async fn yaml_files(&self) -> ApiResult<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut pending = vec![self.data_dir.clone()];
+
+        while let Some(dir) = pending.pop() {
+            let mut entries = fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    pending.push(path);
+                } else if matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml")) {
+                    files.push(path);
+                }
+            }
+        }
+
+        Ok(files)
+    }
 }
 
 // ====================================================
@@ -209,21 +1287,615 @@ impl YamlService {
 
 impl YamlService {
     pub async fn list_available_schemas(&self) -> ApiResult<Vec<String>> {
-        Ok(self.schemas.keys().cloned().collect())
+        Ok(self.schema_set().await.compiled.keys().cloned().collect())
+    }
+
+    /// Form descriptor derived from a loaded schema, cached until the next reload
+    pub async fn form_schema(&self, schema_name: &str) -> ApiResult<FormSchema> {
+        if let Some(cached) = self.form_schemas.read().await.get(schema_name) {
+            return Ok(cached.clone());
+        }
+
+        let schemas = self.schema_set().await;
+        if !schemas.compiled.contains_key(schema_name) {
+            return Err(schema_missing_error(&schemas, schema_name));
+        }
+
+        let mut source = None;
+        for file_name in [format!("{}.schema.json", schema_name), format!("{}.json", schema_name)] {
+            let path = self.schema_dir.join(file_name);
+            if path.exists() {
+                source = Some(fs::read_to_string(&path).await?);
+                break;
+            }
+        }
+        let source = source.ok_or_else(|| {
+            ApiError::NotFound(format!("Schema file for '{}' not found", schema_name))
+        })?;
+        let schema: Value = serde_json::from_str(&source)
+            .map_err(|e| ApiError::ValidationError(format!("Invalid JSON schema: {}", e)))?;
+
+        let descriptor = form_schema_from(schema_name, &schema);
+        self.form_schemas
+            .write()
+            .await
+            .insert(schema_name.to_string(), descriptor.clone());
+        Ok(descriptor)
+    }
+
+    /// Source of a loaded schema exactly as read, for client-side validation
+    pub async fn raw_schema(&self, schema_name: &str) -> ApiResult<RawSchema> {
+        let schemas = self.schema_set().await;
+        match schemas.raw.get(schema_name) {
+            Some(raw) => Ok(raw.clone()),
+            None => Err(schema_missing_error(&schemas, schema_name)),
+        }
+    }
+
+    /// Validates an in-memory value against a loaded schema
+    ///
+    /// Failures are ApiError::SchemaValidation as for data files; a missing schema is 404
+    /// and one that failed to compile is 503.
+    pub async fn validate_value(&self, schema_name: &str, value: &Value) -> ApiResult<()> {
+        let schemas = self.schema_set().await;
+        let Some(schema) = schemas.compiled.get(schema_name) else {
+            return Err(schema_missing_error(&schemas, schema_name));
+        };
+        validate_document(schema, value)
+    }
+
+    /// Fails as validate_value() would when `schema_name` is not loaded and compiled
+    pub async fn require_schema(&self, schema_name: &str) -> ApiResult<()> {
+        let schemas = self.schema_set().await;
+        if schemas.compiled.contains_key(schema_name) {
+            Ok(())
+        } else {
+            Err(schema_missing_error(&schemas, schema_name))
+        }
+    }
+
+    /// Schemas that failed to compile at the last load, with their errors
+    pub async fn list_failed_schemas(&self) -> Vec<FailedSchema> {
+        failed_schemas(&*self.schema_set().await)
+    }
+
+    /// YAML files directly inside a data subdirectory, sorted by name
+    ///
+    /// A missing directory lists no files.
+    pub async fn data_files(&self, dir: &str) -> ApiResult<Vec<PathBuf>> {
+        let dir = resolve_within(&self.data_dir, dir)?;
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        let mut entries = fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_file()
+                && matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml"))
+            {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Modification time of the data file that get_yaml_data() would read
+    pub async fn data_modified(&self, schema_name: &str, file_path: Option<&str>) -> ApiResult<SystemTime> {
+        let yaml_path = self.resolve_yaml_path(schema_name, file_path)?;
+        let metadata = fs::metadata(&yaml_path).await.map_err(|_| {
+            ApiError::FileNotFound(format!("YAML file not found: {}", yaml_path.display()))
+        })?;
+        Ok(metadata.modified()?)
     }
 
     fn resolve_yaml_path(&self, schema_name: &str, file_path: Option<&str>) -> ApiResult<PathBuf> {
         match file_path {
-            Some(path) => {
-                // If a specific file path is provided, use it relative to data_dir
-                let full_path = self.data_dir.join(path);
-                Ok(full_path)
+            // If a specific file path is provided, use it relative to data_dir
+            Some(path) => resolve_within(&self.data_dir, path),
+            // Default to schema_name.yaml in the data directory
+            None => resolve_within(&self.data_dir, &format!("{}.yaml", schema_name)),
+        }
+    }
+}
+
+/// Builds a form descriptor from a JSON schema document
+fn form_schema_from(schema_name: &str, schema: &Value) -> FormSchema {
+    let root = form_field("", schema, false);
+    FormSchema {
+        schema: schema_name.to_string(),
+        title: schema.get("title").and_then(Value::as_str).map(str::to_string),
+        field_type: root.field_type,
+        item_type: root.item_type,
+        fields: root.fields,
+    }
+}
+
+/// Describes one schema node; objects and arrays of objects recurse into their properties
+fn form_field(name: &str, schema: &Value, required: bool) -> FormField {
+    let field_type = schema_type(schema);
+    let mut item_type = None;
+    let fields = match field_type.as_str() {
+        "object" => object_fields(schema),
+        "array" => match schema.get("items") {
+            Some(items) => {
+                let items_type = schema_type(items);
+                let fields = if items_type == "object" { object_fields(items) } else { Vec::new() };
+                item_type = Some(items_type);
+                fields
             }
-            None => {
-                // Default to schema_name.yaml in the data directory
-                let default_file = format!("{}.yaml", schema_name);
-                Ok(self.data_dir.join(default_file))
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+
+    FormField {
+        name: name.to_string(),
+        field_type,
+        required,
+        description: schema.get("description").and_then(Value::as_str).map(str::to_string),
+        options: schema.get("enum").and_then(Value::as_array).cloned(),
+        item_type,
+        fields,
+    }
+}
+
+/// Fields for each entry of an object schema's `properties`
+fn object_fields(schema: &Value) -> Vec<FormField> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, property)| form_field(name, property, required.contains(&name.as_str())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The schema's `type`, joining union types with `|`; untyped enums are strings
+fn schema_type(schema: &Value) -> String {
+    match schema.get("type") {
+        Some(Value::String(name)) => name.clone(),
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("|"),
+        _ if schema.get("enum").is_some() => "string".to_string(),
+        _ if schema.get("properties").is_some() => "object".to_string(),
+        _ => "any".to_string(),
+    }
+}
+
+/// Fails with the compile error when the schema exists but is broken
+///
+/// Data for a broken schema must not be served or written unvalidated.
+fn ensure_schema_usable(schemas: &SchemaSet, schema_name: &str) -> ApiResult<()> {
+    match schemas.failed.get(schema_name) {
+        Some(failed) => Err(broken_schema_error(failed)),
+        None => Ok(()),
+    }
+}
+
+/// Adds the `.json` files of a directory to `files`, keyed by file name
+async fn list_schema_files(dir: &Path, files: &mut BTreeMap<String, (PathBuf, u64)>) -> ApiResult<()> {
+    let mut entries = fs::read_dir(dir).await.map_err(ApiError::IoError)?;
+    while let Some(entry) = entries.next_entry().await.map_err(ApiError::IoError)? {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            let size = entry.metadata().await.map_err(ApiError::IoError)?.len();
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                files.insert(name.to_string(), (path.clone(), size));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Error for a schema that is not loaded: broken (503) or absent (404)
+fn schema_missing_error(schemas: &SchemaSet, schema_name: &str) -> ApiError {
+    match schemas.failed.get(schema_name) {
+        Some(failed) => broken_schema_error(failed),
+        None => ApiError::NotFound(format!("Schema '{}' not found", schema_name)),
+    }
+}
+
+/// Failed schemas of a set, sorted by name
+fn failed_schemas(schemas: &SchemaSet) -> Vec<FailedSchema> {
+    let mut failed: Vec<FailedSchema> = schemas.failed.values().cloned().collect();
+    failed.sort_by(|a, b| a.name.cmp(&b.name));
+    failed
+}
+
+fn broken_schema_error(failed: &FailedSchema) -> ApiError {
+    ApiError::SchemaUnavailable(format!(
+        "Schema '{}' ({}) failed to compile: {}",
+        failed.name, failed.file, failed.error
+    ))
+}
+
+/// Joins a user-influenced relative path (e.g. `sidebars/{id}.yaml`) onto `base`.
+///
+/// Absolute paths and `..` components are rejected outright. The deepest existing
+/// ancestor of the result is then canonicalized and must still lie inside `base`,
+/// so a symlink cannot lead out of the directory either.
+fn resolve_within(base: &Path, relative: &str) -> ApiResult<PathBuf> {
+    let escapes = || ApiError::BadRequest(format!("Path '{}' is outside the data directory", relative));
+
+    let relative_path = Path::new(relative);
+    if relative.is_empty()
+        || relative_path
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(escapes());
+    }
+
+    let joined = base.join(relative_path);
+
+    let canonical_base = base.canonicalize()?;
+    let mut existing = joined.as_path();
+    while !existing.exists() {
+        existing = existing.parent().ok_or_else(escapes)?;
+    }
+    if !existing.canonicalize()?.starts_with(&canonical_base) {
+        warn!("Rejected path '{}' resolving outside {}", relative, base.display());
+        return Err(escapes());
+    }
+
+    Ok(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yaml-service-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("sidebars")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn rejects_parent_traversal() {
+        let dir = data_dir();
+        assert!(matches!(resolve_within(&dir, "../secret.yaml"), Err(ApiError::BadRequest(_))));
+        assert!(matches!(resolve_within(&dir, "sidebars/../../secret.yaml"), Err(ApiError::BadRequest(_))));
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        let dir = data_dir();
+        assert!(matches!(resolve_within(&dir, "/etc/passwd"), Err(ApiError::BadRequest(_))));
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed() {
+        let old = serde_json::json!({ "a": 1, "b": { "c": [1, 2] }, "gone": true });
+        let new = serde_json::json!({ "a": 2, "b": { "c": [1] }, "new/key": "x" });
+
+        let diff = diff_values(&old, &new);
+        let summary: Vec<(&str, DiffOp)> = diff.iter().map(|d| (d.path.as_str(), d.op)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/a", DiffOp::Changed),
+                ("/b/c/1", DiffOp::Removed),
+                ("/gone", DiffOp::Removed),
+                ("/new~1key", DiffOp::Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_of_identical_documents_is_empty() {
+        let doc = serde_json::json!({ "a": [1, { "b": null }] });
+        assert!(diff_values(&doc, &doc).is_empty());
+    }
+
+    #[test]
+    fn form_schema_describes_array_item_fields() {
+        let schema = serde_json::json!({
+            "title": "Nav",
+            "type": "array",
+            "items": {
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "kind": { "enum": ["link", "group"] },
+                    "children": { "type": "array", "items": { "type": "string" } }
+                }
             }
+        });
+
+        let form = form_schema_from("nav", &schema);
+        assert_eq!(form.field_type, "array");
+        assert_eq!(form.item_type.as_deref(), Some("object"));
+
+        let by_name = |name: &str| form.fields.iter().find(|f| f.name == name).unwrap();
+        assert!(by_name("id").required);
+        assert_eq!(by_name("kind").field_type, "string");
+        assert_eq!(by_name("kind").options.as_ref().map(Vec::len), Some(2));
+        assert_eq!(by_name("children").item_type.as_deref(), Some("string"));
+    }
+
+    #[test]
+    fn accepts_nested_names() {
+        let dir = data_dir();
+        assert_eq!(
+            resolve_within(&dir, "sidebars/main.yaml").unwrap(),
+            dir.join("sidebars/main.yaml")
+        );
+        assert_eq!(
+            resolve_within(&dir, "inventories/inventory.yaml").unwrap(),
+            dir.join("inventories/inventory.yaml")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn reads_during_reload_see_a_complete_schema_set() {
+        let schema_dir = data_dir();
+        let data = data_dir();
+        for name in ["a", "b", "c", "d"] {
+            let schema = serde_json::json!({ "type": "object", "required": ["id"] });
+            std::fs::write(schema_dir.join(format!("{}.schema.json", name)), schema.to_string()).unwrap();
+            std::fs::write(data.join(format!("{}.yaml", name)), "id: 1\n").unwrap();
+        }
+        let service = Arc::new(
+            YamlService::new(schema_dir.to_str().unwrap(), data.to_str().unwrap(), None).await.unwrap(),
+        );
+
+        let reloader = {
+            let service = Arc::clone(&service);
+            tokio::spawn(async move {
+                for _ in 0..50 {
+                    service.reload_schemas().await.unwrap();
+                }
+            })
+        };
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                let service = Arc::clone(&service);
+                tokio::spawn(async move {
+                    for _ in 0..50 {
+                        assert_eq!(service.list_available_schemas().await.unwrap().len(), 4);
+                        service.get_yaml_data("c", None).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        reloader.await.unwrap();
+        for reader in readers {
+            reader.await.unwrap();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn single_flight_locks_are_dropped_after_loading() {
+        let schema_dir = data_dir();
+        let data = data_dir();
+        let schema = serde_json::json!({ "type": "object", "required": ["id"] });
+        std::fs::write(schema_dir.join("item.schema.json"), schema.to_string()).unwrap();
+        std::fs::write(data.join("item.yaml"), "id: 1\n").unwrap();
+        std::fs::write(data.join("broken.yaml"), "id: [\n").unwrap();
+        let service = Arc::new(
+            YamlService::new(schema_dir.to_str().unwrap(), data.to_str().unwrap(), None).await.unwrap(),
+        );
+
+        let loads: Vec<_> = (0..8)
+            .map(|i| {
+                let service = Arc::clone(&service);
+                tokio::spawn(async move {
+                    let file = if i % 2 == 0 { "item.yaml" } else { "broken.yaml" };
+                    service.get_yaml_data("item", Some(file)).await.is_ok()
+                })
+            })
+            .collect();
+        for (i, load) in loads.into_iter().enumerate() {
+            assert_eq!(load.await.unwrap(), i % 2 == 0);
+        }
+        assert!(service.loads.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn shipped_settings_navigation_is_valid_and_typed() {
+        let shared = Path::new(env!("CARGO_MANIFEST_DIR")).join("../shared");
+        let service = YamlService::new(
+            shared.join("schemas").to_str().unwrap(),
+            shared.join("data").to_str().unwrap(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let nav: crate::models::NavigationConfig =
+            service.get_typed_data("settingsSidebarNavigation", None).await.unwrap();
+        assert!(!nav.items.is_empty());
+        assert!(nav.items.iter().all(|item| item.children.as_ref().is_some_and(|c| !c.is_empty())));
+    }
+
+    #[tokio::test]
+    async fn validate_all_scans_hundreds_of_files() {
+        let schema_dir = data_dir();
+        let data = data_dir();
+        let schema = serde_json::json!({ "type": "object", "required": ["id"] });
+        std::fs::write(schema_dir.join("item.schema.json"), schema.to_string()).unwrap();
+        // Data files belong to a schema by file stem, so each one gets its own directory
+        for i in 0..300 {
+            let dir = data.join(format!("nested/{}", i));
+            std::fs::create_dir_all(&dir).unwrap();
+            let content = if i == 123 { "name: missing-id\n".to_string() } else { format!("id: {}\n", i) };
+            std::fs::write(dir.join("item.yaml"), content).unwrap();
+        }
+        let config = YamlServiceConfig { scan_concurrency: 4, ..YamlServiceConfig::default() };
+        let service =
+            YamlService::new(schema_dir.to_str().unwrap(), data.to_str().unwrap(), Some(config)).await.unwrap();
+
+        let report = service.validate_all().await.unwrap();
+        assert_eq!(report.files.len(), 300);
+        assert!(!report.passed);
+        let invalid: Vec<_> = report.files.iter().filter(|f| !f.valid).map(|f| f.file.as_str()).collect();
+        assert_eq!(invalid, ["nested/123/item.yaml"]);
+    }
+
+    #[tokio::test]
+    async fn listed_files_are_validated_in_the_order_given() {
+        let dir = tempfile::tempdir().unwrap();
+        let (schemas, data) = (dir.path().join("schemas"), dir.path().join("data"));
+        std::fs::create_dir_all(&schemas).unwrap();
+        std::fs::create_dir_all(data.join("inventories")).unwrap();
+        let schema = serde_json::json!({ "type": "object", "required": ["locations"] });
+        std::fs::write(schemas.join("inventory.schema.json"), schema.to_string()).unwrap();
+        std::fs::write(data.join("inventories/lab.yaml"), "locations: {}\n").unwrap();
+        std::fs::write(data.join("inventories/dc.yaml"), "devices: []\n").unwrap();
+        let service = YamlService::new(schemas.to_str().unwrap(), data.to_str().unwrap(), None).await.unwrap();
+
+        let files = ["inventories/lab.yaml".to_string(), "inventories/dc.yaml".to_string()];
+        let results = service.validate_files("inventory", &files).await.unwrap();
+        let summary: Vec<_> = results.iter().map(|r| (r.file.as_str(), r.valid)).collect();
+        assert_eq!(summary, [("inventories/lab.yaml", true), ("inventories/dc.yaml", false)]);
+        assert!(!results[1].errors.is_empty());
+
+        assert!(matches!(service.validate_files("sites", &files).await, Err(ApiError::NotFound(_))));
+        let escape = ["../outside.yaml".to_string()];
+        assert!(service.validate_files("inventory", &escape).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn schemas_past_the_count_or_size_limit_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let (schemas, data) = (dir.path().join("schemas"), dir.path().join("data"));
+        std::fs::create_dir_all(&schemas).unwrap();
+        std::fs::create_dir_all(&data).unwrap();
+        // Loaded in name order: "big" is over the size limit, "d" comes after two have loaded
+        for name in ["a", "c", "d"] {
+            std::fs::write(schemas.join(format!("{}.schema.json", name)), r#"{ "type": "object" }"#).unwrap();
         }
+        let padding = " ".repeat(200);
+        std::fs::write(schemas.join("big.schema.json"), format!(r#"{{ "type": "object" }}{}"#, padding)).unwrap();
+        let config = YamlServiceConfig { max_schema_count: 2, max_schema_size: 100, ..YamlServiceConfig::default() };
+        let service =
+            YamlService::new(schemas.to_str().unwrap(), data.to_str().unwrap(), Some(config)).await.unwrap();
+
+        let report = service.reload_schemas().await.unwrap();
+        assert_eq!(report.loaded, ["a", "c"]);
+        let skipped: Vec<_> = report.skipped.iter().map(|s| (s.file.as_str(), s.reason.as_str())).collect();
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped[0].0.ends_with("big.schema.json") && skipped[0].1.contains("limit of 100 bytes"), "{:?}", skipped);
+        assert!(skipped[1].0.ends_with("d.schema.json") && skipped[1].1.contains("count limit of 2"), "{:?}", skipped);
+        assert_eq!(service.list_available_schemas().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn remote_schemas_override_local_and_survive_an_outage() {
+        use axum::{routing::get, Router};
+
+        let app = Router::new()
+            .route("/schemas/index.json", get(|| async { r#"["item.schema.json"]"# }))
+            .route(
+                "/schemas/item.schema.json",
+                get(|| async { r#"{ "type": "object", "required": ["remote_id"] }"# }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let schema_dir = data_dir();
+        let data = data_dir();
+        std::fs::write(schema_dir.join("item.schema.json"), r#"{ "type": "object", "required": ["id"] }"#).unwrap();
+        std::fs::write(data.join("item.yaml"), "remote_id: 1\n").unwrap();
+        let config = YamlServiceConfig {
+            remote_schema_url: Some(format!("http://{}/schemas", addr)),
+            ..YamlServiceConfig::default()
+        };
+        let service =
+            YamlService::new(schema_dir.to_str().unwrap(), data.to_str().unwrap(), Some(config)).await.unwrap();
+        service.get_yaml_data("item", None).await.unwrap();
+
+        // Unreachable: the cached remote copy is still used
+        server.abort();
+        let _ = server.await;
+        let report = service.reload_schemas().await.unwrap();
+        assert!(report.remote.unwrap().error.is_some());
+        service.get_yaml_data("item", None).await.unwrap();
+    }
+
+    #[test]
+    fn inventory_issues_name_the_failing_device() {
+        let schema = JSONSchema::compile(&serde_json::json!({
+            "type": "object",
+            "properties": {
+                "locations": { "additionalProperties": { "additionalProperties": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["host_name", "ip_address"],
+                        "properties": { "ip_address": { "type": "string" } }
+                    }
+                } } }
+            }
+        }))
+        .unwrap();
+        let full = serde_json::json!({ "locations": { "LAB": { "routers": [
+            { "host_name": "r1", "ip_address": "10.0.0.1" },
+            { "host_name": "r2", "ip_address": 42 },
+            { "host_name": "r3" }
+        ] } } });
+
+        let Err(ApiError::SchemaValidation(issues)) = validate_document(&schema, &full) else {
+            panic!("expected schema validation issues");
+        };
+        let located: Vec<_> = issues
+            .iter()
+            .map(|i| (i.device_index, i.device_hostname.as_deref(), i.field.as_deref()))
+            .collect();
+        assert!(located.contains(&(Some(1), Some("r2"), Some("ip_address"))));
+        assert!(located.contains(&(Some(2), Some("r3"), Some("ip_address"))));
+        assert!(issues.iter().all(|i| i.location.as_deref() == Some("LAB")));
+
+        // Incremental validation only sees the changed device but reports its real index
+        let partial = serde_json::json!({ "locations": { "LAB": { "routers": [
+            { "host_name": "r2", "ip_address": 42 }
+        ] } } });
+        let Err(ApiError::SchemaValidation(issues)) = validate_partial(&schema, &partial, &full) else {
+            panic!("expected schema validation issues");
+        };
+        assert_eq!(issues[0].device_index, Some(1));
+        assert_eq!(issues[0].path, "/locations/LAB/routers/1/ip_address");
+    }
+
+    #[test]
+    fn entries_are_patched_in_place_or_not_at_all() {
+        let patch = |source: &str, patch: Value| {
+            let mut data: Value = serde_yaml::from_str(source).unwrap();
+            let patch = patch.as_object().unwrap().clone();
+            for (name, value) in &patch {
+                match value {
+                    Value::Null => data["a"].as_object_mut().unwrap().remove(name),
+                    _ => data["a"].as_object_mut().unwrap().insert(name.clone(), value.clone()),
+                };
+            }
+            patch_entry_source(source, "a", &patch, &data)
+        };
+
+        let block = "a:\n  # kept\n  x: 1\n  y: [1, 2]\nb:\n  x: 2\n";
+        assert_eq!(
+            patch(block, serde_json::json!({ "y": null, "z": "new" })).as_deref(),
+            Some("a:\n  # kept\n  x: 1\n  z: new\nb:\n  x: 2\n")
+        );
+        // A flow-style entry cannot be edited line by line
+        assert_eq!(patch("a: { x: 1 }\n", serde_json::json!({ "x": 2 })), None);
     }
 }
@@ -0,0 +1,102 @@
+// File Path: src/test_support.rs
+// Version: 1.1.1
+// Description: Test fixture building an AppState over temporary schema and data directories,
+// so handlers can be called directly in tests without Docker or the Python API.
+//
+// Usage Guide:
+// ```
+// let app = TestApp::new().await;
+// app.write_data("reports.yaml", "bgp: { ... }").await;
+// let response = get_report_run(Path((id, execution_id)), State(app.state.clone())).await;
+// ```
+// Schemas written with `write_schema` are compiled by the following `reload_schemas` call.
+// `stub_python_api` serves a router on a local port and points `python_api_url` at it.
+//
+// Change Log:
+// - 1.1.1: The temporary directory is removed when the TestApp is dropped
+// - 1.1.0: Added stub_python_api
+// - 1.0.0: Initial implementation
+
+use std::{path::PathBuf, sync::Arc};
+use tempfile::TempDir;
+
+use crate::{
+    middleware::auth::{ApiKeyAuthenticator, Authenticator},
+    models::PaginationConfig,
+    services::{
+        credentials_service::CredentialDefaults, device_list_service::DeviceListBreakerConfig, BackupPool, CredentialsService, DeviceListService,
+        DeviceLockService, InventoryAuditService, JobService, PythonRunnerService, RouteMetricsService,
+        TaskHealthService, WebSocketService, WebhookService, YamlService,
+    },
+    AppState,
+};
+
+/// An AppState whose YAML service reads a fresh temporary directory, removed on drop
+pub(crate) struct TestApp {
+    pub state: AppState,
+    pub root: PathBuf,
+    _dir: TempDir,
+}
+
+impl TestApp {
+    pub async fn new() -> Self {
+        let dir = tempfile::Builder::new().prefix("xaos-test-").tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::create_dir_all(root.join("schemas")).unwrap();
+        std::fs::create_dir_all(root.join("data")).unwrap();
+
+        let yaml_service = Arc::new(
+            YamlService::new(
+                root.join("schemas").to_str().unwrap(),
+                root.join("data").to_str().unwrap(),
+                None,
+            )
+            .await
+            .unwrap(),
+        );
+        let websocket_service = Arc::new(WebSocketService::new(None, Arc::new(WebhookService::new(None))));
+        let python_runner_service = Arc::new(PythonRunnerService::new(websocket_service.clone(), None).await.unwrap());
+        let authenticator: Arc<dyn Authenticator> = Arc::new(ApiKeyAuthenticator::default());
+        let credentials_service = Arc::new(CredentialsService::new(yaml_service.clone(), CredentialDefaults::default()));
+        let python_api_url = "http://python_runner:8000".to_string();
+
+        let state = AppState {
+            yaml_service,
+            websocket_service,
+            python_runner_service,
+            device_lock_service: Arc::new(DeviceLockService::new()),
+            job_service: Arc::new(JobService::new()),
+            route_metrics_service: Arc::new(RouteMetricsService::new()),
+            backup_pool: Arc::new(BackupPool::new(2)),
+            device_list_service: Arc::new(DeviceListService::new(DeviceListBreakerConfig::from_env(&python_api_url))),
+            authenticator,
+            pagination: PaginationConfig::default(),
+            task_health: Arc::new(TaskHealthService::new()),
+            credentials_service,
+            inventory_audit: Arc::new(InventoryAuditService::new(root.join("logs/inventory_audit.jsonl"))),
+            python_api_url,
+        };
+        Self { state, root, _dir: dir }
+    }
+
+    /// Writes a file relative to the data directory
+    pub async fn write_data(&self, path: &str, content: &str) {
+        let path = self.root.join("data").join(path);
+        tokio::fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        tokio::fs::write(path, content).await.unwrap();
+    }
+
+    /// Writes `<name>.schema.json` and recompiles the schemas
+    pub async fn write_schema(&self, name: &str, schema: &serde_json::Value) {
+        let path = self.root.join("schemas").join(format!("{}.schema.json", name));
+        tokio::fs::write(path, schema.to_string()).await.unwrap();
+        self.state.yaml_service.reload_schemas().await.unwrap();
+    }
+
+    /// Serves `routes` in place of the Python API for this app's handlers
+    pub async fn stub_python_api(&mut self, routes: axum::Router) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        self.state.python_api_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, routes).await });
+    }
+}
@@ -1,11 +1,13 @@
 # =================================================================================================
 # FILE: api.py (FastAPI Endpoint)
-# VERSION: 2.0.6 - FIXED RESPONSE STATUS + ENHANCED DEBUGGING + CORRECTED PORT
+# VERSION: 2.0.7 - FIXED RESPONSE STATUS + ENHANCED DEBUGGING + CORRECTED PORT
 # OVERVIEW:
 # A FastAPI application that exposes API endpoints for backup and restore operations.
 # It acts as a bridge between the Rust backend and the Python worker scripts.
 # Updated to include WebSocket integration for real-time job progress updates.
 # FIXED: Corrected port configuration and enhanced debugging for frontend integration
+# Job events echo the `trace_id` sent with the backup or restore request; the worker script
+# receives it as XAOS_TRACE_ID.
 # =================================================================================================
 
 import json
@@ -206,11 +208,14 @@ async def send_job_progress(
     await forward_to_rust_websocket(event)
 
 
-async def read_process_output(process, job_id: str, device: str, job_type: str):
+async def read_process_output(
+    process, job_id: str, device: str, job_type: str, trace_id: Optional[str] = None
+):
     """
     Read process output line by line and send progress updates to both WS systems.
     This function captures both stdout and stderr, logs each line, saves to a debug file, and forwards events.
     Enhanced with better error handling and debugging.
+    `trace_id` is the trace id sent with the request that started the job, if any.
     """
     output = ""
     output_lines = []
@@ -264,6 +269,9 @@ async def read_process_output(process, job_id: str, device: str, job_type: str):
                     "status": "in_progress",
                     "data": progress_data,
                     "error": None,
+                    # Echo the request's trace id so Rust links the event to its run; this server
+                    # handles many jobs, so its own environment never carries a job's trace id
+                    "trace_id": progress_data.get("trace_id") or trace_id,
                 }
                 logger.info(f"📨 [json progress] Forwarding to Rust: {rust_event}")
                 await forward_to_rust_websocket(rust_event)
@@ -339,6 +347,8 @@ class BackupRequest(BaseModel):
     inventory_file: Optional[str] = None
     username: str
     password: str
+    # Execution trace id echoed on this job's events
+    trace_id: Optional[str] = None
 
 
 class RestoreRequest(BaseModel):
@@ -349,6 +359,17 @@ class RestoreRequest(BaseModel):
     restore_type: str = "override"
     confirmed_commit_timeout: int = 0
     commit_timeout: int = 300
+    # Execution trace id echoed on this job's events
+    trace_id: Optional[str] = None
+
+
+def job_env(trace_id: Optional[str]) -> Dict[str, str]:
+    """Environment for a job's worker script, carrying the request's trace id as XAOS_TRACE_ID."""
+    env = dict(os.environ)
+    env.pop("XAOS_TRACE_ID", None)
+    if trace_id:
+        env["XAOS_TRACE_ID"] = trace_id
+    return env
 
 
 # =================================================================================================
@@ -529,6 +550,7 @@ async def execute_backup_with_websocket(
             stderr=subprocess.STDOUT,
             text=False,  # Set to False to handle bytes correctly
             bufsize=1,  # Line buffered
+            env=job_env(request.trace_id),
         )
 
         logger.info(
@@ -536,7 +558,9 @@ async def execute_backup_with_websocket(
         )
 
         # Read output in real-time and forward to both WebSocket systems
-        output = await read_process_output(process, job_id, device, "backup")
+        output = await read_process_output(
+            process, job_id, device, "backup", request.trace_id
+        )
 
         # Wait for process to complete
         return_code = await asyncio.to_thread(process.wait)
@@ -770,10 +794,13 @@ async def restore_device(request: RestoreRequest):
             stdout=subprocess.PIPE,
             stderr=subprocess.STDOUT,
             text=False,  # Set to False
+            env=job_env(request.trace_id),
         )
 
         # Read output in real-time and forward to both WebSocket systems
-        output = await read_process_output(process, job_id, request.hostname, "restore")
+        output = await read_process_output(
+            process, job_id, request.hostname, "restore", request.trace_id
+        )
 
         # Wait for process to complete
         return_code = await asyncio.to_thread(process.wait)
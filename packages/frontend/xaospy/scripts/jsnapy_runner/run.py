@@ -51,6 +51,7 @@
 # SECTION 1: IMPORTS & INITIAL SETUP
 # ====================================================================================
 import argparse
+import os
 import sys
 import json
 import asyncio
@@ -92,6 +93,8 @@ def run_single_test(device, test_definition):
     rpc_to_call_name = test_definition['rpc'].replace('-', '_')
     rpc_to_call = getattr(device.rpc, rpc_to_call_name)
     rpc_args = test_definition.get('rpc_args', {})
+    # Run-time overrides, already validated by the backend's report-run endpoint
+    rpc_args = {**rpc_args, **json.loads(os.environ.get('REPORT_RPC_ARGS', '{}'))}
     xml_data = rpc_to_call(**rpc_args)
 
     table_data = []